@@ -0,0 +1,25 @@
+use std::process::Command;
+
+// Embeds the git commit in the binary for the /version endpoint. Builds without a git checkout,
+// like the Docker image, can pass it in through the GIT_SHA environment variable instead
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs");
+    println!("cargo:rerun-if-changed=../.git/packed-refs");
+
+    let git_sha = std::env::var("GIT_SHA").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|sha| sha.trim().to_string())
+    });
+
+    println!(
+        "cargo:rustc-env=GIT_SHA={}",
+        git_sha.unwrap_or("unknown".to_string())
+    );
+}
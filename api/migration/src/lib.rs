@@ -1,12 +1,62 @@
+// Iden variants mirror the table, column and enum value names in the database
+#![allow(clippy::enum_variant_names, clippy::upper_case_acronyms)]
+
 pub use sea_orm_migration::prelude::*;
 
+mod m20241221_030000_create_postgres_enum_types;
+mod m20241221_031752_create_postgres_tables;
 mod m20241221_031752_create_tables;
+mod m20250106_214510_create_login_security_tables;
+mod m20250112_180233_create_rate_limit_table;
+mod m20250119_093041_create_two_factor_tables;
+mod m20250125_152207_create_signing_key_table;
+mod m20250201_101530_add_indexes_and_constraints;
+mod m20250208_143012_create_user_email_index;
+mod m20250215_094512_add_session_lifecycle;
+mod m20250222_101204_add_execution_scores;
+mod m20250301_163845_add_flight_and_displacement;
+mod m20250308_112730_create_sync_pair_tables;
+mod m20250315_090415_create_personal_best_tables;
+mod m20250322_104620_create_catalogue_skill_table;
+mod m20250329_141855_create_comment_tables;
+mod m20250405_083015_create_plan_tables;
+mod m20250412_164205_create_goal_table;
+mod m20250419_102311_create_used_token_table;
+mod m20250426_091530_clear_untariffed_difficulty;
+mod m20250503_103015_create_training_group_tables;
+mod m20250510_091245_leave_unjudged_execution_unscored;
+mod m20250517_103420_rank_judged_score_bests;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20241221_031752_create_tables::Migration)]
+        vec![
+            // Added later, but the enum types it creates on Postgres are needed by the first tables
+            Box::new(m20241221_030000_create_postgres_enum_types::Migration),
+            // Runs m20241221_031752_create_tables, or its Postgres equivalent
+            Box::new(m20241221_031752_create_postgres_tables::Migration),
+            Box::new(m20250106_214510_create_login_security_tables::Migration),
+            Box::new(m20250112_180233_create_rate_limit_table::Migration),
+            Box::new(m20250119_093041_create_two_factor_tables::Migration),
+            Box::new(m20250125_152207_create_signing_key_table::Migration),
+            Box::new(m20250201_101530_add_indexes_and_constraints::Migration),
+            Box::new(m20250208_143012_create_user_email_index::Migration),
+            Box::new(m20250215_094512_add_session_lifecycle::Migration),
+            Box::new(m20250222_101204_add_execution_scores::Migration),
+            Box::new(m20250301_163845_add_flight_and_displacement::Migration),
+            Box::new(m20250308_112730_create_sync_pair_tables::Migration),
+            Box::new(m20250315_090415_create_personal_best_tables::Migration),
+            Box::new(m20250322_104620_create_catalogue_skill_table::Migration),
+            Box::new(m20250329_141855_create_comment_tables::Migration),
+            Box::new(m20250405_083015_create_plan_tables::Migration),
+            Box::new(m20250412_164205_create_goal_table::Migration),
+            Box::new(m20250419_102311_create_used_token_table::Migration),
+            Box::new(m20250426_091530_clear_untariffed_difficulty::Migration),
+            Box::new(m20250503_103015_create_training_group_tables::Migration),
+            Box::new(m20250510_091245_leave_unjudged_execution_unscored::Migration),
+            Box::new(m20250517_103420_rank_judged_score_bests::Migration),
+        ]
     }
 }
@@ -0,0 +1,165 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend, Statement};
+use sea_orm_migration::sea_query::extension::postgres::Type;
+
+pub struct Migration;
+
+// Postgres needs native enum types before the first tables can use them, so this runs ahead of
+// them. SQLite stores enums as text and needs nothing. Databases set up while these types were
+// created by the table migrations already have them
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        let types = [
+            (
+                UserType::Table.to_string(),
+                Type::create()
+                    .as_enum(UserType::Table)
+                    .values([UserType::Coach, UserType::Athlete])
+                    .to_owned(),
+            ),
+            (
+                Event::Table.to_string(),
+                Type::create()
+                    .as_enum(Event::Table)
+                    .values([Event::TRA, Event::DMT, Event::TUM])
+                    .to_owned(),
+            ),
+            (
+                Direction::Table.to_string(),
+                Type::create()
+                    .as_enum(Direction::Table)
+                    .values([Direction::FORWARD, Direction::BACKWARD, Direction::NONE])
+                    .to_owned(),
+            ),
+            (
+                Position::Table.to_string(),
+                Type::create()
+                    .as_enum(Position::Table)
+                    .values([
+                        Position::TUCK,
+                        Position::PIKE,
+                        Position::STRAIGHT,
+                        Position::SPLIT,
+                        Position::NONE,
+                    ])
+                    .to_owned(),
+            ),
+            (
+                LockoutScope::Table.to_string(),
+                Type::create()
+                    .as_enum(LockoutScope::Table)
+                    .values([LockoutScope::ACCOUNT, LockoutScope::IP])
+                    .to_owned(),
+            ),
+        ];
+
+        for (name, enum_type) in types {
+            if !type_exists(manager, &name).await? {
+                manager.create_type(enum_type).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .names([
+                        UserType::Table.into_iden(),
+                        Event::Table.into_iden(),
+                        Direction::Table.into_iden(),
+                        Position::Table.into_iden(),
+                        LockoutScope::Table.into_iden(),
+                    ])
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+async fn type_exists(manager: &SchemaManager<'_>, name: &str) -> Result<bool, DbErr> {
+    let row = manager
+        .get_connection()
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT 1 FROM pg_type WHERE typname = $1 AND typnamespace = current_schema()::regnamespace",
+            [name.into()],
+        ))
+        .await?;
+
+    Ok(row.is_some())
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20241221_030000_create_postgres_enum_types"
+    }
+}
+
+// Enum values are named after what is stored in the column
+#[derive(DeriveIden)]
+enum UserType {
+    Table,
+    #[sea_orm(iden = "C")]
+    Coach,
+    #[sea_orm(iden = "A")]
+    Athlete,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    #[sea_orm(iden = "TRA")]
+    TRA,
+    #[sea_orm(iden = "DMT")]
+    DMT,
+    #[sea_orm(iden = "TUM")]
+    TUM,
+}
+
+#[derive(DeriveIden)]
+enum Direction {
+    Table,
+    #[sea_orm(iden = "FORWARD")]
+    FORWARD,
+    #[sea_orm(iden = "BACKWARD")]
+    BACKWARD,
+    #[sea_orm(iden = "NONE")]
+    NONE,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    #[sea_orm(iden = "skill_position")]
+    Table,
+    #[sea_orm(iden = "TUCK")]
+    TUCK,
+    #[sea_orm(iden = "PIKE")]
+    PIKE,
+    #[sea_orm(iden = "STRAIGHT")]
+    STRAIGHT,
+    #[sea_orm(iden = "SPLIT")]
+    SPLIT,
+    #[sea_orm(iden = "NONE")]
+    NONE,
+}
+
+#[derive(DeriveIden)]
+enum LockoutScope {
+    Table,
+    #[sea_orm(iden = "ACCOUNT")]
+    ACCOUNT,
+    #[sea_orm(iden = "IP")]
+    IP,
+}
@@ -0,0 +1,313 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+use crate::m20241221_031752_create_tables;
+
+// Runs in place of m20241221_031752_create_tables, under its name so the migration history is
+// the same either way. That migration gives the skill shape column a POSITION type, which Postgres
+// can't parse unquoted in a column definition, so Postgres gets the same tables from here with the
+// type named skill_position. SQLite stores enums as text and runs the original as it was
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return m20241221_031752_create_tables::Migration.up(manager).await;
+        }
+
+        create_user_table(manager).await?;
+        create_club_table(manager).await?;
+        create_club_member_table(manager).await?;
+        create_session_table(manager).await?;
+        create_turn_table(manager).await?;
+        create_skill_table(manager).await
+    }
+
+    // Dropping the tables doesn't name any types
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        m20241221_031752_create_tables::Migration
+            .down(manager)
+            .await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        m20241221_031752_create_tables::Migration.name()
+    }
+}
+
+async fn create_user_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(User::Table)
+                .if_not_exists()
+                .col(pk_auto(User::UserId))
+                .col(string(User::NameFirst))
+                .col(string(User::NameLast))
+                .col(string(User::Email))
+                .col(string(User::Password))
+                .col(
+                    ColumnDef::new(User::UserType)
+                        .enumeration(UserType::Table, vec![UserType::Coach, UserType::Athlete])
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_club_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Club::Table)
+                .if_not_exists()
+                .col(pk_auto(Club::ClubId))
+                .col(string(Club::Name))
+                .col(integer(Club::OwnerId))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-club-user_id")
+                        .from(Club::Table, Club::OwnerId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_club_member_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(ClubMember::Table)
+                .if_not_exists()
+                .col(pk_auto(ClubMember::ClubMemberId))
+                .col(integer(ClubMember::UserId))
+                .col(integer(ClubMember::ClubId))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-club_member-user_id")
+                        .from(ClubMember::Table, ClubMember::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-club_member-club_id")
+                        .from(ClubMember::Table, ClubMember::ClubId)
+                        .to(Club::Table, Club::ClubId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_session_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Session::Table)
+                .if_not_exists()
+                .col(pk_auto(Session::SessionId))
+                .col(integer(Session::UserId))
+                .col(
+                    ColumnDef::new(Session::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(date_time(Session::TimeStart))
+                .col(string(Session::Summary))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-turn-user_id")
+                        .from(Session::Table, Session::UserId)
+                        .to(User::Table, User::UserId),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_turn_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Turn::Table)
+                .if_not_exists()
+                .col(pk_auto(Turn::TurnId))
+                .col(integer(Turn::SessionId))
+                .col(integer(Turn::UserId))
+                .col(string(Turn::Note))
+                .col(
+                    ColumnDef::new(Turn::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(float(Turn::TotalDifficulty))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-turn-session_id")
+                        .from(Turn::Table, Turn::SessionId)
+                        .to(Session::Table, Session::SessionId),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-turn-user_id")
+                        .from(Turn::Table, Turn::UserId)
+                        .to(User::Table, User::UserId),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_skill_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Skill::Table)
+                .if_not_exists()
+                .col(pk_auto(Skill::SkillId))
+                .col(integer(Skill::TurnId))
+                .col(
+                    ColumnDef::new(Skill::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(integer(Skill::SkillNum))
+                .col(integer(Skill::FigRep))
+                .col(
+                    ColumnDef::new(Skill::Direction)
+                        .enumeration(
+                            Direction::Table,
+                            vec![Direction::FORWARD, Direction::BACKWARD, Direction::NONE],
+                        )
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Skill::Position)
+                        .enumeration(
+                            Position::Table,
+                            vec![
+                                Position::TUCK,
+                                Position::PIKE,
+                                Position::STRAIGHT,
+                                Position::SPLIT,
+                                Position::NONE,
+                            ],
+                        )
+                        .not_null(),
+                )
+                .col(float(Skill::Difficulty))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-skill-turn_id")
+                        .from(Skill::Table, Skill::TurnId)
+                        .to(Turn::Table, Turn::TurnId),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+    NameFirst,
+    NameLast,
+    Email,
+    Password,
+    UserType,
+}
+
+#[derive(DeriveIden)]
+enum UserType {
+    Table,
+    Coach,
+    Athlete,
+}
+
+#[derive(DeriveIden)]
+enum Club {
+    Table,
+    ClubId,
+    OwnerId,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum ClubMember {
+    Table,
+    ClubMemberId,
+    UserId,
+    ClubId,
+}
+
+#[derive(DeriveIden)]
+enum Session {
+    Table,
+    SessionId,
+    UserId,
+    EventId,
+    TimeStart,
+    Summary,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    TurnId,
+    SessionId,
+    UserId,
+    EventId,
+    TotalDifficulty,
+    Note,
+}
+
+#[derive(DeriveIden)]
+enum Skill {
+    Table,
+    SkillId,
+    TurnId,
+    EventId,
+    SkillNum,
+    FigRep,
+    Direction,
+    Position,
+    Difficulty,
+}
+
+#[derive(DeriveIden)]
+enum Direction {
+    Table,
+    FORWARD,
+    BACKWARD,
+    NONE,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    #[sea_orm(iden = "skill_position")]
+    Table,
+    TUCK,
+    PIKE,
+    STRAIGHT,
+    SPLIT,
+    NONE,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    TRA,
+    DMT,
+    TUM,
+}
@@ -0,0 +1,165 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        add_user_admin_column(manager).await?;
+        create_login_attempt_table(manager).await?;
+        create_account_lockout_table(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_account_lockout_table(manager).await?;
+        drop_login_attempt_table(manager).await?;
+        drop_user_admin_column(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250106_214510_create_login_security_tables"
+    }
+}
+
+async fn add_user_admin_column(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(User::Table)
+                .add_column(boolean(User::IsAdmin).default(false))
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_login_attempt_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(LoginAttempt::Table)
+                .if_not_exists()
+                .col(pk_auto(LoginAttempt::LoginAttemptId))
+                .col(string(LoginAttempt::Email))
+                .col(string(LoginAttempt::IpAddress))
+                .col(boolean(LoginAttempt::Successful))
+                .col(date_time(LoginAttempt::AttemptedAt))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-login_attempt-email")
+                .table(LoginAttempt::Table)
+                .col(LoginAttempt::Email)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-login_attempt-ip_address")
+                .table(LoginAttempt::Table)
+                .col(LoginAttempt::IpAddress)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_account_lockout_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(AccountLockout::Table)
+                .if_not_exists()
+                .col(pk_auto(AccountLockout::LockoutId))
+                .col(
+                    ColumnDef::new(AccountLockout::Scope)
+                        .enumeration(
+                            LockoutScope::Table,
+                            vec![LockoutScope::ACCOUNT, LockoutScope::IP],
+                        )
+                        .not_null(),
+                )
+                .col(string(AccountLockout::Subject))
+                .col(integer(AccountLockout::FailedAttempts))
+                .col(date_time(AccountLockout::LockedAt))
+                .col(date_time(AccountLockout::LockedUntil))
+                .col(date_time_null(AccountLockout::ClearedAt))
+                .col(integer_null(AccountLockout::ClearedBy))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-account_lockout-cleared_by")
+                        .from(AccountLockout::Table, AccountLockout::ClearedBy)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_user_admin_column(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(User::Table)
+                .drop_column(User::IsAdmin)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_login_attempt_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(LoginAttempt::Table).to_owned())
+        .await
+}
+
+async fn drop_account_lockout_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(AccountLockout::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+    IsAdmin,
+}
+
+#[derive(DeriveIden)]
+enum LoginAttempt {
+    Table,
+    LoginAttemptId,
+    Email,
+    IpAddress,
+    Successful,
+    AttemptedAt,
+}
+
+#[derive(DeriveIden)]
+enum AccountLockout {
+    Table,
+    LockoutId,
+    Scope,
+    Subject,
+    FailedAttempts,
+    LockedAt,
+    LockedUntil,
+    ClearedAt,
+    ClearedBy,
+}
+
+#[derive(DeriveIden)]
+enum LockoutScope {
+    Table,
+    ACCOUNT,
+    IP,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RateLimitBucket::Table)
+                    .if_not_exists()
+                    .col(string(RateLimitBucket::BucketKey).primary_key())
+                    .col(double(RateLimitBucket::Tokens))
+                    .col(date_time(RateLimitBucket::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RateLimitBucket::Table).to_owned())
+            .await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250112_180233_create_rate_limit_table"
+    }
+}
+
+#[derive(DeriveIden)]
+enum RateLimitBucket {
+    Table,
+    BucketKey,
+    Tokens,
+    UpdatedAt,
+}
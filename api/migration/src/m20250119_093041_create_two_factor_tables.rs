@@ -0,0 +1,147 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        add_club_require_two_factor_column(manager).await?;
+        create_user_totp_table(manager).await?;
+        create_recovery_code_table(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_recovery_code_table(manager).await?;
+        drop_user_totp_table(manager).await?;
+        drop_club_require_two_factor_column(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250119_093041_create_two_factor_tables"
+    }
+}
+
+async fn add_club_require_two_factor_column(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Club::Table)
+                .add_column(boolean(Club::RequireTwoFactor).default(false))
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_user_totp_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(UserTotp::Table)
+                .if_not_exists()
+                .col(integer(UserTotp::UserId).primary_key())
+                .col(string(UserTotp::Secret))
+                .col(boolean(UserTotp::Enabled))
+                .col(big_integer_null(UserTotp::LastUsedStep))
+                .col(date_time(UserTotp::CreatedAt))
+                .col(date_time_null(UserTotp::EnabledAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-user_totp-user_id")
+                        .from(UserTotp::Table, UserTotp::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_recovery_code_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(RecoveryCode::Table)
+                .if_not_exists()
+                .col(pk_auto(RecoveryCode::RecoveryCodeId))
+                .col(integer(RecoveryCode::UserId))
+                .col(string(RecoveryCode::CodeHash))
+                .col(date_time_null(RecoveryCode::UsedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-recovery_code-user_id")
+                        .from(RecoveryCode::Table, RecoveryCode::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-recovery_code-user_id")
+                .table(RecoveryCode::Table)
+                .col(RecoveryCode::UserId)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_club_require_two_factor_column(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Club::Table)
+                .drop_column(Club::RequireTwoFactor)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_user_totp_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(UserTotp::Table).to_owned())
+        .await
+}
+
+async fn drop_recovery_code_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(RecoveryCode::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Club {
+    Table,
+    RequireTwoFactor,
+}
+
+#[derive(DeriveIden)]
+enum UserTotp {
+    Table,
+    UserId,
+    Secret,
+    Enabled,
+    LastUsedStep,
+    CreatedAt,
+    EnabledAt,
+}
+
+#[derive(DeriveIden)]
+enum RecoveryCode {
+    Table,
+    RecoveryCodeId,
+    UserId,
+    CodeHash,
+    UsedAt,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SigningKey::Table)
+                    .if_not_exists()
+                    .col(string(SigningKey::Kid).primary_key())
+                    .col(string(SigningKey::Algorithm))
+                    .col(text(SigningKey::PrivateKey))
+                    .col(string(SigningKey::PublicKey))
+                    .col(date_time(SigningKey::CreatedAt))
+                    .col(date_time(SigningKey::ActivatesAt))
+                    .col(date_time(SigningKey::RetiresAt))
+                    .col(date_time(SigningKey::ExpiresAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SigningKey::Table).to_owned())
+            .await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250125_152207_create_signing_key_table"
+    }
+}
+
+#[derive(DeriveIden)]
+enum SigningKey {
+    Table,
+    Kid,
+    Algorithm,
+    PrivateKey,
+    PublicKey,
+    CreatedAt,
+    ActivatesAt,
+    RetiresAt,
+    ExpiresAt,
+}
@@ -0,0 +1,328 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        fix_club_owner_foreign_key(manager).await?;
+        fix_session_user_foreign_key(manager).await?;
+        remove_duplicate_memberships(manager).await?;
+        create_indexes(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_indexes(manager).await?;
+        restore_session_user_foreign_key(manager).await?;
+        restore_club_owner_foreign_key(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250201_101530_add_indexes_and_constraints"
+    }
+}
+
+// owner_id is NOT NULL, so a coach who owns a club can't be deleted until ownership moves
+async fn fix_club_owner_foreign_key(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    replace_foreign_key(
+        manager,
+        "club",
+        club_table(ForeignKeyAction::Restrict),
+        "fk-club-user_id",
+        ForeignKey::create()
+            .name("fk-club-user_id")
+            .from(Club::Table, Club::OwnerId)
+            .to(User::Table, User::UserId)
+            .on_delete(ForeignKeyAction::Restrict)
+            .to_owned(),
+    )
+    .await
+}
+
+async fn restore_club_owner_foreign_key(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    replace_foreign_key(
+        manager,
+        "club",
+        club_table(ForeignKeyAction::SetNull),
+        "fk-club-user_id",
+        ForeignKey::create()
+            .name("fk-club-user_id")
+            .from(Club::Table, Club::OwnerId)
+            .to(User::Table, User::UserId)
+            .on_delete(ForeignKeyAction::SetNull)
+            .to_owned(),
+    )
+    .await
+}
+
+async fn fix_session_user_foreign_key(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    replace_foreign_key(
+        manager,
+        "session",
+        session_table("fk-session-user_id"),
+        "fk-turn-user_id",
+        ForeignKey::create()
+            .name("fk-session-user_id")
+            .from(Session::Table, Session::UserId)
+            .to(User::Table, User::UserId)
+            .to_owned(),
+    )
+    .await
+}
+
+async fn restore_session_user_foreign_key(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    replace_foreign_key(
+        manager,
+        "session",
+        session_table("fk-turn-user_id"),
+        "fk-session-user_id",
+        ForeignKey::create()
+            .name("fk-turn-user_id")
+            .from(Session::Table, Session::UserId)
+            .to(User::Table, User::UserId)
+            .to_owned(),
+    )
+    .await
+}
+
+// Keeps the oldest membership for each user, so the unique index can be created
+async fn remove_duplicate_memberships(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .get_connection()
+        .execute_unprepared(
+            "DELETE FROM club_member WHERE club_member_id NOT IN \
+             (SELECT MIN(club_member_id) FROM club_member GROUP BY user_id)",
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn create_indexes(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-club-name")
+                .table(Club::Table)
+                .col(Club::Name)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+    // A user can only be in one club, which also rules out duplicate membership pairs
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-club_member-user_id")
+                .table(ClubMember::Table)
+                .col(ClubMember::UserId)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-session-user_id")
+                .table(Session::Table)
+                .col(Session::UserId)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-turn-session_id")
+                .table(Turn::Table)
+                .col(Turn::SessionId)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-skill-turn_id")
+                .table(Skill::Table)
+                .col(Skill::TurnId)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_indexes(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let indexes = [
+        ("idx-skill-turn_id", Skill::Table.into_iden()),
+        ("idx-turn-session_id", Turn::Table.into_iden()),
+        ("idx-session-user_id", Session::Table.into_iden()),
+        ("idx-club_member-user_id", ClubMember::Table.into_iden()),
+        ("idx-club-name", Club::Table.into_iden()),
+    ];
+
+    for (name, table) in indexes {
+        manager
+            .drop_index(Index::drop().name(name).table(table).to_owned())
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Swaps a foreign key for a new definition. SQLite can't alter constraints, so there the table
+// is rebuilt from `table`, which must have the same columns with the new foreign key
+async fn replace_foreign_key(
+    manager: &SchemaManager<'_>,
+    table_name: &str,
+    table: TableCreateStatement,
+    old_name: &str,
+    foreign_key: ForeignKeyCreateStatement,
+) -> Result<(), DbErr> {
+    match manager.get_database_backend() {
+        DbBackend::Sqlite => rebuild_sqlite_table(manager, table_name, table).await,
+        _ => {
+            manager
+                .drop_foreign_key(
+                    ForeignKey::drop()
+                        .name(old_name)
+                        .table(Alias::new(table_name))
+                        .to_owned(),
+                )
+                .await?;
+            manager.create_foreign_key(foreign_key).await
+        }
+    }
+}
+
+// Follows SQLite's documented procedure for schema changes ALTER TABLE can't make. Everything
+// runs as one batch, so the foreign_keys pragma applies to the same pooled connection
+async fn rebuild_sqlite_table(
+    manager: &SchemaManager<'_>,
+    table_name: &str,
+    table: TableCreateStatement,
+) -> Result<(), DbErr> {
+    let backend = manager.get_database_backend();
+    let columns = table
+        .get_columns()
+        .iter()
+        .map(|column| format!("\"{}\"", column.get_column_name()))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let mut new_table = table.clone();
+    new_table.table(Alias::new(format!("{}_new", table_name)));
+
+    manager
+        .get_connection()
+        .execute_unprepared(&format!(
+            "PRAGMA foreign_keys = OFF;
+             BEGIN;
+             {create};
+             INSERT INTO \"{table}_new\" ({columns}) SELECT {columns} FROM \"{table}\";
+             DROP TABLE \"{table}\";
+             ALTER TABLE \"{table}_new\" RENAME TO \"{table}\";
+             COMMIT;
+             PRAGMA foreign_keys = ON;",
+            create = backend.build(&new_table),
+            table = table_name,
+            columns = columns,
+        ))
+        .await?;
+
+    Ok(())
+}
+
+fn club_table(on_delete: ForeignKeyAction) -> TableCreateStatement {
+    Table::create()
+        .table(Club::Table)
+        .col(pk_auto(Club::ClubId))
+        .col(string(Club::Name))
+        .col(integer(Club::OwnerId))
+        .col(boolean(Club::RequireTwoFactor).default(false))
+        .foreign_key(
+            ForeignKey::create()
+                .name("fk-club-user_id")
+                .from_col(Club::OwnerId)
+                .to(User::Table, User::UserId)
+                .on_delete(on_delete),
+        )
+        .to_owned()
+}
+
+fn session_table(foreign_key_name: &str) -> TableCreateStatement {
+    Table::create()
+        .table(Session::Table)
+        .col(pk_auto(Session::SessionId))
+        .col(integer(Session::UserId))
+        .col(
+            ColumnDef::new(Session::EventId)
+                .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                .not_null(),
+        )
+        .col(date_time(Session::TimeStart))
+        .col(string(Session::Summary))
+        .foreign_key(
+            ForeignKey::create()
+                .name(foreign_key_name)
+                .from_col(Session::UserId)
+                .to(User::Table, User::UserId),
+        )
+        .to_owned()
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Club {
+    Table,
+    ClubId,
+    Name,
+    OwnerId,
+    RequireTwoFactor,
+}
+
+#[derive(DeriveIden)]
+enum ClubMember {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Session {
+    Table,
+    SessionId,
+    UserId,
+    EventId,
+    TimeStart,
+    Summary,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    SessionId,
+}
+
+#[derive(DeriveIden)]
+enum Skill {
+    Table,
+    TurnId,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    TRA,
+    DMT,
+    TUM,
+}
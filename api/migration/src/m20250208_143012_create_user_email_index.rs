@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Registration checks for an existing account first, this stops two requests racing past it
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-user-email-user_type")
+                    .table(User::Table)
+                    .col(User::Email)
+                    .col(User::UserType)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-user-email-user_type")
+                    .table(User::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250208_143012_create_user_email_index"
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Email,
+    UserType,
+}
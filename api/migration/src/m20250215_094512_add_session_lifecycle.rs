@@ -0,0 +1,168 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+use sea_orm_migration::sea_query::extension::postgres::Type;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_session_status_type(manager).await?;
+        add_session_columns(manager).await?;
+        create_open_session_index(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_open_session_index(manager).await?;
+        drop_session_columns(manager).await?;
+        drop_session_status_type(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250215_094512_add_session_lifecycle"
+    }
+}
+
+// Postgres needs a native enum type for the status column, SQLite stores it as text
+async fn create_session_status_type(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    manager
+        .create_type(
+            Type::create()
+                .as_enum(SessionStatus::Table)
+                .values([
+                    SessionStatus::OPEN,
+                    SessionStatus::CLOSED,
+                    SessionStatus::AutoClosed,
+                ])
+                .to_owned(),
+        )
+        .await
+}
+
+// Sessions from before this migration never had an end, so they are all treated as closed.
+// SQLite can only add one column per statement
+async fn add_session_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Session::Table)
+                .add_column(
+                    ColumnDef::new(Session::Status)
+                        .enumeration(
+                            SessionStatus::Table,
+                            vec![
+                                SessionStatus::OPEN,
+                                SessionStatus::CLOSED,
+                                SessionStatus::AutoClosed,
+                            ],
+                        )
+                        .not_null()
+                        .default("CLOSED"),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Session::Table)
+                .add_column(date_time_null(Session::TimeEnd))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Session::Table)
+                .add_column(date_time_null(Session::LastActivityAt))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .get_connection()
+        .execute_unprepared(
+            "UPDATE session SET time_end = time_start, last_activity_at = time_start",
+        )
+        .await?;
+
+    Ok(())
+}
+
+// A partial unique index, so an athlete can only have one open session. sea-query can't
+// build the WHERE clause, but Postgres and SQLite share the syntax
+async fn create_open_session_index(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .get_connection()
+        .execute_unprepared(
+            "CREATE UNIQUE INDEX \"idx-session-user_id-open\" ON session (user_id) \
+             WHERE status = 'OPEN'",
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn drop_open_session_index(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_index(
+            Index::drop()
+                .name("idx-session-user_id-open")
+                .table(Session::Table)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_session_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    for column in [Session::LastActivityAt, Session::TimeEnd, Session::Status] {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Session::Table)
+                    .drop_column(column)
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn drop_session_status_type(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    manager
+        .drop_type(Type::drop().name(SessionStatus::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum Session {
+    Table,
+    Status,
+    TimeEnd,
+    LastActivityAt,
+}
+
+#[derive(DeriveIden)]
+enum SessionStatus {
+    Table,
+    #[sea_orm(iden = "OPEN")]
+    OPEN,
+    #[sea_orm(iden = "CLOSED")]
+    CLOSED,
+    #[sea_orm(iden = "AUTO_CLOSED")]
+    AutoClosed,
+}
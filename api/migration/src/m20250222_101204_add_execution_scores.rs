@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        add_skill_columns(manager).await?;
+        add_turn_columns(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_turn_columns(manager).await?;
+        drop_skill_columns(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250222_101204_add_execution_scores"
+    }
+}
+
+async fn add_skill_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Skill::Table)
+                .add_column(float(Skill::Deduction).default(0.0))
+                .to_owned(),
+        )
+        .await
+}
+
+// Turns logged before this migration have no execution, so their total is just the DD.
+// SQLite can only add one column per statement
+async fn add_turn_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    for column in [
+        Turn::LandingDeduction,
+        Turn::ExecutionScore,
+        Turn::TotalScore,
+    ] {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Turn::Table)
+                    .add_column(float(column).default(0.0))
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    manager
+        .get_connection()
+        .execute_unprepared("UPDATE turn SET total_score = total_difficulty")
+        .await?;
+
+    Ok(())
+}
+
+async fn drop_skill_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Skill::Table)
+                .drop_column(Skill::Deduction)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_turn_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    for column in [
+        Turn::TotalScore,
+        Turn::ExecutionScore,
+        Turn::LandingDeduction,
+    ] {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Turn::Table)
+                    .drop_column(column)
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+enum Skill {
+    Table,
+    Deduction,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    LandingDeduction,
+    ExecutionScore,
+    TotalScore,
+}
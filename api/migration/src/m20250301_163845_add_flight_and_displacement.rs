@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite can only add one column per statement
+        let columns = [
+            (Skill::Table.into_iden(), float_null(Skill::TimeOfFlight)),
+            (Skill::Table.into_iden(), integer_null(Skill::LandingZone)),
+            (Turn::Table.into_iden(), float_null(Turn::TimeOfFlight)),
+            (Turn::Table.into_iden(), float_null(Turn::HorizontalScore)),
+            (
+                Turn::Table.into_iden(),
+                float(Turn::Penalty).default(0.0).to_owned(),
+            ),
+        ];
+
+        for (table, mut column) in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            (Turn::Table.into_iden(), Turn::Penalty.into_iden()),
+            (Turn::Table.into_iden(), Turn::HorizontalScore.into_iden()),
+            (Turn::Table.into_iden(), Turn::TimeOfFlight.into_iden()),
+            (Skill::Table.into_iden(), Skill::LandingZone.into_iden()),
+            (Skill::Table.into_iden(), Skill::TimeOfFlight.into_iden()),
+        ];
+
+        for (table, column) in columns {
+            manager
+                .alter_table(Table::alter().table(table).drop_column(column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250301_163845_add_flight_and_displacement"
+    }
+}
+
+#[derive(DeriveIden)]
+enum Skill {
+    Table,
+    TimeOfFlight,
+    LandingZone,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    TimeOfFlight,
+    HorizontalScore,
+    Penalty,
+}
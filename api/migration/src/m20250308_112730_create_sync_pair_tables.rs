@@ -0,0 +1,162 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_sync_pair_table(manager).await?;
+        create_sync_turn_table(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_sync_turn_table(manager).await?;
+        drop_sync_pair_table(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250308_112730_create_sync_pair_tables"
+    }
+}
+
+// The lower user id is always athlete one, so each pair can only be stored once. A pair is
+// requested by one athlete and only counts once the other accepts
+async fn create_sync_pair_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(SyncPair::Table)
+                .if_not_exists()
+                .col(pk_auto(SyncPair::SyncPairId))
+                .col(integer(SyncPair::AthleteOneId))
+                .col(integer(SyncPair::AthleteTwoId))
+                .col(integer(SyncPair::RequestedBy))
+                .col(date_time(SyncPair::CreatedAt))
+                .col(date_time_null(SyncPair::AcceptedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-sync_pair-athlete_one_id")
+                        .from(SyncPair::Table, SyncPair::AthleteOneId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-sync_pair-athlete_two_id")
+                        .from(SyncPair::Table, SyncPair::AthleteTwoId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-sync_pair-athletes")
+                .table(SyncPair::Table)
+                .col(SyncPair::AthleteOneId)
+                .col(SyncPair::AthleteTwoId)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-sync_pair-athlete_two_id")
+                .table(SyncPair::Table)
+                .col(SyncPair::AthleteTwoId)
+                .to_owned(),
+        )
+        .await
+}
+
+// Links a turn to the pair that performed it. The turn stays in the session of the athlete who
+// logged it
+async fn create_sync_turn_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(SyncTurn::Table)
+                .if_not_exists()
+                .col(pk_auto(SyncTurn::SyncTurnId))
+                .col(integer_uniq(SyncTurn::TurnId))
+                .col(integer(SyncTurn::SyncPairId))
+                .col(float_null(SyncTurn::SyncScore))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-sync_turn-turn_id")
+                        .from(SyncTurn::Table, SyncTurn::TurnId)
+                        .to(Turn::Table, Turn::TurnId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-sync_turn-sync_pair_id")
+                        .from(SyncTurn::Table, SyncTurn::SyncPairId)
+                        .to(SyncPair::Table, SyncPair::SyncPairId),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-sync_turn-sync_pair_id")
+                .table(SyncTurn::Table)
+                .col(SyncTurn::SyncPairId)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_sync_pair_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(SyncPair::Table).to_owned())
+        .await
+}
+
+async fn drop_sync_turn_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(SyncTurn::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    TurnId,
+}
+
+#[derive(DeriveIden)]
+enum SyncPair {
+    Table,
+    SyncPairId,
+    AthleteOneId,
+    AthleteTwoId,
+    RequestedBy,
+    CreatedAt,
+    AcceptedAt,
+}
+
+#[derive(DeriveIden)]
+enum SyncTurn {
+    Table,
+    SyncTurnId,
+    TurnId,
+    SyncPairId,
+    SyncScore,
+}
@@ -0,0 +1,345 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+use sea_orm_migration::sea_query::extension::postgres::Type;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_best_kind_type(manager).await?;
+        create_personal_best_table(manager).await?;
+        create_first_skill_table(manager).await?;
+        backfill_personal_bests(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_first_skill_table(manager).await?;
+        drop_personal_best_table(manager).await?;
+        drop_best_kind_type(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250315_090415_create_personal_best_tables"
+    }
+}
+
+// Every turn an athlete performed: their own, and synchronized turns their partner logged
+const ATHLETE_TURN: &str = "athlete_turn AS (
+    SELECT turn.user_id AS athlete_id, turn.turn_id FROM turn
+    UNION
+    SELECT
+        CASE WHEN sync_pair.athlete_one_id = turn.user_id
+            THEN sync_pair.athlete_two_id ELSE sync_pair.athlete_one_id END,
+        turn.turn_id
+    FROM sync_turn
+    JOIN sync_pair ON sync_pair.sync_pair_id = sync_turn.sync_pair_id
+    JOIN turn ON turn.turn_id = sync_turn.turn_id
+)";
+
+// Postgres needs a native enum type for the kind column, SQLite stores it as text
+async fn create_best_kind_type(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    manager
+        .create_type(
+            Type::create()
+                .as_enum(BestKind::Table)
+                .values([
+                    BestKind::HighestDifficulty,
+                    BestKind::HighestScore,
+                    BestKind::LongestRoutine,
+                ])
+                .to_owned(),
+        )
+        .await
+}
+
+// One row per athlete, event and kind of best, pointing at the turn that set it
+async fn create_personal_best_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(PersonalBest::Table)
+                .if_not_exists()
+                .col(pk_auto(PersonalBest::PersonalBestId))
+                .col(integer(PersonalBest::UserId))
+                .col(
+                    ColumnDef::new(PersonalBest::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(PersonalBest::Kind)
+                        .enumeration(
+                            BestKind::Table,
+                            vec![
+                                BestKind::HighestDifficulty,
+                                BestKind::HighestScore,
+                                BestKind::LongestRoutine,
+                            ],
+                        )
+                        .not_null(),
+                )
+                .col(integer(PersonalBest::TurnId))
+                .col(float(PersonalBest::Value))
+                .col(date_time(PersonalBest::AchievedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-personal_best-user_id")
+                        .from(PersonalBest::Table, PersonalBest::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-personal_best-turn_id")
+                        .from(PersonalBest::Table, PersonalBest::TurnId)
+                        .to(Turn::Table, Turn::TurnId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-personal_best-user_id-event_id-kind")
+                .table(PersonalBest::Table)
+                .col(PersonalBest::UserId)
+                .col(PersonalBest::EventId)
+                .col(PersonalBest::Kind)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+// The first time an athlete landed each distinct skill, a figure in a given shape
+async fn create_first_skill_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(FirstSkill::Table)
+                .if_not_exists()
+                .col(pk_auto(FirstSkill::FirstSkillId))
+                .col(integer(FirstSkill::UserId))
+                .col(
+                    ColumnDef::new(FirstSkill::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(integer(FirstSkill::FigRep))
+                .col(
+                    ColumnDef::new(FirstSkill::Position)
+                        .enumeration(
+                            Position::Table,
+                            vec![
+                                Position::TUCK,
+                                Position::PIKE,
+                                Position::STRAIGHT,
+                                Position::SPLIT,
+                                Position::NONE,
+                            ],
+                        )
+                        .not_null(),
+                )
+                .col(integer(FirstSkill::TurnId))
+                .col(integer(FirstSkill::SkillId))
+                .col(date_time(FirstSkill::AchievedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-first_skill-user_id")
+                        .from(FirstSkill::Table, FirstSkill::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-first_skill-skill_id")
+                        .from(FirstSkill::Table, FirstSkill::SkillId)
+                        .to(Skill::Table, Skill::SkillId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-first_skill-user_id-event_id-skill")
+                .table(FirstSkill::Table)
+                .col(FirstSkill::UserId)
+                .col(FirstSkill::EventId)
+                .col(FirstSkill::FigRep)
+                .col(FirstSkill::Position)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+// Works out the bests for turns logged before this migration. Ties go to the earlier turn, and
+// turns and skills are numbered in the order they were logged
+async fn backfill_personal_bests(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+
+    for (kind, value) in [
+        ("HIGHEST_DIFFICULTY", "turn.total_difficulty"),
+        ("HIGHEST_SCORE", "turn.total_score"),
+        (
+            "LONGEST_ROUTINE",
+            "(SELECT COUNT(*) FROM skill WHERE skill.turn_id = turn.turn_id)",
+        ),
+    ] {
+        db.execute_unprepared(&format!(
+            "WITH {ATHLETE_TURN}, ranked AS (
+                SELECT athlete_turn.athlete_id, turn.event_id, turn.turn_id, {value} AS value,
+                    session.time_start,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY athlete_turn.athlete_id, turn.event_id
+                        ORDER BY {value} DESC, turn.turn_id
+                    ) AS place
+                FROM athlete_turn
+                JOIN turn ON turn.turn_id = athlete_turn.turn_id
+                JOIN session ON session.session_id = turn.session_id
+            )
+            INSERT INTO personal_best (user_id, event_id, kind, turn_id, value, achieved_at)
+            SELECT athlete_id, event_id, '{kind}', turn_id, value, time_start
+            FROM ranked WHERE place = 1"
+        ))
+        .await?;
+    }
+
+    db.execute_unprepared(&format!(
+        "WITH {ATHLETE_TURN}, ranked AS (
+            SELECT athlete_turn.athlete_id, skill.event_id, skill.fig_rep, skill.position,
+                skill.turn_id, skill.skill_id, session.time_start,
+                ROW_NUMBER() OVER (
+                    PARTITION BY athlete_turn.athlete_id, skill.event_id, skill.fig_rep,
+                        skill.position
+                    ORDER BY skill.skill_id
+                ) AS place
+            FROM athlete_turn
+            JOIN skill ON skill.turn_id = athlete_turn.turn_id
+            JOIN turn ON turn.turn_id = skill.turn_id
+            JOIN session ON session.session_id = turn.session_id
+        )
+        INSERT INTO first_skill (user_id, event_id, fig_rep, position, turn_id, skill_id, achieved_at)
+        SELECT athlete_id, event_id, fig_rep, position, turn_id, skill_id, time_start
+        FROM ranked WHERE place = 1"
+    ))
+    .await?;
+
+    Ok(())
+}
+
+async fn drop_best_kind_type(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    manager
+        .drop_type(Type::drop().name(BestKind::Table).to_owned())
+        .await
+}
+
+async fn drop_personal_best_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(PersonalBest::Table).to_owned())
+        .await
+}
+
+async fn drop_first_skill_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(FirstSkill::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    TurnId,
+}
+
+#[derive(DeriveIden)]
+enum Skill {
+    Table,
+    SkillId,
+}
+
+#[derive(DeriveIden)]
+enum PersonalBest {
+    Table,
+    PersonalBestId,
+    UserId,
+    EventId,
+    Kind,
+    TurnId,
+    Value,
+    AchievedAt,
+}
+
+#[derive(DeriveIden)]
+enum FirstSkill {
+    Table,
+    FirstSkillId,
+    UserId,
+    EventId,
+    FigRep,
+    Position,
+    TurnId,
+    SkillId,
+    AchievedAt,
+}
+
+#[derive(DeriveIden)]
+enum BestKind {
+    Table,
+    #[sea_orm(iden = "HIGHEST_DIFFICULTY")]
+    HighestDifficulty,
+    #[sea_orm(iden = "HIGHEST_SCORE")]
+    HighestScore,
+    #[sea_orm(iden = "LONGEST_ROUTINE")]
+    LongestRoutine,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    #[sea_orm(iden = "TRA")]
+    TRA,
+    #[sea_orm(iden = "DMT")]
+    DMT,
+    #[sea_orm(iden = "TUM")]
+    TUM,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    #[sea_orm(iden = "skill_position")]
+    Table,
+    #[sea_orm(iden = "TUCK")]
+    TUCK,
+    #[sea_orm(iden = "PIKE")]
+    PIKE,
+    #[sea_orm(iden = "STRAIGHT")]
+    STRAIGHT,
+    #[sea_orm(iden = "SPLIT")]
+    SPLIT,
+    #[sea_orm(iden = "NONE")]
+    NONE,
+}
@@ -0,0 +1,163 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_catalogue_skill_table(manager).await?;
+        seed_catalogue(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_catalogue_skill_table(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250322_104620_create_catalogue_skill_table"
+    }
+}
+
+// The common named skills, with the shapes they are usually done in
+const NAMED_SKILLS: [(i32, &str, &[&str]); 18] = [
+    (40, "Somersault", &["TUCK", "PIKE", "STRAIGHT"]),
+    (41, "Barani", &["TUCK", "PIKE", "STRAIGHT"]),
+    (42, "Full", &["STRAIGHT"]),
+    (43, "Rudi", &["STRAIGHT"]),
+    (44, "Double full", &["STRAIGHT"]),
+    (45, "Randy", &["STRAIGHT"]),
+    (46, "Triple full", &["STRAIGHT"]),
+    (47, "Adolph", &["STRAIGHT"]),
+    (800, "Double somersault", &["TUCK", "PIKE", "STRAIGHT"]),
+    (801, "Half-out", &["TUCK", "PIKE", "STRAIGHT"]),
+    (811, "Half-in-half-out", &["TUCK", "PIKE", "STRAIGHT"]),
+    (802, "Full-out", &["TUCK", "PIKE", "STRAIGHT"]),
+    (820, "Full-in", &["TUCK", "PIKE", "STRAIGHT"]),
+    (803, "Rudi-out", &["TUCK", "PIKE", "STRAIGHT"]),
+    (813, "Half-in-rudi-out", &["TUCK", "PIKE", "STRAIGHT"]),
+    (822, "Full-in-full-out", &["TUCK", "PIKE", "STRAIGHT"]),
+    (12000, "Triple somersault", &["TUCK", "PIKE"]),
+    (12001, "Triple half-out", &["TUCK", "PIKE"]),
+];
+
+// One named skill per event, figure and shape. Its DD is worked out from the notation
+async fn create_catalogue_skill_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(CatalogueSkill::Table)
+                .if_not_exists()
+                .col(pk_auto(CatalogueSkill::CatalogueSkillId))
+                .col(
+                    ColumnDef::new(CatalogueSkill::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(integer(CatalogueSkill::FigRep))
+                .col(
+                    ColumnDef::new(CatalogueSkill::Position)
+                        .enumeration(
+                            Position::Table,
+                            vec![
+                                Position::TUCK,
+                                Position::PIKE,
+                                Position::STRAIGHT,
+                                Position::SPLIT,
+                                Position::NONE,
+                            ],
+                        )
+                        .not_null(),
+                )
+                .col(string(CatalogueSkill::Name))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-catalogue_skill-event_id-fig_rep-position")
+                .table(CatalogueSkill::Table)
+                .col(CatalogueSkill::EventId)
+                .col(CatalogueSkill::FigRep)
+                .col(CatalogueSkill::Position)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+// Trampoline and double mini share their skill names. Literals rather than bound values, so
+// Postgres casts them to its enum types
+async fn seed_catalogue(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let rows = ["TRA", "DMT"]
+        .iter()
+        .flat_map(|event| {
+            NAMED_SKILLS
+                .iter()
+                .flat_map(move |(fig_rep, name, positions)| {
+                    positions.iter().map(move |position| {
+                        format!("('{}', {}, '{}', '{}')", event, fig_rep, position, name)
+                    })
+                })
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    manager
+        .get_connection()
+        .execute_unprepared(&format!(
+            "INSERT INTO catalogue_skill (event_id, fig_rep, position, name) VALUES {}",
+            rows
+        ))
+        .await?;
+
+    Ok(())
+}
+
+async fn drop_catalogue_skill_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(CatalogueSkill::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum CatalogueSkill {
+    Table,
+    CatalogueSkillId,
+    EventId,
+    FigRep,
+    Position,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    #[sea_orm(iden = "TRA")]
+    TRA,
+    #[sea_orm(iden = "DMT")]
+    DMT,
+    #[sea_orm(iden = "TUM")]
+    TUM,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    #[sea_orm(iden = "skill_position")]
+    Table,
+    #[sea_orm(iden = "TUCK")]
+    TUCK,
+    #[sea_orm(iden = "PIKE")]
+    PIKE,
+    #[sea_orm(iden = "STRAIGHT")]
+    STRAIGHT,
+    #[sea_orm(iden = "SPLIT")]
+    SPLIT,
+    #[sea_orm(iden = "NONE")]
+    NONE,
+}
@@ -0,0 +1,204 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_comment_table(manager).await?;
+        create_comment_read_table(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_comment_read_table(manager).await?;
+        drop_comment_table(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250329_141855_create_comment_tables"
+    }
+}
+
+// A comment on a session, a turn in it or one skill of that turn. The session is always set, so
+// a whole session's discussion is one thread. Replies are on the same session, turn or skill as
+// the comment they answer, and deleted comments are kept so their replies still make sense
+async fn create_comment_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Comment::Table)
+                .if_not_exists()
+                .col(pk_auto(Comment::CommentId))
+                .col(integer(Comment::SessionId))
+                .col(integer_null(Comment::TurnId))
+                .col(integer_null(Comment::SkillId))
+                .col(integer_null(Comment::ParentCommentId))
+                .col(integer(Comment::UserId))
+                .col(text(Comment::Body))
+                .col(date_time(Comment::CreatedAt))
+                .col(date_time_null(Comment::EditedAt))
+                .col(date_time_null(Comment::DeletedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment-session_id")
+                        .from(Comment::Table, Comment::SessionId)
+                        .to(Session::Table, Session::SessionId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment-turn_id")
+                        .from(Comment::Table, Comment::TurnId)
+                        .to(Turn::Table, Turn::TurnId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment-skill_id")
+                        .from(Comment::Table, Comment::SkillId)
+                        .to(Skill::Table, Skill::SkillId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment-parent_comment_id")
+                        .from(Comment::Table, Comment::ParentCommentId)
+                        .to(Comment::Table, Comment::CommentId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment-user_id")
+                        .from(Comment::Table, Comment::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-comment-session_id")
+                .table(Comment::Table)
+                .col(Comment::SessionId)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-comment-turn_id")
+                .table(Comment::Table)
+                .col(Comment::TurnId)
+                .to_owned(),
+        )
+        .await
+}
+
+// When each user last read a session's comments. Anything posted by someone else since is unread
+async fn create_comment_read_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(CommentRead::Table)
+                .if_not_exists()
+                .col(pk_auto(CommentRead::CommentReadId))
+                .col(integer(CommentRead::UserId))
+                .col(integer(CommentRead::SessionId))
+                .col(date_time(CommentRead::ReadAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment_read-user_id")
+                        .from(CommentRead::Table, CommentRead::UserId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-comment_read-session_id")
+                        .from(CommentRead::Table, CommentRead::SessionId)
+                        .to(Session::Table, Session::SessionId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-comment_read-user_id-session_id")
+                .table(CommentRead::Table)
+                .col(CommentRead::UserId)
+                .col(CommentRead::SessionId)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_comment_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(Comment::Table).to_owned())
+        .await
+}
+
+async fn drop_comment_read_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(CommentRead::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Session {
+    Table,
+    SessionId,
+}
+
+#[derive(DeriveIden)]
+enum Turn {
+    Table,
+    TurnId,
+}
+
+#[derive(DeriveIden)]
+enum Skill {
+    Table,
+    SkillId,
+}
+
+#[derive(DeriveIden)]
+enum Comment {
+    Table,
+    CommentId,
+    SessionId,
+    TurnId,
+    SkillId,
+    ParentCommentId,
+    UserId,
+    Body,
+    CreatedAt,
+    EditedAt,
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum CommentRead {
+    Table,
+    CommentReadId,
+    UserId,
+    SessionId,
+    ReadAt,
+}
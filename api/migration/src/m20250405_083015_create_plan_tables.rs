@@ -0,0 +1,302 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_plan_table(manager).await?;
+        create_plan_item_table(manager).await?;
+        create_plan_item_skill_table(manager).await?;
+        create_plan_assignment_table(manager).await?;
+        add_session_plan_column(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_session_plan_column(manager).await?;
+        drop_plan_tables(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250405_083015_create_plan_tables"
+    }
+}
+
+// A workout a coach prescribes for one day of training in an event
+async fn create_plan_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Plan::Table)
+                .if_not_exists()
+                .col(pk_auto(Plan::PlanId))
+                .col(integer(Plan::ClubId))
+                .col(integer(Plan::CoachId))
+                .col(
+                    ColumnDef::new(Plan::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(date(Plan::ScheduledFor))
+                .col(string(Plan::Name))
+                .col(date_time(Plan::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-plan-club_id")
+                        .from(Plan::Table, Plan::ClubId)
+                        .to(Club::Table, Club::ClubId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-plan-coach_id")
+                        .from(Plan::Table, Plan::CoachId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-plan-club_id")
+                .table(Plan::Table)
+                .col(Plan::ClubId)
+                .to_owned(),
+        )
+        .await
+}
+
+// Each item is either one skill, counted wherever it is landed, or a whole turn of exactly these
+// skills, to be done a number of times
+async fn create_plan_item_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(PlanItem::Table)
+                .if_not_exists()
+                .col(pk_auto(PlanItem::PlanItemId))
+                .col(integer(PlanItem::PlanId))
+                .col(integer(PlanItem::ItemNum))
+                .col(boolean(PlanItem::WholeTurn))
+                .col(integer(PlanItem::Repetitions))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-plan_item-plan_id")
+                        .from(PlanItem::Table, PlanItem::PlanId)
+                        .to(Plan::Table, Plan::PlanId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn create_plan_item_skill_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(PlanItemSkill::Table)
+                .if_not_exists()
+                .col(pk_auto(PlanItemSkill::PlanItemSkillId))
+                .col(integer(PlanItemSkill::PlanItemId))
+                .col(integer(PlanItemSkill::SkillNum))
+                .col(integer(PlanItemSkill::FigRep))
+                .col(
+                    ColumnDef::new(PlanItemSkill::Position)
+                        .enumeration(
+                            Position::Table,
+                            vec![
+                                Position::TUCK,
+                                Position::PIKE,
+                                Position::STRAIGHT,
+                                Position::SPLIT,
+                                Position::NONE,
+                            ],
+                        )
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-plan_item_skill-plan_item_id")
+                        .from(PlanItemSkill::Table, PlanItemSkill::PlanItemId)
+                        .to(PlanItem::Table, PlanItem::PlanItemId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+// The athletes a plan is assigned to, each at most once
+async fn create_plan_assignment_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(PlanAssignment::Table)
+                .if_not_exists()
+                .col(pk_auto(PlanAssignment::PlanAssignmentId))
+                .col(integer(PlanAssignment::PlanId))
+                .col(integer(PlanAssignment::AthleteId))
+                .col(date_time(PlanAssignment::AssignedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-plan_assignment-plan_id")
+                        .from(PlanAssignment::Table, PlanAssignment::PlanId)
+                        .to(Plan::Table, Plan::PlanId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-plan_assignment-athlete_id")
+                        .from(PlanAssignment::Table, PlanAssignment::AthleteId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-plan_assignment-plan_id-athlete_id")
+                .table(PlanAssignment::Table)
+                .col(PlanAssignment::PlanId)
+                .col(PlanAssignment::AthleteId)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+// The plan a session was trained against. SQLite cannot add a foreign key to an existing table,
+// so deleting a plan clears it from its sessions instead
+async fn add_session_plan_column(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Session::Table)
+                .add_column(integer_null(Session::PlanId))
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_session_plan_column(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Session::Table)
+                .drop_column(Session::PlanId)
+                .to_owned(),
+        )
+        .await
+}
+
+// Items, their skills and assignments reference the plan, so they go first
+async fn drop_plan_tables(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(PlanAssignment::Table).to_owned())
+        .await?;
+    manager
+        .drop_table(Table::drop().table(PlanItemSkill::Table).to_owned())
+        .await?;
+    manager
+        .drop_table(Table::drop().table(PlanItem::Table).to_owned())
+        .await?;
+    manager
+        .drop_table(Table::drop().table(Plan::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Club {
+    Table,
+    ClubId,
+}
+
+#[derive(DeriveIden)]
+enum Session {
+    Table,
+    PlanId,
+}
+
+#[derive(DeriveIden)]
+enum Plan {
+    Table,
+    PlanId,
+    ClubId,
+    CoachId,
+    EventId,
+    ScheduledFor,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PlanItem {
+    Table,
+    PlanItemId,
+    PlanId,
+    ItemNum,
+    WholeTurn,
+    Repetitions,
+}
+
+#[derive(DeriveIden)]
+enum PlanItemSkill {
+    Table,
+    PlanItemSkillId,
+    PlanItemId,
+    SkillNum,
+    FigRep,
+    Position,
+}
+
+#[derive(DeriveIden)]
+enum PlanAssignment {
+    Table,
+    PlanAssignmentId,
+    PlanId,
+    AthleteId,
+    AssignedAt,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    #[sea_orm(iden = "TRA")]
+    TRA,
+    #[sea_orm(iden = "DMT")]
+    DMT,
+    #[sea_orm(iden = "TUM")]
+    TUM,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    #[sea_orm(iden = "skill_position")]
+    Table,
+    #[sea_orm(iden = "TUCK")]
+    TUCK,
+    #[sea_orm(iden = "PIKE")]
+    PIKE,
+    #[sea_orm(iden = "STRAIGHT")]
+    STRAIGHT,
+    #[sea_orm(iden = "SPLIT")]
+    SPLIT,
+    #[sea_orm(iden = "NONE")]
+    NONE,
+}
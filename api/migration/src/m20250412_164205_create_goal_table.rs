@@ -0,0 +1,188 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+use sea_orm_migration::sea_orm::DbBackend;
+use sea_orm_migration::sea_query::extension::postgres::Type;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_goal_kind_type(manager).await?;
+        create_goal_table(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_goal_table(manager).await?;
+        drop_goal_kind_type(manager).await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250412_164205_create_goal_table"
+    }
+}
+
+// Postgres needs a native enum type for the kind column, SQLite stores it as text
+async fn create_goal_kind_type(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    manager
+        .create_type(
+            Type::create()
+                .as_enum(GoalKind::Table)
+                .values([GoalKind::Difficulty, GoalKind::ConsistentSkill])
+                .to_owned(),
+        )
+        .await
+}
+
+// A target an athlete works towards by a deadline. The target is a DD in tenths, or a number of
+// consistent landings of one skill (its figure and shape). It is marked completed the first time
+// the athlete's training reaches it
+async fn create_goal_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Goal::Table)
+                .if_not_exists()
+                .col(pk_auto(Goal::GoalId))
+                .col(integer(Goal::AthleteId))
+                .col(integer(Goal::SetBy))
+                .col(
+                    ColumnDef::new(Goal::Kind)
+                        .enumeration(
+                            GoalKind::Table,
+                            vec![GoalKind::Difficulty, GoalKind::ConsistentSkill],
+                        )
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Goal::EventId)
+                        .enumeration(Event::Table, vec![Event::TRA, Event::DMT, Event::TUM])
+                        .not_null(),
+                )
+                .col(integer(Goal::Target))
+                .col(integer_null(Goal::FigRep))
+                .col(
+                    ColumnDef::new(Goal::Position)
+                        .enumeration(
+                            Position::Table,
+                            vec![
+                                Position::TUCK,
+                                Position::PIKE,
+                                Position::STRAIGHT,
+                                Position::SPLIT,
+                                Position::NONE,
+                            ],
+                        )
+                        .null(),
+                )
+                .col(date(Goal::Deadline))
+                .col(date_time(Goal::CreatedAt))
+                .col(date_time_null(Goal::CompletedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-goal-athlete_id")
+                        .from(Goal::Table, Goal::AthleteId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-goal-set_by")
+                        .from(Goal::Table, Goal::SetBy)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-goal-athlete_id")
+                .table(Goal::Table)
+                .col(Goal::AthleteId)
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_goal_kind_type(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    manager
+        .drop_type(Type::drop().name(GoalKind::Table).to_owned())
+        .await
+}
+
+async fn drop_goal_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .drop_table(Table::drop().table(Goal::Table).to_owned())
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Goal {
+    Table,
+    GoalId,
+    AthleteId,
+    SetBy,
+    Kind,
+    EventId,
+    Target,
+    FigRep,
+    Position,
+    Deadline,
+    CreatedAt,
+    CompletedAt,
+}
+
+#[derive(DeriveIden)]
+enum GoalKind {
+    Table,
+    #[sea_orm(iden = "DIFFICULTY")]
+    Difficulty,
+    #[sea_orm(iden = "CONSISTENT_SKILL")]
+    ConsistentSkill,
+}
+
+#[derive(DeriveIden)]
+enum Event {
+    Table,
+    #[sea_orm(iden = "TRA")]
+    TRA,
+    #[sea_orm(iden = "DMT")]
+    DMT,
+    #[sea_orm(iden = "TUM")]
+    TUM,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    #[sea_orm(iden = "skill_position")]
+    Table,
+    #[sea_orm(iden = "TUCK")]
+    TUCK,
+    #[sea_orm(iden = "PIKE")]
+    PIKE,
+    #[sea_orm(iden = "STRAIGHT")]
+    STRAIGHT,
+    #[sea_orm(iden = "SPLIT")]
+    SPLIT,
+    #[sea_orm(iden = "NONE")]
+    NONE,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UsedToken::Table)
+                    .if_not_exists()
+                    .col(string(UsedToken::Jti).primary_key())
+                    .col(date_time(UsedToken::UsedAt))
+                    .col(date_time(UsedToken::ExpiresAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UsedToken::Table).to_owned())
+            .await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250419_102311_create_used_token_table"
+    }
+}
+
+#[derive(DeriveIden)]
+enum UsedToken {
+    Table,
+    Jti,
+    UsedAt,
+    ExpiresAt,
+}
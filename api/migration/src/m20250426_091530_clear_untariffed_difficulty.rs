@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+// Double mini and tumbling turns were given the trampoline tariff. Their difficulty is now left
+// unscored, so it comes off the totals along with the personal bests based on it
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "UPDATE turn SET total_score = total_score - total_difficulty, total_difficulty = 0 \
+             WHERE event_id <> 'TRA'",
+        )
+        .await?;
+        db.execute_unprepared("UPDATE skill SET difficulty = 0 WHERE event_id <> 'TRA'")
+            .await?;
+        db.execute_unprepared(
+            "DELETE FROM personal_best WHERE kind = 'HIGHEST_DIFFICULTY' AND event_id <> 'TRA'",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // The old tariffs were wrong, so there is nothing worth restoring
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250426_091530_clear_untariffed_difficulty"
+    }
+}
@@ -0,0 +1,136 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::schema::*;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_training_group_table(manager).await?;
+        create_training_group_member_table(manager).await
+    }
+
+    // Members reference the group, so they go first
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TrainingGroupMember::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TrainingGroup::Table).to_owned())
+            .await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250503_103015_create_training_group_tables"
+    }
+}
+
+// A named group of a club's athletes that plans can be assigned to, with names unique per club
+async fn create_training_group_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(TrainingGroup::Table)
+                .if_not_exists()
+                .col(pk_auto(TrainingGroup::TrainingGroupId))
+                .col(integer(TrainingGroup::ClubId))
+                .col(string(TrainingGroup::Name))
+                .col(date_time(TrainingGroup::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-training_group-club_id")
+                        .from(TrainingGroup::Table, TrainingGroup::ClubId)
+                        .to(Club::Table, Club::ClubId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-training_group-club_id-name")
+                .table(TrainingGroup::Table)
+                .col(TrainingGroup::ClubId)
+                .col(TrainingGroup::Name)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+// The athletes in each group, each at most once
+async fn create_training_group_member_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(TrainingGroupMember::Table)
+                .if_not_exists()
+                .col(pk_auto(TrainingGroupMember::TrainingGroupMemberId))
+                .col(integer(TrainingGroupMember::TrainingGroupId))
+                .col(integer(TrainingGroupMember::AthleteId))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-training_group_member-training_group_id")
+                        .from(
+                            TrainingGroupMember::Table,
+                            TrainingGroupMember::TrainingGroupId,
+                        )
+                        .to(TrainingGroup::Table, TrainingGroup::TrainingGroupId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-training_group_member-athlete_id")
+                        .from(TrainingGroupMember::Table, TrainingGroupMember::AthleteId)
+                        .to(User::Table, User::UserId)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx-training_group_member-training_group_id-athlete_id")
+                .table(TrainingGroupMember::Table)
+                .col(TrainingGroupMember::TrainingGroupId)
+                .col(TrainingGroupMember::AthleteId)
+                .unique()
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Club {
+    Table,
+    ClubId,
+}
+
+#[derive(DeriveIden)]
+enum TrainingGroup {
+    Table,
+    TrainingGroupId,
+    ClubId,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TrainingGroupMember {
+    Table,
+    TrainingGroupMemberId,
+    TrainingGroupId,
+    AthleteId,
+}
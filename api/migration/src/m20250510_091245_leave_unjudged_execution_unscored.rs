@@ -0,0 +1,130 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+
+pub struct Migration;
+
+// Turns logged without deductions were saved as perfectly executed. Their execution is now left
+// unscored until the turn is judged, so it comes off their totals
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, column) in COLUMNS {
+            allow_null(manager, table, column).await?;
+        }
+
+        // A turn without a single deduction is what an unjudged turn was saved as. Totals are
+        // rounded back to thousandths, which single precision floats don't keep exactly
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "UPDATE turn SET total_score = ROUND(CAST(total_score - execution_score AS NUMERIC), 3), \
+             execution_score = NULL, landing_deduction = NULL \
+             WHERE landing_deduction = 0 AND NOT EXISTS \
+             (SELECT 1 FROM skill WHERE skill.turn_id = turn.turn_id AND skill.deduction <> 0)",
+        )
+        .await?;
+        db.execute_unprepared(
+            "UPDATE skill SET deduction = NULL WHERE turn_id IN \
+             (SELECT turn_id FROM turn WHERE execution_score IS NULL)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Unjudged turns go back to being scored as if nothing was deducted
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "UPDATE turn SET execution_score = 2.0 * \
+             (SELECT COUNT(*) FROM skill WHERE skill.turn_id = turn.turn_id), \
+             landing_deduction = 0 WHERE execution_score IS NULL",
+        )
+        .await?;
+        db.execute_unprepared(
+            "UPDATE turn SET total_score = ROUND(CAST(total_score + execution_score AS NUMERIC), 3) \
+             WHERE turn_id IN (SELECT turn_id FROM skill WHERE deduction IS NULL)",
+        )
+        .await?;
+        db.execute_unprepared("UPDATE skill SET deduction = 0 WHERE deduction IS NULL")
+            .await?;
+
+        for (table, column) in COLUMNS {
+            require_value(manager, table, column).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250510_091245_leave_unjudged_execution_unscored"
+    }
+}
+
+const COLUMNS: [(&str, &str); 3] = [
+    ("skill", "deduction"),
+    ("turn", "landing_deduction"),
+    ("turn", "execution_score"),
+];
+
+async fn allow_null(manager: &SchemaManager<'_>, table: &str, column: &str) -> Result<(), DbErr> {
+    match manager.get_database_backend() {
+        DbBackend::Sqlite => replace_sqlite_column(manager, table, column, "float NULL").await,
+        _ => {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "ALTER TABLE \"{table}\" ALTER COLUMN \"{column}\" DROP DEFAULT, \
+                     ALTER COLUMN \"{column}\" DROP NOT NULL"
+                ))
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+async fn require_value(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    column: &str,
+) -> Result<(), DbErr> {
+    match manager.get_database_backend() {
+        DbBackend::Sqlite => {
+            replace_sqlite_column(manager, table, column, "float NOT NULL DEFAULT 0").await
+        }
+        _ => {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "ALTER TABLE \"{table}\" ALTER COLUMN \"{column}\" SET DEFAULT 0, \
+                     ALTER COLUMN \"{column}\" SET NOT NULL"
+                ))
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+// SQLite can't change whether a column is nullable, so the column is copied into a new one with
+// the definition given, which then takes its name
+async fn replace_sqlite_column(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), DbErr> {
+    manager
+        .get_connection()
+        .execute_unprepared(&format!(
+            "ALTER TABLE \"{table}\" ADD COLUMN \"{column}_new\" {definition};
+             UPDATE \"{table}\" SET \"{column}_new\" = \"{column}\";
+             ALTER TABLE \"{table}\" DROP COLUMN \"{column}\";
+             ALTER TABLE \"{table}\" RENAME COLUMN \"{column}_new\" TO \"{column}\";"
+        ))
+        .await?;
+
+    Ok(())
+}
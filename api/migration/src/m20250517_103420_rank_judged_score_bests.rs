@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rank_highest_scores(manager, "turn.execution_score IS NOT NULL").await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rank_highest_scores(manager, "TRUE").await
+    }
+}
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20250517_103420_rank_judged_score_bests"
+    }
+}
+
+// Every turn each athlete performed, with synchronized turns counted for both partners
+const ATHLETE_TURN: &str = "athlete_turn AS (
+    SELECT turn.user_id AS athlete_id, turn.turn_id FROM turn
+    UNION
+    SELECT
+        CASE WHEN sync_pair.athlete_one_id = turn.user_id
+            THEN sync_pair.athlete_two_id ELSE sync_pair.athlete_one_id END,
+        turn.turn_id
+    FROM sync_turn
+    JOIN sync_pair ON sync_pair.sync_pair_id = sync_turn.sync_pair_id
+    JOIN turn ON turn.turn_id = sync_turn.turn_id
+)";
+
+// Works out every highest score best again from the turns that match `ranked`. Unjudged turns
+// have no execution in their total, so only judged turns are ranked. Ties go to the earlier turn
+async fn rank_highest_scores(manager: &SchemaManager<'_>, ranked: &str) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+
+    db.execute_unprepared("DELETE FROM personal_best WHERE kind = 'HIGHEST_SCORE'")
+        .await?;
+    db.execute_unprepared(&format!(
+        "WITH {ATHLETE_TURN}, ranked AS (
+            SELECT athlete_turn.athlete_id, turn.event_id, turn.turn_id, turn.total_score,
+                session.time_start,
+                ROW_NUMBER() OVER (
+                    PARTITION BY athlete_turn.athlete_id, turn.event_id
+                    ORDER BY turn.total_score DESC, turn.turn_id
+                ) AS place
+            FROM athlete_turn
+            JOIN turn ON turn.turn_id = athlete_turn.turn_id
+            JOIN session ON session.session_id = turn.session_id
+            WHERE {ranked}
+        )
+        INSERT INTO personal_best (user_id, event_id, kind, turn_id, value, achieved_at)
+        SELECT athlete_id, event_id, 'HIGHEST_SCORE', turn_id, total_score, time_start
+        FROM ranked WHERE place = 1"
+    ))
+    .await?;
+
+    Ok(())
+}
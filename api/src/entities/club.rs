@@ -7,8 +7,10 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key)]
     pub club_id: i32,
+    #[sea_orm(unique)]
     pub name: String,
     pub owner_id: i32,
+    pub require_two_factor: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -20,7 +22,7 @@ pub enum Relation {
         from = "Column::OwnerId",
         to = "super::user::Column::UserId",
         on_update = "NoAction",
-        on_delete = "SetNull"
+        on_delete = "Restrict"
     )]
     User,
 }
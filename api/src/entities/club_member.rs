@@ -7,6 +7,7 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key)]
     pub club_member_id: i32,
+    #[sea_orm(unique)]
     pub user_id: i32,
     pub club_id: i32,
 }
@@ -2,9 +2,32 @@
 
 pub mod prelude;
 
+pub mod account_lockout;
+pub mod catalogue_skill;
 pub mod club;
 pub mod club_member;
+pub mod comment;
+pub mod comment_read;
+pub mod first_skill;
+pub mod goal;
+pub mod login_attempt;
+pub mod migration_lock;
+pub mod personal_best;
+pub mod plan;
+pub mod plan_assignment;
+pub mod plan_item;
+pub mod plan_item_skill;
+pub mod rate_limit_bucket;
+pub mod recovery_code;
+pub mod sea_orm_active_enums;
 pub mod session;
+pub mod signing_key;
 pub mod skill;
+pub mod sync_pair;
+pub mod sync_turn;
+pub mod training_group;
+pub mod training_group_member;
 pub mod turn;
+pub mod used_token;
 pub mod user;
+pub mod user_totp;
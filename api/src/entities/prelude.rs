@@ -1,8 +1,30 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.2
 
+// pub use super::account_lockout::Entity as AccountLockout;
+// pub use super::catalogue_skill::Entity as CatalogueSkill;
 // pub use super::club::Entity as Club;
 // pub use super::club_member::Entity as ClubMember;
+// pub use super::comment::Entity as Comment;
+// pub use super::comment_read::Entity as CommentRead;
+// pub use super::first_skill::Entity as FirstSkill;
+// pub use super::goal::Entity as Goal;
+// pub use super::login_attempt::Entity as LoginAttempt;
+// pub use super::migration_lock::Entity as MigrationLock;
+// pub use super::personal_best::Entity as PersonalBest;
+// pub use super::plan::Entity as Plan;
+// pub use super::plan_assignment::Entity as PlanAssignment;
+// pub use super::plan_item::Entity as PlanItem;
+// pub use super::plan_item_skill::Entity as PlanItemSkill;
+// pub use super::rate_limit_bucket::Entity as RateLimitBucket;
+// pub use super::recovery_code::Entity as RecoveryCode;
 // pub use super::session::Entity as Session;
+// pub use super::signing_key::Entity as SigningKey;
 // pub use super::skill::Entity as Skill;
+// pub use super::sync_pair::Entity as SyncPair;
+// pub use super::sync_turn::Entity as SyncTurn;
+// pub use super::training_group::Entity as TrainingGroup;
+// pub use super::training_group_member::Entity as TrainingGroupMember;
 // pub use super::turn::Entity as Turn;
+// pub use super::used_token::Entity as UsedToken;
 // pub use super::user::Entity as User;
+// pub use super::user_totp::Entity as UserTotp;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.2
 
+use super::sea_orm_active_enums::{Event, SessionStatus};
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -8,14 +9,19 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub session_id: i32,
     pub user_id: i32,
-    #[sea_orm(column_type = "custom(\"enum_text\")")]
-    pub event_id: String,
+    pub event_id: Event,
     pub time_start: DateTime,
     pub summary: String,
+    pub status: SessionStatus,
+    pub time_end: Option<DateTime>,
+    pub last_activity_at: Option<DateTime>,
+    pub plan_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::comment::Entity")]
+    Comment,
     #[sea_orm(has_many = "super::turn::Entity")]
     Turn,
     #[sea_orm(
@@ -28,6 +34,12 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comment.def()
+    }
+}
+
 impl Related<super::turn::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Turn.def()
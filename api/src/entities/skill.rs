@@ -1,5 +1,8 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.2
 
+use super::sea_orm_active_enums::Direction;
+use super::sea_orm_active_enums::Event;
+use super::sea_orm_active_enums::Position;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -8,16 +11,18 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub skill_id: i32,
     pub turn_id: i32,
-    #[sea_orm(column_type = "custom(\"enum_text\")")]
-    pub event_id: String,
+    pub event_id: Event,
     pub skill_num: i32,
     pub fig_rep: i32,
-    #[sea_orm(column_type = "custom(\"enum_text\")")]
-    pub direction: String,
-    #[sea_orm(column_type = "custom(\"enum_text\")")]
-    pub position: String,
+    pub direction: Direction,
+    pub position: Position,
     #[sea_orm(column_type = "Float")]
     pub difficulty: f32,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub deduction: Option<f32>,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub time_of_flight: Option<f32>,
+    pub landing_zone: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.2
 
+use super::sea_orm_active_enums::Event;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -10,10 +11,21 @@ pub struct Model {
     pub session_id: i32,
     pub user_id: i32,
     pub note: String,
-    #[sea_orm(column_type = "custom(\"enum_text\")")]
-    pub event_id: String,
+    pub event_id: Event,
     #[sea_orm(column_type = "Float")]
     pub total_difficulty: f32,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub landing_deduction: Option<f32>,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub execution_score: Option<f32>,
+    #[sea_orm(column_type = "Float")]
+    pub total_score: f32,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub time_of_flight: Option<f32>,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub horizontal_score: Option<f32>,
+    #[sea_orm(column_type = "Float")]
+    pub penalty: f32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -28,6 +40,8 @@ pub enum Relation {
     Session,
     #[sea_orm(has_many = "super::skill::Entity")]
     Skill,
+    #[sea_orm(has_one = "super::sync_turn::Entity")]
+    SyncTurn,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -50,6 +64,12 @@ impl Related<super::skill::Entity> for Entity {
     }
 }
 
+impl Related<super::sync_turn::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SyncTurn.def()
+    }
+}
+
 impl Related<super::user::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::User.def()
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.2
 
+use super::sea_orm_active_enums::UserType;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -11,20 +12,32 @@ pub struct Model {
     pub name_last: String,
     pub email: String,
     pub password: String,
-    #[sea_orm(column_type = "custom(\"enum_text\")")]
-    pub user_type: String,
+    pub user_type: UserType,
+    pub is_admin: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::account_lockout::Entity")]
+    AccountLockout,
     #[sea_orm(has_many = "super::club::Entity")]
     Club,
     #[sea_orm(has_many = "super::club_member::Entity")]
     ClubMember,
+    #[sea_orm(has_many = "super::recovery_code::Entity")]
+    RecoveryCode,
     #[sea_orm(has_many = "super::session::Entity")]
     Session,
     #[sea_orm(has_many = "super::turn::Entity")]
     Turn,
+    #[sea_orm(has_one = "super::user_totp::Entity")]
+    UserTotp,
+}
+
+impl Related<super::account_lockout::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AccountLockout.def()
+    }
 }
 
 impl Related<super::club::Entity> for Entity {
@@ -39,6 +52,12 @@ impl Related<super::club_member::Entity> for Entity {
     }
 }
 
+impl Related<super::recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCode.def()
+    }
+}
+
 impl Related<super::session::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Session.def()
@@ -51,4 +70,10 @@ impl Related<super::turn::Entity> for Entity {
     }
 }
 
+impl Related<super::user_totp::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserTotp.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
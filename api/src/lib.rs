@@ -0,0 +1,7 @@
+// The server as a library, so integration tests can build the same app the binary runs
+pub mod entities;
+pub mod routes;
+pub mod utils;
+
+#[cfg(not(any(feature = "sqlite", feature = "postgres")))]
+compile_error!("Enable the sqlite or postgres feature to pick a database backend");
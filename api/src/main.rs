@@ -1,11 +1,29 @@
-use actix_web::{middleware::Logger, web, App, HttpResponse, HttpServer, Responder};
+use actix_web::{middleware::from_fn, web, App, HttpResponse, HttpServer, Responder};
+use api::{
+    routes::{
+        self,
+        services::{login_attempt_service, session_service, two_factor_service},
+    },
+    utils::{
+        app_state::AppState,
+        config::{Config, LogFormat, RateLimitBackend},
+        jobs::JobRunner,
+        metrics::Metrics,
+        migrations::{self, MigrationMode},
+        rate_limiter::{DatabaseStore, MemoryStore, RateLimitStore, RateLimiter},
+        signing_keys::KeyStore,
+    },
+};
 use sea_orm::{Database, DatabaseConnection};
 use serde::{Deserialize, Serialize};
-use utils::app_state::AppState;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
 
-mod entities;
-mod routes;
-mod utils;
+// Login attempts only count within the attempt window and used challenges expire within
+// minutes, so pruning hourly is plenty
+const LOGIN_PRUNE_INTERVAL_SECONDS: u64 = 60 * 60;
+// Idle sessions are closed within a minute of passing the idle period
+const SESSION_SWEEP_INTERVAL_SECONDS: u64 = 60;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct MainError {
@@ -22,32 +40,142 @@ impl Responder for MainError {
 
 #[actix_web::main]
 async fn main() -> Result<(), MainError> {
-    // Setting up logger
-    if std::env::var_os("RUST_LOG").is_none() {
-        std::env::set_var("RUST_LOG", "actix_web=info");
-    }
-
-    // Init logger
-    dotenv::dotenv().ok();
-    env_logger::init();
+    // Load and validate every setting before starting anything
+    let config = Config::load().map_err(|err| MainError {
+        message: err.to_string(),
+    })?;
 
-    // Get env variables
-    let port = utils::constants::PORT.clone();
-    let address = utils::constants::ADDRESS.clone();
-    let db_url = utils::constants::DATABASE_URL.clone();
+    // Init logger, RUST_LOG overrides the default levels
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("actix_web=info,actix_server=info,api=info"));
+    match config.log_format {
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_current_span(true)
+            .with_span_list(true)
+            .init(),
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).init(),
+    }
+    tracing::info!("Loaded configuration: {:?}", config);
 
-    // Establish database connection
-    let db: DatabaseConnection = Database::connect(db_url).await.map_err(|err| MainError {
+    let metrics = Metrics::new().map_err(|err| MainError {
         message: err.to_string(),
     })?;
 
-    // Booting up web server
+    // Establish database connection
+    let mut db: DatabaseConnection =
+        Database::connect(&config.database_url)
+            .await
+            .map_err(|err| MainError {
+                message: err.to_string(),
+            })?;
+    metrics.instrument(&mut db);
+
+    // Bring the schema up to date, or make sure it already is
+    let migration_mode = if std::env::args().any(|arg| arg == "--check-migrations") {
+        MigrationMode::Check
+    } else if config.run_migrations {
+        MigrationMode::Apply
+    } else {
+        MigrationMode::Skip
+    };
+    migrations::run(&db, migration_mode)
+        .await
+        .map_err(|err| MainError {
+            message: err.to_string(),
+        })?;
+
+    // Pick where rate limit buckets are stored
+    let rate_limit_store: Box<dyn RateLimitStore> = match config.rate_limit_backend {
+        RateLimitBackend::Database => Box::new(DatabaseStore { db: db.clone() }),
+        RateLimitBackend::Memory => Box::new(MemoryStore::default()),
+    };
+
+    // Load the token signing keys, creating the first one on a fresh database
+    let signing_keys = KeyStore::load(&db, &config)
+        .await
+        .map_err(|err| MainError {
+            message: err.to_string(),
+        })?;
+
+    let address = config.address.clone();
+    let port = config.port;
+    let shutdown_timeout = config.shutdown_timeout_seconds;
+    let rotation_interval = config.signing_keys.rotation_check_minutes * 60;
+
+    // Shared between workers, so rate limits apply across the whole server
+    let app_state = web::Data::new(AppState {
+        rate_limiter: RateLimiter {
+            store: rate_limit_store,
+            policies: config.rate_limit_policies.clone(),
+        },
+        config,
+        db,
+        jobs: JobRunner::default(),
+        metrics,
+        signing_keys,
+    });
+
+    two_factor_service::encrypt_stored_secrets(&app_state)
+        .await
+        .map_err(|err| MainError {
+            message: err.to_string(),
+        })?;
+
+    // Periodic background work, stopped once the server has shut down
+    let job_state = app_state.clone();
+    app_state.jobs.register(
+        "rotate-signing-keys",
+        Duration::from_secs(rotation_interval),
+        move || {
+            let state = job_state.clone();
+            async move { state.signing_keys.rotate_if_due(&state.db).await }
+        },
+    );
+    let job_state = app_state.clone();
+    app_state.jobs.register(
+        "prune-login-attempts",
+        Duration::from_secs(LOGIN_PRUNE_INTERVAL_SECONDS),
+        move || {
+            let state = job_state.clone();
+            async move { login_attempt_service::prune_attempts(&state).await }
+        },
+    );
+    let job_state = app_state.clone();
+    app_state.jobs.register(
+        "prune-used-tokens",
+        Duration::from_secs(LOGIN_PRUNE_INTERVAL_SECONDS),
+        move || {
+            let state = job_state.clone();
+            async move { two_factor_service::prune_used_tokens(&state).await }
+        },
+    );
+    let job_state = app_state.clone();
+    app_state.jobs.register(
+        "close-idle-sessions",
+        Duration::from_secs(SESSION_SWEEP_INTERVAL_SECONDS),
+        move || {
+            let state = job_state.clone();
+            async move { session_service::close_idle_sessions(&state).await }
+        },
+    );
+
+    // Booting up web server. On SIGTERM or SIGINT it stops accepting connections and gives
+    // in-flight requests the shutdown timeout to finish
+    let server_state = app_state.clone();
     HttpServer::new(move || {
         App::new()
-            .app_data(web::Data::new(AppState { db: db.clone() }))
-            .wrap(Logger::default()) // Logger middleware
+            .app_data(server_state.clone())
+            .wrap(from_fn(
+                routes::middleware::metrics_middleware::record_metrics,
+            ))
+            .wrap(from_fn(
+                routes::middleware::request_middleware::trace_request,
+            )) // Request ids and logging
             .configure(routes::config) // Configure routes
     })
+    .shutdown_timeout(shutdown_timeout)
     .bind((address, port))
     .map_err(|err| MainError {
         message: err.to_string(),
@@ -56,5 +184,15 @@ async fn main() -> Result<(), MainError> {
     .await
     .map_err(|err| MainError {
         message: err.to_string(),
-    })
+    })?;
+
+    // Requests have drained, so stop the jobs and only then close the pool they use
+    tracing::info!("Server stopped, shutting down background jobs");
+    app_state.jobs.shutdown().await;
+    app_state.db.close_by_ref().await.map_err(|err| MainError {
+        message: err.to_string(),
+    })?;
+    tracing::info!("Shutdown complete");
+
+    Ok(())
 }
@@ -0,0 +1,14 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/admin")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("admin", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::admin_controller::get_lockouts)
+            .service(controllers::admin_controller::clear_lockout),
+    );
+}
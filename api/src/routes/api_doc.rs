@@ -0,0 +1,293 @@
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use super::controllers::{
+    admin_controller, analytics_controller, auth_controller, club_controller, comment_controller,
+    docs_controller, goal_controller, group_controller, health_controller, metrics_controller,
+    plan_controller, session_controller, skill_controller, sync_pair_controller, turn_controller,
+    two_factor_controller, user_controller, well_known_controller,
+};
+
+// Builds ApiDoc from the listed handlers, and keeps the same controller::handler list around so
+// the tests can check it against the routes
+macro_rules! api_doc {
+    ($($controller:ident::$handler:ident),* $(,)?) => {
+        #[derive(OpenApi)]
+        #[openapi(
+            info(title = "Bounce API", description = "Trampoline & Tumbling training logger"),
+            paths($($controller::$handler),*),
+            modifiers(&BearerToken)
+        )]
+        pub struct ApiDoc;
+
+        #[cfg(test)]
+        const DOCUMENTED_HANDLERS: &[(&str, &str)] =
+            &[$((stringify!($controller), stringify!($handler))),*];
+    };
+}
+
+// The OpenAPI document, built from the handlers' #[utoipa::path] attributes. Every handler
+// registered in a *_routes.rs file has to be listed here
+api_doc!(
+    auth_controller::register_athlete,
+    auth_controller::login,
+    auth_controller::verify_login,
+    user_controller::get_user,
+    user_controller::get_user_club,
+    user_controller::get_personal_bests,
+    user_controller::update,
+    user_controller::reset_password,
+    club_controller::get_club,
+    club_controller::create_club,
+    club_controller::leave_club,
+    club_controller::join_club,
+    club_controller::delete_club,
+    club_controller::transfer_ownership,
+    club_controller::require_two_factor,
+    session_controller::start_session,
+    session_controller::get_sessions_by_athlete,
+    session_controller::get_session,
+    session_controller::end_session,
+    turn_controller::create_turn,
+    turn_controller::get_turns_by_session,
+    turn_controller::get_turns_by_athlete,
+    turn_controller::get_turn,
+    turn_controller::score_turn,
+    turn_controller::delete_turn,
+    skill_controller::get_catalogue,
+    skill_controller::create_catalogue_skill,
+    skill_controller::delete_catalogue_skill,
+    skill_controller::get_repertoire,
+    comment_controller::create_comment,
+    comment_controller::get_comments_by_session,
+    comment_controller::mark_session_read,
+    comment_controller::get_unread,
+    comment_controller::edit_comment,
+    comment_controller::delete_comment,
+    plan_controller::create_plan,
+    plan_controller::assign_plan,
+    plan_controller::get_plans_by_athlete,
+    plan_controller::get_plans_by_club,
+    plan_controller::get_plan,
+    plan_controller::delete_plan,
+    group_controller::create_group,
+    group_controller::set_members,
+    group_controller::get_groups_by_club,
+    group_controller::delete_group,
+    goal_controller::create_goal,
+    goal_controller::get_goals_by_athlete,
+    goal_controller::get_goal,
+    goal_controller::delete_goal,
+    sync_pair_controller::create_sync_pair,
+    sync_pair_controller::accept_sync_pair,
+    sync_pair_controller::get_sync_pairs_by_athlete,
+    sync_pair_controller::delete_sync_pair,
+    analytics_controller::get_athlete_analytics,
+    analytics_controller::get_club_analytics,
+    admin_controller::get_lockouts,
+    admin_controller::clear_lockout,
+    two_factor_controller::enroll,
+    two_factor_controller::confirm_enrollment,
+    two_factor_controller::disable,
+    two_factor_controller::regenerate_recovery_codes,
+    well_known_controller::get_jwks,
+    health_controller::healthz,
+    health_controller::readyz,
+    health_controller::version,
+    metrics_controller::get_metrics,
+    docs_controller::get_openapi,
+);
+
+// Login tokens are sent as `Authorization: Bearer <token>`
+struct BearerToken;
+
+impl Modify for BearerToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(
+                Http::builder()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs, path::Path};
+
+    use actix_web::{
+        dev::Service,
+        http::Method,
+        test::{init_service, TestRequest},
+        App,
+    };
+
+    use super::*;
+    use crate::routes;
+
+    // Handlers passed to .service(...) in the *_routes.rs files, as controller::function pairs
+    fn registered_handlers() -> HashSet<(String, String)> {
+        let routes_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/routes");
+        let mut handlers = HashSet::new();
+
+        for entry in fs::read_dir(routes_dir).unwrap() {
+            let path = entry.unwrap().path();
+            let is_routes_file = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with("_routes.rs"));
+            if !is_routes_file {
+                continue;
+            }
+
+            let source = fs::read_to_string(&path).unwrap();
+            for (_, rest) in source
+                .match_indices("controllers::")
+                .map(|(i, _)| source.split_at(i))
+            {
+                let mut parts = rest["controllers::".len()..]
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+                    .next()
+                    .unwrap()
+                    .split("::");
+                if let (Some(controller), Some(handler)) = (parts.next(), parts.next()) {
+                    handlers.insert((controller.to_string(), handler.to_string()));
+                }
+            }
+        }
+
+        handlers
+    }
+
+    fn documented_handlers() -> HashSet<(String, String)> {
+        DOCUMENTED_HANDLERS
+            .iter()
+            .map(|(controller, handler)| (controller.to_string(), handler.to_string()))
+            .collect()
+    }
+
+    fn describe(handlers: HashSet<&(String, String)>) -> String {
+        let mut names = handlers
+            .into_iter()
+            .map(|(controller, handler)| format!("{}::{}", controller, handler))
+            .collect::<Vec<String>>();
+        names.sort();
+        names.join(", ")
+    }
+
+    // Compared as controller::handler pairs, since several controllers share handler names
+    #[test]
+    fn every_registered_route_is_documented() {
+        let registered = registered_handlers();
+        let documented = documented_handlers();
+        assert!(!registered.is_empty(), "No registered handlers were found");
+
+        let missing = registered.difference(&documented).collect::<HashSet<_>>();
+        assert!(
+            missing.is_empty(),
+            "Registered handlers missing from the OpenAPI document: {}",
+            describe(missing)
+        );
+
+        let unregistered = documented.difference(&registered).collect::<HashSet<_>>();
+        assert!(
+            unregistered.is_empty(),
+            "Documented handlers not registered in any *_routes.rs file: {}",
+            describe(unregistered)
+        );
+    }
+
+    #[test]
+    fn every_documented_handler_has_an_operation() {
+        let spec = ApiDoc::openapi();
+        let operations = spec
+            .paths
+            .paths
+            .values()
+            .map(|item| {
+                [&item.get, &item.post, &item.put, &item.delete, &item.patch]
+                    .into_iter()
+                    .flatten()
+                    .count()
+            })
+            .sum::<usize>();
+
+        assert_eq!(operations, DOCUMENTED_HANDLERS.len());
+    }
+
+    // The only JSON bodies; everything else is ApiResponse text
+    #[test]
+    fn json_responses_have_schemas() {
+        let spec = ApiDoc::openapi();
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        assert!(schemas.contains_key("JwkSet"));
+        assert!(schemas.contains_key("PublicJwk"));
+
+        for path in ["/.well-known/jwks.json", "/openapi.json"] {
+            let operation = spec.paths.paths[path].get.as_ref().unwrap();
+            let response = match &operation.responses.responses["200"] {
+                utoipa::openapi::RefOr::T(response) => response,
+                utoipa::openapi::RefOr::Ref(_) => panic!("{} response is a reference", path),
+            };
+            assert!(
+                response.content["application/json"].schema.is_some(),
+                "{} has no response schema",
+                path
+            );
+        }
+    }
+
+    // Unmatched routes 404 before any handler or middleware runs, so anything else means the
+    // documented path and method exist
+    #[actix_web::test]
+    async fn every_documented_route_is_registered() {
+        let app = init_service(App::new().configure(routes::config)).await;
+        let spec = ApiDoc::openapi();
+
+        for (path, item) in &spec.paths.paths {
+            let uri = path
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with('{') {
+                        "1"
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<&str>>()
+                .join("/");
+
+            let methods = [
+                (Method::GET, &item.get),
+                (Method::POST, &item.post),
+                (Method::PUT, &item.put),
+                (Method::DELETE, &item.delete),
+                (Method::PATCH, &item.patch),
+            ];
+
+            for (method, _) in methods.iter().filter(|(_, operation)| operation.is_some()) {
+                let req = TestRequest::default()
+                    .method(method.clone())
+                    .uri(&uri)
+                    .to_request();
+                let status = match app.call(req).await {
+                    Ok(res) => res.status(),
+                    Err(err) => err.as_response_error().status_code(),
+                };
+
+                assert_ne!(
+                    status, 404,
+                    "{} {} is documented but not registered",
+                    method, path
+                );
+            }
+        }
+    }
+}
@@ -1,10 +1,14 @@
-use super::controllers;
-use actix_web::web;
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
 
 pub fn config(config: &mut web::ServiceConfig) {
     config.service(
         web::scope("/auth")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("auth", req, next)
+            }))
             .service(controllers::auth_controller::login)
+            .service(controllers::auth_controller::verify_login)
             .service(controllers::auth_controller::register_athlete),
     );
 }
@@ -4,12 +4,16 @@ use actix_web::{middleware::from_fn, web};
 pub fn config(config: &mut web::ServiceConfig) {
     config.service(
         web::scope("/club")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("club", req, next)
+            }))
             .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
             .service(controllers::club_controller::get_club)
             .service(controllers::club_controller::create_club)
             .service(controllers::club_controller::leave_club)
             .service(controllers::club_controller::join_club)
             .service(controllers::club_controller::delete_club)
-            .service(controllers::club_controller::transfer_ownership),
+            .service(controllers::club_controller::transfer_ownership)
+            .service(controllers::club_controller::require_two_factor),
     );
 }
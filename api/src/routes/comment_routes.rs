@@ -0,0 +1,18 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/comment")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("comment", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::comment_controller::create_comment)
+            .service(controllers::comment_controller::get_comments_by_session)
+            .service(controllers::comment_controller::mark_session_read)
+            .service(controllers::comment_controller::get_unread)
+            .service(controllers::comment_controller::edit_comment)
+            .service(controllers::comment_controller::delete_comment),
+    );
+}
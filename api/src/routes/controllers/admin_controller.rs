@@ -0,0 +1,50 @@
+use actix_web::{get, post, web};
+
+use crate::{
+    routes::services::admin_service,
+    utils::{
+        api_response::ApiResponse, app_state, jwt::Claims,
+        request_models::admin_models::LockoutQueryModel,
+    },
+};
+
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    params(LockoutQueryModel),
+    responses(
+        (status = 200, description = "Account and IP address lockouts, newest first"),
+        (status = 403, description = "The caller is not an admin"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/lockouts")]
+pub async fn get_lockouts(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    query: web::Query<LockoutQueryModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let active_only = query.active.unwrap_or(false);
+    admin_service::get_lockouts(&app_state, claim_data, active_only).await
+}
+
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    params(("lockout_id" = i32, Path, description = "The lockout to clear")),
+    responses(
+        (status = 200, description = "The lockout was cleared"),
+        (status = 403, description = "The caller is not an admin"),
+        (status = 404, description = "Lockout not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/lockouts/{lockout_id}/clear")]
+pub async fn clear_lockout(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let lockout_id = path.into_inner();
+    admin_service::clear_lockout(&app_state, claim_data, lockout_id).await
+}
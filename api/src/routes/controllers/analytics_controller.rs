@@ -0,0 +1,108 @@
+use actix_web::{get, web};
+use chrono::NaiveDate;
+use sea_orm::ActiveEnum;
+
+use crate::{
+    entities::sea_orm_active_enums::Event,
+    routes::services::analytics_service,
+    utils::{
+        analytics::{Filter, Period},
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::analytics_models::AnalyticsQueryModel,
+    },
+};
+
+#[utoipa::path(
+    context_path = "/analytics",
+    tag = "analytics",
+    params(
+        ("athlete_id" = i32, Path, description = "The athlete whose training to add up"),
+        AnalyticsQueryModel,
+    ),
+    responses(
+        (status = 200, description = "The athlete's training volume per period, oldest first"),
+        (status = 401, description = "Only the athlete and their coaches can view their training"),
+        (status = 422, description = "Invalid period, event or date range"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/athlete/{athlete_id}")]
+pub async fn get_athlete_analytics(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    query: web::Query<AnalyticsQueryModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let filter = parse_filter(&query)?;
+    analytics_service::get_athlete_analytics(&app_state, claim_data, path.into_inner(), filter)
+        .await
+}
+
+#[utoipa::path(
+    context_path = "/analytics",
+    tag = "analytics",
+    params(
+        ("club_id" = i32, Path, description = "The club whose roster's training to add up"),
+        AnalyticsQueryModel,
+    ),
+    responses(
+        (status = 200, description = "The club's athletes' training volume per period, oldest first"),
+        (status = 401, description = "Only the club's coaches can view its training"),
+        (status = 404, description = "Club not found"),
+        (status = 422, description = "Invalid period, event or date range"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/club/{club_id}")]
+pub async fn get_club_analytics(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    query: web::Query<AnalyticsQueryModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let filter = parse_filter(&query)?;
+    analytics_service::get_club_analytics(&app_state, claim_data, path.into_inner(), filter).await
+}
+
+fn parse_filter(query: &AnalyticsQueryModel) -> Result<Filter, ApiResponse> {
+    let period = match &query.period {
+        Some(period) => Period::parse(period).ok_or(ApiResponse::new(
+            422,
+            "Invalid period, must be day, week or month".to_string(),
+        ))?,
+        None => Period::Week,
+    };
+    let event = query
+        .event
+        .as_ref()
+        .map(Event::try_from_value)
+        .transpose()
+        .map_err(|_| ApiResponse::new(422, "Invalid event, must be DMT, TRA or TUM".to_string()))?;
+    let from = parse_date(query.from.as_deref(), "from")?;
+    let to = parse_date(query.to.as_deref(), "to")?;
+
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(ApiResponse::new(
+                422,
+                "The from date must not be after the to date".to_string(),
+            ));
+        }
+    }
+
+    Ok(Filter {
+        period,
+        event,
+        from,
+        to,
+    })
+}
+
+fn parse_date(value: Option<&str>, name: &str) -> Result<Option<NaiveDate>, ApiResponse> {
+    value
+        .map(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|_| ApiResponse::new(422, format!("Invalid {} date, must be YYYY-MM-DD", name)))
+}
@@ -1,15 +1,24 @@
-use actix_web::{post, web};
+use actix_web::{post, web, HttpRequest};
 
 use crate::{
-    routes::services::auth_service,
+    routes::services::{auth_service, two_factor_service},
     utils::{
         api_response::ApiResponse,
         app_state,
-        request_models::auth_models::{LoginModel, RegisterModel},
+        request_models::auth_models::{LoginModel, RegisterModel, TwoFactorLoginModel},
     },
 };
 
-#[post("register")]
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    request_body = RegisterModel,
+    responses(
+        (status = 200, description = "The new user's id"),
+        (status = 409, description = "The email is already registered"),
+    )
+)]
+#[post("/register")]
 pub async fn register_athlete(
     app_state: web::Data<app_state::AppState>,
     json: web::Json<RegisterModel>,
@@ -17,10 +26,48 @@ pub async fn register_athlete(
     auth_service::register(&app_state, json).await
 }
 
-#[post("login")]
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    request_body = LoginModel,
+    responses(
+        (status = 200, description = "A login token, or a challenge token when two-factor is enabled"),
+        (status = 401, description = "Wrong email or password"),
+        (status = 429, description = "The account or IP address is locked out"),
+    )
+)]
+#[post("/login")]
 pub async fn login(
     app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
     json: web::Json<LoginModel>,
 ) -> Result<ApiResponse, ApiResponse> {
-    auth_service::login_user(&app_state, json).await
+    auth_service::login_user(&app_state, json, client_ip(&req)).await
+}
+
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    request_body = TwoFactorLoginModel,
+    responses(
+        (status = 200, description = "A login token"),
+        (status = 401, description = "The challenge token or code is invalid"),
+    )
+)]
+#[post("/login/verify")]
+pub async fn verify_login(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    json: web::Json<TwoFactorLoginModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let challenge_token = json.challenge_token.clone();
+    let code = json.code.clone();
+    two_factor_service::verify_login(&app_state, challenge_token, code, client_ip(&req)).await
+}
+
+// Use the socket address, as forwarding headers can be spoofed by the client
+fn client_ip(req: &HttpRequest) -> String {
+    req.peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
 }
@@ -6,22 +6,27 @@ use crate::{
         api_response::ApiResponse,
         app_state,
         jwt::Claims,
-        request_models::club_models::{ClubModel, TransferOwnerModel},
+        request_models::club_models::{ClubModel, RequireTwoFactorModel, TransferOwnerModel},
     },
 };
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    params(("club_id" = i32, Path, description = "The club to look up")),
+    responses(
+        (status = 200, description = "The club"),
+        (status = 404, description = "Club not found"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[get("/{club_id}")]
 pub async fn get_club(
     app_state: web::Data<app_state::AppState>,
     path: web::Path<i32>,
 ) -> Result<ApiResponse, ApiResponse> {
     let club_id = path.into_inner();
-    let club = club_service::get_club_by_id(&app_state, club_id).await;
-
-    if club.is_err() {
-        return Err(club.unwrap_err());
-    }
-    let club = club.unwrap();
+    let club = club_service::get_club_by_id(&app_state.db, club_id).await?;
 
     Ok(ApiResponse::new(
         200,
@@ -32,6 +37,17 @@ pub async fn get_club(
     ))
 }
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    request_body = ClubModel,
+    responses(
+        (status = 200, description = "The new club, owned by the caller"),
+        (status = 401, description = "Only coaches can create clubs"),
+        (status = 409, description = "The name is taken or the caller is already in a club"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[post("/create")]
 pub async fn create_club(
     app_state: web::Data<app_state::AppState>,
@@ -41,6 +57,16 @@ pub async fn create_club(
     club_service::create_club(&app_state, claim_data, json.name.clone()).await
 }
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    params(("club_id" = i32, Path, description = "The club to leave")),
+    responses(
+        (status = 200, description = "Left the club"),
+        (status = 404, description = "The caller is not in a club"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[post("/{club_id}/leave")]
 pub async fn leave_club(
     app_state: web::Data<app_state::AppState>,
@@ -49,6 +75,17 @@ pub async fn leave_club(
     club_member_service::leave_club(&app_state, claim_data).await
 }
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    params(("club_id" = i32, Path, description = "The club to join")),
+    responses(
+        (status = 200, description = "The new membership"),
+        (status = 404, description = "Club not found"),
+        (status = 409, description = "The caller is already in a club"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[post("/{club_id}/join")]
 pub async fn join_club(
     app_state: web::Data<app_state::AppState>,
@@ -57,33 +94,30 @@ pub async fn join_club(
 ) -> Result<ApiResponse, ApiResponse> {
     let club_id = path.into_inner();
     // Get the club
-    let club_result = club_service::get_club_by_id(&app_state, club_id).await;
-
-    // Error handling and formatting
-    if club_result.is_err() {
-        return Err(club_result.unwrap_err());
-    }
-    let club = club_result.unwrap();
+    let club = club_service::get_club_by_id(&app_state.db, club_id).await?;
 
     // Create the membership
-    let join_result =
-        club_member_service::create_membership(&app_state, claim_data, club.club_id).await;
-
-    // Error handling and formatting
-    if join_result.is_err() {
-        return Err(join_result.unwrap_err());
-    }
-    let membership = join_result.unwrap();
+    let membership =
+        club_member_service::create_membership(&app_state.db, claim_data, club.club_id).await?;
 
-    return Ok(ApiResponse::new(
+    Ok(ApiResponse::new(
         200,
         format!(
             "{{ 'club_member_id': {}, 'user_id': {}, 'club_id': {}",
             membership.club_member_id, membership.user_id, membership.club_id
         ),
-    ));
+    ))
 }
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    responses(
+        (status = 200, description = "The caller's club was deleted"),
+        (status = 401, description = "The caller does not own a club"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[delete("/delete")]
 pub async fn delete_club(
     app_state: web::Data<app_state::AppState>,
@@ -92,6 +126,17 @@ pub async fn delete_club(
     club_service::delete_club(&app_state, claim_data).await
 }
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    request_body = TransferOwnerModel,
+    responses(
+        (status = 200, description = "Ownership moved to the new owner"),
+        (status = 401, description = "The caller does not own a club"),
+        (status = 404, description = "The new owner was not found"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[put("/transfer")]
 pub async fn transfer_ownership(
     app_state: web::Data<app_state::AppState>,
@@ -102,6 +147,25 @@ pub async fn transfer_ownership(
     club_service::transfer_ownership(&app_state, claim_data, new_owner_id).await
 }
 
+#[utoipa::path(
+    context_path = "/club",
+    tag = "club",
+    request_body = RequireTwoFactorModel,
+    responses(
+        (status = 200, description = "The club's two-factor requirement was updated"),
+        (status = 401, description = "The caller does not own a club"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[put("/require-two-factor")]
+pub async fn require_two_factor(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<RequireTwoFactorModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    club_service::set_require_two_factor(&app_state, claim_data, json.required).await
+}
+
 // TODO: Get a list of all athletes in the users club
 // #[get("athletes")]
 // pub async fn get_club_athletes(
@@ -0,0 +1,138 @@
+use actix_web::{delete, get, post, put, web};
+
+use crate::{
+    routes::services::comment_service,
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::comment_models::{CommentModel, EditCommentModel},
+    },
+};
+
+#[utoipa::path(
+    context_path = "/comment",
+    tag = "comment",
+    request_body = CommentModel,
+    responses(
+        (status = 201, description = "The new comment"),
+        (status = 401, description = "Only the athlete and their coaches can comment on their training"),
+        (status = 404, description = "Session, turn, skill or comment not found"),
+        (status = 409, description = "Deleted comments cannot be replied to"),
+        (status = 422, description = "Empty or too long, or not exactly one thing to comment on"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/create")]
+pub async fn create_comment(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<CommentModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    comment_service::create_comment(&app_state, claim_data, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/comment",
+    tag = "comment",
+    params(("session_id" = i32, Path, description = "The session whose comments to list")),
+    responses(
+        (status = 200, description = "Every comment on the session, its turns and their skills, oldest first"),
+        (status = 401, description = "Only the athlete and their coaches can view their training"),
+        (status = 404, description = "Session not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/session/{session_id}")]
+pub async fn get_comments_by_session(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let session_id = path.into_inner();
+    comment_service::get_comments_by_session(&app_state, claim_data, session_id).await
+}
+
+#[utoipa::path(
+    context_path = "/comment",
+    tag = "comment",
+    params(("session_id" = i32, Path, description = "The session whose comments have been read")),
+    responses(
+        (status = 200, description = "The session's comments are marked as read"),
+        (status = 401, description = "Only the athlete and their coaches can view their training"),
+        (status = 404, description = "Session not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/session/{session_id}/read")]
+pub async fn mark_session_read(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let session_id = path.into_inner();
+    comment_service::mark_session_read(&app_state, claim_data, session_id).await
+}
+
+#[utoipa::path(
+    context_path = "/comment",
+    tag = "comment",
+    responses(
+        (status = 200, description = "How many unread comments each session has"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/unread")]
+pub async fn get_unread(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+) -> Result<ApiResponse, ApiResponse> {
+    comment_service::get_unread(&app_state, claim_data).await
+}
+
+#[utoipa::path(
+    context_path = "/comment",
+    tag = "comment",
+    params(("comment_id" = i32, Path, description = "The comment to edit")),
+    request_body = EditCommentModel,
+    responses(
+        (status = 200, description = "The edited comment"),
+        (status = 401, description = "Only the author can change a comment"),
+        (status = 404, description = "Comment not found"),
+        (status = 409, description = "Comment has already been deleted"),
+        (status = 422, description = "Empty or too long"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[put("/{comment_id}")]
+pub async fn edit_comment(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    json: web::Json<EditCommentModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let comment_id = path.into_inner();
+    comment_service::edit_comment(&app_state, claim_data, comment_id, json.into_inner().body).await
+}
+
+#[utoipa::path(
+    context_path = "/comment",
+    tag = "comment",
+    params(("comment_id" = i32, Path, description = "The comment to delete")),
+    responses(
+        (status = 200, description = "The comment's text was removed"),
+        (status = 401, description = "Only the author can change a comment"),
+        (status = 404, description = "Comment not found"),
+        (status = 409, description = "Comment has already been deleted"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/{comment_id}")]
+pub async fn delete_comment(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let comment_id = path.into_inner();
+    comment_service::delete_comment(&app_state, claim_data, comment_id).await
+}
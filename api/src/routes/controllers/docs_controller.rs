@@ -0,0 +1,14 @@
+use actix_web::{get, HttpResponse};
+use utoipa::OpenApi;
+
+use crate::routes::api_doc::ApiDoc;
+
+#[utoipa::path(
+    tag = "docs",
+    responses((status = 200, description = "This OpenAPI document", body = Object, content_type = "application/json"))
+)]
+#[get("/openapi.json")]
+pub async fn get_openapi() -> HttpResponse {
+    // Served as real JSON, so OpenAPI tooling can read it
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
@@ -0,0 +1,109 @@
+use actix_web::{delete, get, post, web};
+
+use crate::{
+    routes::services::goal_service::{self, GoalStatus},
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::goal_models::{GoalModel, GoalQueryModel},
+    },
+};
+
+#[utoipa::path(
+    context_path = "/goal",
+    tag = "goal",
+    request_body = GoalModel,
+    responses(
+        (status = 201, description = "The new goal with the athlete's progress towards it"),
+        (status = 401, description = "Only the athlete and their coaches can set their goals"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Invalid kind, event, target, skill or deadline"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/create")]
+pub async fn create_goal(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<GoalModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    goal_service::create_goal(&app_state, claim_data, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/goal",
+    tag = "goal",
+    params(
+        ("athlete_id" = i32, Path, description = "The athlete whose goals to list"),
+        GoalQueryModel,
+    ),
+    responses(
+        (status = 200, description = "The athlete's goals with their progress, the nearest deadline first"),
+        (status = 401, description = "Only the athlete and their coaches can view their goals"),
+        (status = 422, description = "Invalid status"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/athlete/{athlete_id}")]
+pub async fn get_goals_by_athlete(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    query: web::Query<GoalQueryModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let athlete_id = path.into_inner();
+    let status = query
+        .status
+        .as_deref()
+        .map(|status| {
+            GoalStatus::parse(status).ok_or(ApiResponse::new(
+                422,
+                "Invalid status, must be ACTIVE, COMPLETED or MISSED".to_string(),
+            ))
+        })
+        .transpose()?;
+    goal_service::get_goals_by_athlete(&app_state, claim_data, athlete_id, status).await
+}
+
+#[utoipa::path(
+    context_path = "/goal",
+    tag = "goal",
+    params(("goal_id" = i32, Path, description = "The goal to look up")),
+    responses(
+        (status = 200, description = "The goal with the athlete's progress towards it"),
+        (status = 401, description = "Only the athlete and their coaches can view their goals"),
+        (status = 404, description = "Goal not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/{goal_id}")]
+pub async fn get_goal(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let goal_id = path.into_inner();
+    goal_service::get_goal(&app_state, claim_data, goal_id).await
+}
+
+#[utoipa::path(
+    context_path = "/goal",
+    tag = "goal",
+    params(("goal_id" = i32, Path, description = "The goal to delete")),
+    responses(
+        (status = 200, description = "Goal deleted"),
+        (status = 401, description = "Only the athlete and their coaches can delete their goals"),
+        (status = 404, description = "Goal not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/{goal_id}")]
+pub async fn delete_goal(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let goal_id = path.into_inner();
+    goal_service::delete_goal(&app_state, claim_data, goal_id).await
+}
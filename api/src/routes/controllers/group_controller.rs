@@ -0,0 +1,98 @@
+use actix_web::{delete, get, post, put, web};
+
+use crate::{
+    routes::services::group_service,
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::group_models::{GroupMembersModel, GroupModel},
+    },
+};
+
+#[utoipa::path(
+    context_path = "/group",
+    tag = "group",
+    request_body = GroupModel,
+    responses(
+        (status = 201, description = "The new group in the coach's club, with its athletes"),
+        (status = 401, description = "Only the club's coaches can manage its groups"),
+        (status = 409, description = "A group with that name already exists in the club"),
+        (status = 422, description = "No name, or athletes outside the club"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/create")]
+pub async fn create_group(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<GroupModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    group_service::create_group(&app_state, claim_data, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/group",
+    tag = "group",
+    params(("group_id" = i32, Path, description = "The group whose athletes to replace")),
+    request_body = GroupMembersModel,
+    responses(
+        (status = 200, description = "The group with its new athletes"),
+        (status = 401, description = "Only the club's coaches can manage its groups"),
+        (status = 404, description = "Group not found"),
+        (status = 422, description = "Athletes outside the club"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[put("/{group_id}/members")]
+pub async fn set_members(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    json: web::Json<GroupMembersModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let group_id = path.into_inner();
+    group_service::set_members(&app_state, claim_data, group_id, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/group",
+    tag = "group",
+    params(("club_id" = i32, Path, description = "The club whose groups to list")),
+    responses(
+        (status = 200, description = "The club's groups with their athletes"),
+        (status = 401, description = "Only the club's coaches can manage its groups"),
+        (status = 404, description = "Club not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/club/{club_id}")]
+pub async fn get_groups_by_club(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let club_id = path.into_inner();
+    group_service::get_groups_by_club(&app_state, claim_data, club_id).await
+}
+
+#[utoipa::path(
+    context_path = "/group",
+    tag = "group",
+    params(("group_id" = i32, Path, description = "The group to delete")),
+    responses(
+        (status = 200, description = "Group deleted, plans assigned through it are kept"),
+        (status = 401, description = "Only the club's coaches can manage its groups"),
+        (status = 404, description = "Group not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/{group_id}")]
+pub async fn delete_group(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let group_id = path.into_inner();
+    group_service::delete_group(&app_state, claim_data, group_id).await
+}
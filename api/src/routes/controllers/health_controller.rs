@@ -0,0 +1,52 @@
+use actix_web::{get, web};
+
+use crate::{
+    routes::services::health_service,
+    utils::{api_response::ApiResponse, app_state},
+};
+
+// Liveness, answers as long as the server is running
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "The server is running"))
+)]
+#[get("/healthz")]
+pub async fn healthz() -> ApiResponse {
+    ApiResponse::new(200, "{ 'status': 'ok' }".to_string())
+}
+
+// Readiness, fails while the database is unreachable or behind on migrations
+#[utoipa::path(
+    tag = "health",
+    responses(
+        (status = 200, description = "The server can handle requests"),
+        (status = 503, description = "The database is unreachable or migrations are pending"),
+    )
+)]
+#[get("/readyz")]
+pub async fn readyz(app_state: web::Data<app_state::AppState>) -> Result<ApiResponse, ApiResponse> {
+    health_service::check_ready(&app_state).await?;
+
+    Ok(ApiResponse::new(200, "{ 'status': 'ready' }".to_string()))
+}
+
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "Crate version, git commit and schema version"))
+)]
+#[get("/version")]
+pub async fn version(
+    app_state: web::Data<app_state::AppState>,
+) -> Result<ApiResponse, ApiResponse> {
+    let schema_version = health_service::schema_version(&app_state).await?;
+
+    Ok(ApiResponse::new(
+        200,
+        format!(
+            "{{ 'version': {}, 'git_sha': {}, 'schema_version': {} }}",
+            env!("CARGO_PKG_VERSION"),
+            env!("GIT_SHA"),
+            schema_version.unwrap_or("null".to_string())
+        ),
+    ))
+}
@@ -0,0 +1,22 @@
+use actix_web::{get, web, HttpResponse};
+
+use crate::utils::{api_response::ApiResponse, app_state};
+
+// Scraped by Prometheus, so it is served in its text format rather than our JSON-like bodies
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "Metrics in the Prometheus text format", content_type = "text/plain"))
+)]
+#[get("/metrics")]
+pub async fn get_metrics(
+    app_state: web::Data<app_state::AppState>,
+) -> Result<HttpResponse, ApiResponse> {
+    let body = app_state
+        .metrics
+        .render(&app_state.db)
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(body))
+}
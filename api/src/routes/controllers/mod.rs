@@ -1,6 +1,18 @@
+pub mod admin_controller;
+pub mod analytics_controller;
 pub mod auth_controller;
 pub mod club_controller;
+pub mod comment_controller;
+pub mod docs_controller;
+pub mod goal_controller;
+pub mod group_controller;
+pub mod health_controller;
+pub mod metrics_controller;
+pub mod plan_controller;
 pub mod session_controller;
 pub mod skill_controller;
+pub mod sync_pair_controller;
 pub mod turn_controller;
+pub mod two_factor_controller;
 pub mod user_controller;
+pub mod well_known_controller;
@@ -0,0 +1,138 @@
+use actix_web::{delete, get, post, web};
+
+use crate::{
+    routes::services::plan_service,
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::plan_models::{AssignPlanModel, PlanModel},
+    },
+};
+
+#[utoipa::path(
+    context_path = "/plan",
+    tag = "plan",
+    request_body = PlanModel,
+    responses(
+        (status = 201, description = "The new plan for the coach's club"),
+        (status = 401, description = "Only coaches in a club can create plans"),
+        (status = 422, description = "Invalid event, date, name or items"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/create")]
+pub async fn create_plan(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<PlanModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    plan_service::create_plan(&app_state, claim_data, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/plan",
+    tag = "plan",
+    params(("plan_id" = i32, Path, description = "The plan to assign")),
+    request_body = AssignPlanModel,
+    responses(
+        (status = 200, description = "The plan with the progress of every athlete it is assigned to"),
+        (status = 401, description = "Only the club's coaches can manage its plans"),
+        (status = 404, description = "Plan or group not found"),
+        (status = 422, description = "No athletes, or athletes or groups outside the club"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/{plan_id}/assign")]
+pub async fn assign_plan(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    json: web::Json<AssignPlanModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let plan_id = path.into_inner();
+    plan_service::assign_plan(&app_state, claim_data, plan_id, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/plan",
+    tag = "plan",
+    params(("athlete_id" = i32, Path, description = "The athlete whose plans to list")),
+    responses(
+        (status = 200, description = "The plans assigned to the athlete with their progress, the latest first"),
+        (status = 401, description = "Only the athlete and their coaches can view their plans"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/athlete/{athlete_id}")]
+pub async fn get_plans_by_athlete(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let athlete_id = path.into_inner();
+    plan_service::get_plans_by_athlete(&app_state, claim_data, athlete_id).await
+}
+
+#[utoipa::path(
+    context_path = "/plan",
+    tag = "plan",
+    params(("club_id" = i32, Path, description = "The club whose plans to list")),
+    responses(
+        (status = 200, description = "The club's plans with each athlete's progress, the latest first"),
+        (status = 401, description = "Only the club's coaches can manage its plans"),
+        (status = 404, description = "Club not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/club/{club_id}")]
+pub async fn get_plans_by_club(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let club_id = path.into_inner();
+    plan_service::get_plans_by_club(&app_state, claim_data, club_id).await
+}
+
+#[utoipa::path(
+    context_path = "/plan",
+    tag = "plan",
+    params(("plan_id" = i32, Path, description = "The plan to look up")),
+    responses(
+        (status = 200, description = "The plan, with every athlete's progress for coaches and their own for athletes"),
+        (status = 401, description = "Only the club's coaches and the plan's athletes can view it"),
+        (status = 404, description = "Plan not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/{plan_id}")]
+pub async fn get_plan(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let plan_id = path.into_inner();
+    plan_service::get_plan(&app_state, claim_data, plan_id).await
+}
+
+#[utoipa::path(
+    context_path = "/plan",
+    tag = "plan",
+    params(("plan_id" = i32, Path, description = "The plan to delete")),
+    responses(
+        (status = 200, description = "Plan deleted, its sessions are kept"),
+        (status = 401, description = "Only the club's coaches can manage its plans"),
+        (status = 404, description = "Plan not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/{plan_id}")]
+pub async fn delete_plan(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let plan_id = path.into_inner();
+    plan_service::delete_plan(&app_state, claim_data, plan_id).await
+}
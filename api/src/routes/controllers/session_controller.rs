@@ -1,38 +1,106 @@
-use actix_web::{get, web};
-use sea_orm::EntityTrait;
+use actix_web::{get, post, web};
+use sea_orm::ActiveEnum;
 
 use crate::{
-    entities,
-    utils::{api_response::ApiResponse, app_state, jwt::Claims},
+    entities::sea_orm_active_enums::Event,
+    routes::services::session_service,
+    utils::{
+        api_response::ApiResponse, app_state, jwt::Claims,
+        request_models::session_models::StartSessionModel,
+    },
 };
 
-/*
+#[utoipa::path(
+    context_path = "/session",
+    tag = "session",
+    request_body = StartSessionModel,
+    responses(
+        (status = 201, description = "The new open session"),
+        (status = 401, description = "Only athletes can start sessions, and only against plans assigned to them"),
+        (status = 404, description = "Plan not found"),
+        (status = 409, description = "The athlete already has an open session"),
+        (status = 422, description = "Invalid event, or not the plan's event"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/start")]
+pub async fn start_session(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<StartSessionModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let event = Event::try_from_value(&json.event)
+        .map_err(|_| ApiResponse::new(422, "Invalid event, must be DMT, TRA or TUM".to_string()))?;
 
-Requirements:
-    - Get all sessions by athlete_id (only athlete and coaches)
-    - Get a specific session for an athlete (only athlete and coaches)
-    - Create a new session (only athlete)
-    - End a session (Maybe after 30 minutes of inactivity??)
-*/
+    session_service::start_session(
+        &app_state,
+        claim_data,
+        event,
+        json.summary.clone(),
+        json.plan_id,
+    )
+    .await
+}
 
-#[get("/{athlete_id}")]
+#[utoipa::path(
+    context_path = "/session",
+    tag = "session",
+    params(("athlete_id" = i32, Path, description = "The athlete whose sessions to list")),
+    responses(
+        (status = 200, description = "The athlete's sessions, most recent first"),
+        (status = 401, description = "Only the athlete and their coaches can view their sessions"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/athlete/{athlete_id}")]
 pub async fn get_sessions_by_athlete(
     app_state: web::Data<app_state::AppState>,
     claim_data: Claims,
-    //path: web::Path<i32>,
+    path: web::Path<i32>,
 ) -> Result<ApiResponse, ApiResponse> {
-    //let athlete_id = path.into_inner();
-    let user = entities::user::Entity::find_by_id(claim_data.user_id)
-        .one(&app_state.db)
-        .await
-        .map_err(|err| ApiResponse::new(404, err.to_string()))?
-        .ok_or(ApiResponse::new(404, "User not found".to_string()))?;
+    let athlete_id = path.into_inner();
+    session_service::get_sessions_by_athlete(&app_state, claim_data, athlete_id).await
+}
 
-    Ok(ApiResponse::new(
-        200,
-        format!(
-            "{{ 'user_id': {}, 'user_type': {}, 'name_first': {}, 'name_last': {}, 'email': {} }}",
-            user.user_id, user.user_type, user.name_first, user.name_last, user.email
-        ),
-    ))
+#[utoipa::path(
+    context_path = "/session",
+    tag = "session",
+    params(("session_id" = i32, Path, description = "The session to look up")),
+    responses(
+        (status = 200, description = "The session"),
+        (status = 401, description = "Only the athlete and their coaches can view the session"),
+        (status = 404, description = "Session not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/{session_id}")]
+pub async fn get_session(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let session_id = path.into_inner();
+    session_service::get_session(&app_state, claim_data, session_id).await
+}
+
+#[utoipa::path(
+    context_path = "/session",
+    tag = "session",
+    params(("session_id" = i32, Path, description = "The session to end")),
+    responses(
+        (status = 200, description = "The closed session"),
+        (status = 401, description = "Only the athlete can end their session"),
+        (status = 404, description = "Session not found"),
+        (status = 409, description = "The session has already ended"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/{session_id}/end")]
+pub async fn end_session(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let session_id = path.into_inner();
+    session_service::end_session(&app_state, claim_data, session_id).await
 }
@@ -1,20 +1,108 @@
-// use actix_web::{get, post, web};
-// use sea_orm::EntityTrait;
+use actix_web::{delete, get, post, web};
+use sea_orm::ActiveEnum;
 
-// use crate::{
-//     entities,
-//     routes::services::user_service,
-//     utils::{
-//         api_response::ApiResponse,
-//         app_state,
-//         jwt::Claims,
-//         request_models::user_models::{UpdatePasswordModel, UpdateUserModel},
-//     },
-// };
+use crate::{
+    entities::sea_orm_active_enums::Event,
+    routes::services::skill_service,
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::skill_models::{CatalogueSkillModel, SkillQueryModel},
+    },
+};
 
-/*
-Requirements:
-    - Create a new skill
-    - Calculate the DD for the skill based on the event
-    - Delete a skill
-*/
+#[utoipa::path(
+    context_path = "/skill",
+    tag = "skill",
+    params(SkillQueryModel),
+    responses(
+        (status = 200, description = "The named skills with their DD, by event, figure and shape"),
+        (status = 422, description = "Invalid event"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/catalogue")]
+pub async fn get_catalogue(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    query: web::Query<SkillQueryModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let event = parse_event(&query)?;
+    skill_service::get_catalogue(&app_state, claim_data, event).await
+}
+
+#[utoipa::path(
+    context_path = "/skill",
+    tag = "skill",
+    request_body = CatalogueSkillModel,
+    responses(
+        (status = 201, description = "The new catalogue skill"),
+        (status = 403, description = "The caller is not an admin"),
+        (status = 409, description = "This skill is already in the catalogue"),
+        (status = 422, description = "Invalid event, notation, position or name"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/catalogue")]
+pub async fn create_catalogue_skill(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<CatalogueSkillModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    skill_service::create_catalogue_skill(&app_state, claim_data, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/skill",
+    tag = "skill",
+    params(("catalogue_skill_id" = i32, Path, description = "The catalogue skill to delete")),
+    responses(
+        (status = 200, description = "The skill was removed from the catalogue"),
+        (status = 403, description = "The caller is not an admin"),
+        (status = 404, description = "Catalogue skill not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/catalogue/{catalogue_skill_id}")]
+pub async fn delete_catalogue_skill(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    skill_service::delete_catalogue_skill(&app_state, claim_data, path.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/skill",
+    tag = "skill",
+    params(
+        ("athlete_id" = i32, Path, description = "The athlete whose skills to list"),
+        SkillQueryModel,
+    ),
+    responses(
+        (status = 200, description = "Every skill the athlete has performed, with attempts and the consistency of the judged ones"),
+        (status = 401, description = "Only the athlete and their coaches can view their skills"),
+        (status = 422, description = "Invalid event"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/repertoire/{athlete_id}")]
+pub async fn get_repertoire(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    query: web::Query<SkillQueryModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let event = parse_event(&query)?;
+    skill_service::get_repertoire(&app_state, claim_data, path.into_inner(), event).await
+}
+
+fn parse_event(query: &SkillQueryModel) -> Result<Option<Event>, ApiResponse> {
+    query
+        .event
+        .as_ref()
+        .map(Event::try_from_value)
+        .transpose()
+        .map_err(|_| ApiResponse::new(422, "Invalid event, must be DMT, TRA or TUM".to_string()))
+}
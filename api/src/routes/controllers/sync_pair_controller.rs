@@ -0,0 +1,95 @@
+use actix_web::{delete, get, post, web};
+
+use crate::{
+    routes::services::sync_pair_service,
+    utils::{
+        api_response::ApiResponse, app_state, jwt::Claims,
+        request_models::sync_pair_models::SyncPairModel,
+    },
+};
+
+#[utoipa::path(
+    context_path = "/sync-pair",
+    tag = "sync-pair",
+    request_body = SyncPairModel,
+    responses(
+        (status = 201, description = "The sync pair, waiting for the partner to accept"),
+        (status = 401, description = "Only athletes can form sync pairs"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "These athletes are already a sync pair"),
+        (status = 422, description = "The partner is not an athlete"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/create")]
+pub async fn create_sync_pair(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<SyncPairModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    sync_pair_service::create_sync_pair(&app_state, claim_data, json.partner_id).await
+}
+
+#[utoipa::path(
+    context_path = "/sync-pair",
+    tag = "sync-pair",
+    params(("sync_pair_id" = i32, Path, description = "The sync pair to accept")),
+    responses(
+        (status = 200, description = "The accepted sync pair"),
+        (status = 401, description = "Only the invited athlete can accept the sync pair"),
+        (status = 404, description = "Sync pair not found"),
+        (status = 409, description = "Sync pair has already been accepted"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/{sync_pair_id}/accept")]
+pub async fn accept_sync_pair(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let sync_pair_id = path.into_inner();
+    sync_pair_service::accept_sync_pair(&app_state, claim_data, sync_pair_id).await
+}
+
+#[utoipa::path(
+    context_path = "/sync-pair",
+    tag = "sync-pair",
+    params(("athlete_id" = i32, Path, description = "The athlete whose sync pairs to list")),
+    responses(
+        (status = 200, description = "The athlete's sync pairs"),
+        (status = 401, description = "Only the athlete and their coaches can view their sync pairs"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/athlete/{athlete_id}")]
+pub async fn get_sync_pairs_by_athlete(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let athlete_id = path.into_inner();
+    sync_pair_service::get_sync_pairs_by_athlete(&app_state, claim_data, athlete_id).await
+}
+
+#[utoipa::path(
+    context_path = "/sync-pair",
+    tag = "sync-pair",
+    params(("sync_pair_id" = i32, Path, description = "The sync pair to delete")),
+    responses(
+        (status = 200, description = "The sync pair or request was deleted"),
+        (status = 401, description = "Only the pair's athletes can delete it"),
+        (status = 404, description = "Sync pair not found"),
+        (status = 409, description = "The pair has logged turns"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/{sync_pair_id}")]
+pub async fn delete_sync_pair(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let sync_pair_id = path.into_inner();
+    sync_pair_service::delete_sync_pair(&app_state, claim_data, sync_pair_id).await
+}
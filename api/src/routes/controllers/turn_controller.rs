@@ -1,25 +1,140 @@
-// use actix_web::{get, post, web};
-// use sea_orm::EntityTrait;
+use actix_web::{delete, get, post, put, web};
 
-// use crate::{
-//     entities,
-//     routes::services::user_service,
-//     utils::{
-//         api_response::ApiResponse,
-//         app_state,
-//         jwt::Claims,
-//         request_models::user_models::{UpdatePasswordModel, UpdateUserModel},
-//     },
-// };
+use crate::{
+    routes::services::turn_service,
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::turn_models::{ExecutionModel, TurnModel},
+    },
+};
 
-/*
-Requirements:
-    - Get all turns for a session
-    - Create a new turn for a session
-        -- DD needs to be calculated on creation
-        -- DD needs to be calculated according to the event the session is being created for
-    - Edit a turn in a session (DD needs to be recalculated via DB trigger most likely)
-        -- When a turn is editted, all of the associated skills will need to be deleted and reuploaded
-        -- If the turn is 10 skills long, routine specific DD will need to be applied
-    - Delete a turn in a session
-*/
+#[utoipa::path(
+    context_path = "/turn",
+    tag = "turn",
+    request_body = TurnModel,
+    responses(
+        (status = 201, description = "The new turn with its difficulty, execution and total score"),
+        (status = 401, description = "Only the athlete can log turns in their session, with their own sync pair"),
+        (status = 404, description = "Session or sync pair not found"),
+        (status = 409, description = "The session has ended"),
+        (status = 422, description = "A skill or deduction is invalid"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/create")]
+pub async fn create_turn(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<TurnModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    turn_service::create_turn(&app_state, claim_data, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/turn",
+    tag = "turn",
+    params(("session_id" = i32, Path, description = "The session whose turns to list")),
+    responses(
+        (status = 200, description = "The session's turns in the order they were logged"),
+        (status = 401, description = "Only the athlete and their coaches can view the turns"),
+        (status = 404, description = "Session not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/session/{session_id}")]
+pub async fn get_turns_by_session(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let session_id = path.into_inner();
+    turn_service::get_turns_by_session(&app_state, claim_data, session_id).await
+}
+
+#[utoipa::path(
+    context_path = "/turn",
+    tag = "turn",
+    params(("athlete_id" = i32, Path, description = "The athlete whose turns to list")),
+    responses(
+        (status = 200, description = "The athlete's turns, including synchronized turns logged by their partners, most recent first"),
+        (status = 401, description = "Only the athlete and their coaches can view the turns"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/athlete/{athlete_id}")]
+pub async fn get_turns_by_athlete(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let athlete_id = path.into_inner();
+    turn_service::get_turns_by_athlete(&app_state, claim_data, athlete_id).await
+}
+
+#[utoipa::path(
+    context_path = "/turn",
+    tag = "turn",
+    params(("turn_id" = i32, Path, description = "The turn to look up")),
+    responses(
+        (status = 200, description = "The turn and its skills"),
+        (status = 401, description = "Only the athlete and their coaches can view the turn"),
+        (status = 404, description = "Turn not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/{turn_id}")]
+pub async fn get_turn(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let turn_id = path.into_inner();
+    turn_service::get_turn(&app_state, claim_data, turn_id).await
+}
+
+#[utoipa::path(
+    context_path = "/turn",
+    tag = "turn",
+    params(("turn_id" = i32, Path, description = "The turn to judge")),
+    request_body = ExecutionModel,
+    responses(
+        (status = 200, description = "The turn with its new execution and total score"),
+        (status = 401, description = "Only the athlete and their coaches can judge the turn"),
+        (status = 404, description = "Turn not found"),
+        (status = 422, description = "A deduction is invalid"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[put("/{turn_id}/execution")]
+pub async fn score_turn(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+    json: web::Json<ExecutionModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    let turn_id = path.into_inner();
+    turn_service::score_turn(&app_state, claim_data, turn_id, json.into_inner()).await
+}
+
+#[utoipa::path(
+    context_path = "/turn",
+    tag = "turn",
+    params(("turn_id" = i32, Path, description = "The turn to delete")),
+    responses(
+        (status = 200, description = "The turn and its skills were deleted"),
+        (status = 401, description = "Only the athlete can delete their turn"),
+        (status = 404, description = "Turn not found"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[delete("/{turn_id}")]
+pub async fn delete_turn(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let turn_id = path.into_inner();
+    turn_service::delete_turn(&app_state, claim_data, turn_id).await
+}
@@ -0,0 +1,97 @@
+use actix_web::{post, web};
+
+use crate::{
+    routes::services::two_factor_service,
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::{Claims, TokenPurpose},
+        request_models::two_factor_models::{DisableTwoFactorModel, TwoFactorCodeModel},
+    },
+};
+
+#[utoipa::path(
+    context_path = "/two-factor",
+    tag = "two-factor",
+    responses(
+        (status = 200, description = "A new TOTP secret and its otpauth URL"),
+        (status = 409, description = "Two-factor is already enabled"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/enroll")]
+pub async fn enroll(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+) -> Result<ApiResponse, ApiResponse> {
+    two_factor_service::begin_enrollment(&app_state, claim_data).await
+}
+
+#[utoipa::path(
+    context_path = "/two-factor",
+    tag = "two-factor",
+    request_body = TwoFactorCodeModel,
+    responses(
+        (status = 200, description = "Two-factor enabled, with single-use recovery codes"),
+        (status = 401, description = "The code is invalid"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/enroll/confirm")]
+pub async fn confirm_enrollment(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<TwoFactorCodeModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    two_factor_service::confirm_enrollment(&app_state, claim_data, json.code.clone()).await
+}
+
+#[utoipa::path(
+    context_path = "/two-factor",
+    tag = "two-factor",
+    request_body = DisableTwoFactorModel,
+    responses(
+        (status = 200, description = "Two-factor disabled"),
+        (status = 401, description = "The password or code is invalid"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/disable")]
+pub async fn disable(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<DisableTwoFactorModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    require_access_token(&claim_data)?;
+    let password = json.password.clone();
+    let code = json.code.clone();
+    two_factor_service::disable(&app_state, claim_data, password, code).await
+}
+
+#[utoipa::path(
+    context_path = "/two-factor",
+    tag = "two-factor",
+    request_body = TwoFactorCodeModel,
+    responses(
+        (status = 200, description = "New recovery codes, replacing the old ones"),
+        (status = 401, description = "The code is invalid"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[post("/recovery-codes")]
+pub async fn regenerate_recovery_codes(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: web::Json<TwoFactorCodeModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    require_access_token(&claim_data)?;
+    two_factor_service::regenerate_recovery_codes(&app_state, claim_data, json.code.clone()).await
+}
+
+// Enrollment tokens can only be used to set up two-factor, not to manage it
+fn require_access_token(claim_data: &Claims) -> Result<(), ApiResponse> {
+    if claim_data.purpose != TokenPurpose::Access {
+        return Err(ApiResponse::new(401, "Unauthorized".to_string()));
+    }
+    Ok(())
+}
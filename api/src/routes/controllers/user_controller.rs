@@ -1,9 +1,9 @@
 use actix_web::{get, post, web};
-use sea_orm::EntityTrait;
+use sea_orm::{ActiveEnum, EntityTrait};
 
 use crate::{
     entities,
-    routes::services::{club_member_service, club_service, user_service},
+    routes::services::{club_member_service, club_service, stats_service, user_service},
     utils::{
         api_response::ApiResponse,
         app_state,
@@ -12,6 +12,16 @@ use crate::{
     },
 };
 
+#[utoipa::path(
+    context_path = "/user",
+    tag = "user",
+    params(("user_id" = i32, Path, description = "The user to look up")),
+    responses(
+        (status = 200, description = "The user's profile"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[get("/{user_id}")]
 pub async fn get_user(
     app_state: web::Data<app_state::AppState>,
@@ -28,11 +38,45 @@ pub async fn get_user(
         200,
         format!(
             "{{ 'user_id': {}, 'user_type': {}, 'name_first': {}, 'name_last': {}, 'email': {} }}",
-            user.user_id, user.user_type, user.name_first, user.name_last, user.email
+            user.user_id,
+            user.user_type.to_value(),
+            user.name_first,
+            user.name_last,
+            user.email
         ),
     ))
 }
 
+#[utoipa::path(
+    context_path = "/user",
+    tag = "user",
+    params(("user_id" = i32, Path, description = "The athlete whose personal bests to list")),
+    responses(
+        (status = 200, description = "The athlete's personal bests in each event they have logged turns in"),
+        (status = 401, description = "Only the athlete and their coaches can view their personal bests"),
+    ),
+    security(("bearer_token" = []))
+)]
+#[get("/{user_id}/bests")]
+pub async fn get_personal_bests(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    path: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    let user_id = path.into_inner();
+    stats_service::get_personal_bests(&app_state, claim_data, user_id).await
+}
+
+#[utoipa::path(
+    context_path = "/user",
+    tag = "user",
+    params(("user_id" = i32, Path, description = "The member to look up")),
+    responses(
+        (status = 200, description = "The club the user belongs to"),
+        (status = 404, description = "The user is not in a club"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[get("/{user_id}/club")]
 pub async fn get_user_club(
     app_state: web::Data<app_state::AppState>,
@@ -41,20 +85,10 @@ pub async fn get_user_club(
 ) -> Result<ApiResponse, ApiResponse> {
     let user_id = path.into_inner();
     // Get the membership
-    let membership = club_member_service::get_member_by_user_id(&app_state, user_id).await;
-
-    if membership.is_err() {
-        return Err(membership.unwrap_err());
-    }
-    let membership = membership.unwrap();
+    let membership = club_member_service::get_member_by_user_id(&app_state.db, user_id).await?;
 
     // Get the club the user is a part of
-    let search_result = club_service::get_club_by_id(&app_state, membership.club_id).await;
-
-    if search_result.is_err() {
-        return Err(search_result.unwrap_err());
-    }
-    let club = search_result.unwrap();
+    let club = club_service::get_club_by_id(&app_state.db, membership.club_id).await?;
 
     Ok(ApiResponse::new(
         200,
@@ -65,6 +99,16 @@ pub async fn get_user_club(
     ))
 }
 
+#[utoipa::path(
+    context_path = "/user",
+    tag = "user",
+    request_body = UpdatePasswordModel,
+    responses(
+        (status = 200, description = "Password changed"),
+        (status = 401, description = "The old password is wrong"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[post("/reset-password")]
 pub async fn reset_password(
     app_state: web::Data<app_state::AppState>,
@@ -76,6 +120,16 @@ pub async fn reset_password(
     user_service::reset_password(&app_state, claim_data, old_pass, new_pass).await
 }
 
+#[utoipa::path(
+    context_path = "/user",
+    tag = "user",
+    request_body = UpdateUserModel,
+    responses(
+        (status = 200, description = "The updated profile"),
+        (status = 409, description = "The email is already registered"),
+    ),
+    security(("bearer_token" = []))
+)]
 #[post("/update")]
 pub async fn update(
     app_state: web::Data<app_state::AppState>,
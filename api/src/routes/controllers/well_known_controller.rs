@@ -0,0 +1,16 @@
+use actix_web::{get, web, HttpResponse};
+
+use crate::utils::{app_state, signing_keys::JwkSet};
+
+#[utoipa::path(
+    context_path = "/.well-known",
+    tag = "well-known",
+    responses((status = 200, description = "Public keys for verifying login tokens, as a JWK set", body = JwkSet, content_type = "application/json"))
+)]
+#[get("/jwks.json")]
+pub async fn get_jwks(app_state: web::Data<app_state::AppState>) -> HttpResponse {
+    // Served as real JSON, since other services parse it with standard JWKS libraries
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(app_state.signing_keys.jwks())
+}
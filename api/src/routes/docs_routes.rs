@@ -0,0 +1,11 @@
+use super::{api_doc::ApiDoc, controllers};
+use actix_web::web;
+use utoipa::OpenApi;
+use utoipa_scalar::{Scalar, Servable};
+
+// The OpenAPI document and an interactive page for browsing and trying it, both public
+pub fn config(config: &mut web::ServiceConfig) {
+    config
+        .service(controllers::docs_controller::get_openapi)
+        .service(Scalar::with_url("/docs", ApiDoc::openapi()));
+}
@@ -0,0 +1,16 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/goal")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("goal", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::goal_controller::create_goal)
+            .service(controllers::goal_controller::get_goals_by_athlete)
+            .service(controllers::goal_controller::get_goal)
+            .service(controllers::goal_controller::delete_goal),
+    );
+}
@@ -0,0 +1,16 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/group")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("group", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::group_controller::create_group)
+            .service(controllers::group_controller::set_members)
+            .service(controllers::group_controller::get_groups_by_club)
+            .service(controllers::group_controller::delete_group),
+    );
+}
@@ -0,0 +1,10 @@
+use super::controllers;
+use actix_web::web;
+
+// Left unauthenticated so orchestrators can probe the server
+pub fn config(config: &mut web::ServiceConfig) {
+    config
+        .service(controllers::health_controller::healthz)
+        .service(controllers::health_controller::readyz)
+        .service(controllers::health_controller::version);
+}
@@ -0,0 +1,7 @@
+use super::controllers;
+use actix_web::web;
+
+// Left unauthenticated for the scraper, so it should only be reachable from inside the network
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(controllers::metrics_controller::get_metrics);
+}
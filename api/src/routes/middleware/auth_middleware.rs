@@ -3,42 +3,75 @@ use actix_web::{
     dev::{ServiceRequest, ServiceResponse},
     http::header::AUTHORIZATION,
     middleware::Next,
-    Error, HttpMessage,
+    web, Error, HttpMessage,
 };
 
-use crate::utils::{api_response, jwt::decode_jwt};
+use crate::utils::{
+    api_response,
+    app_state::AppState,
+    jwt::{decode_jwt, TokenPurpose},
+};
 
 pub async fn check_auth_middleware(
     req: ServiceRequest,
     next: Next<impl MessageBody>,
 ) -> Result<ServiceResponse<impl MessageBody>, Error> {
-    // Parse auth headers
-    let auth = req.headers().get(AUTHORIZATION);
+    authorize(&req, &[TokenPurpose::Access])?;
+
+    // Captures any errors with the request
+    next.call(req)
+        .await
+        .map_err(|err| Error::from(api_response::ApiResponse::new(500, err.to_string())))
+}
 
-    // If none are provided, return unauthorized
-    if auth.is_none() {
-        return Err(Error::from(api_response::ApiResponse::new(
+// Also lets through the short lived token handed out when a club requires two-factor setup at login
+pub async fn check_two_factor_setup_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    authorize(
+        &req,
+        &[TokenPurpose::Access, TokenPurpose::TwoFactorEnrollment],
+    )?;
+
+    // Captures any errors with the request
+    next.call(req)
+        .await
+        .map_err(|err| Error::from(api_response::ApiResponse::new(500, err.to_string())))
+}
+
+// Decodes the bearer token and inserts its claims, if it is valid for one of the allowed purposes
+fn authorize(req: &ServiceRequest, allowed: &[TokenPurpose]) -> Result<(), Error> {
+    let unauthorized = || {
+        Error::from(api_response::ApiResponse::new(
             401,
             "Unauthorized".to_string(),
-        )));
-    }
+        ))
+    };
+
+    // Parse auth headers, if none are provided, return unauthorized
+    let auth = req.headers().get(AUTHORIZATION).ok_or_else(unauthorized)?;
 
     // Parse token
     let token = auth
-        .unwrap()
         .to_str()
-        .unwrap()
+        .map_err(|_| unauthorized())?
         .replace("Bearer ", "")
         .to_owned();
 
-    // Decode the token
-    let claim = decode_jwt(token).unwrap();
+    // Decode the token with the keys it may have been signed with
+    let app_state = req
+        .app_data::<web::Data<AppState>>()
+        .ok_or_else(unauthorized)?;
+    let claim = decode_jwt(&app_state.signing_keys, token).map_err(|_| unauthorized())?;
+
+    // Make sure the token can be used here
+    if !allowed.contains(&claim.claims.purpose) {
+        return Err(unauthorized());
+    }
 
     // Inserting the claim
     req.extensions_mut().insert(claim.claims);
 
-    // Captures any errors with the request
-    next.call(req)
-        .await
-        .map_err(|err| Error::from(api_response::ApiResponse::new(500, err.to_string())))
+    Ok(())
 }
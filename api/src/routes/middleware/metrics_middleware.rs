@@ -0,0 +1,46 @@
+use std::time::Instant;
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web, Error,
+};
+
+use crate::utils::app_state::AppState;
+
+// Counts and times every request, labelled by the matched route pattern rather than the raw
+// path, so ids in the URL don't create a new series each
+pub async fn record_metrics(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let app_state = req.app_data::<web::Data<AppState>>().cloned();
+    let method = req.method().to_string();
+    let route = req
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started = Instant::now();
+
+    let res = next.call(req).await;
+
+    if let Some(app_state) = app_state {
+        let status = match &res {
+            Ok(res) => res.status(),
+            Err(err) => err.as_response_error().status_code(),
+        };
+
+        app_state
+            .metrics
+            .http_requests
+            .with_label_values(&[&method, &route, status.as_str()])
+            .inc();
+        app_state
+            .metrics
+            .http_request_duration
+            .with_label_values(&[&method, &route])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    res
+}
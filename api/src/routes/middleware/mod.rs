@@ -1 +1,4 @@
 pub mod auth_middleware;
+pub mod metrics_middleware;
+pub mod rate_limit_middleware;
+pub mod request_middleware;
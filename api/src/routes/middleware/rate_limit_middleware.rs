@@ -0,0 +1,69 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue, RETRY_AFTER},
+    middleware::Next,
+    web, Error, HttpMessage, HttpResponse,
+};
+
+use crate::utils::{
+    api_response, app_state::AppState, jwt::Claims, rate_limiter::RateLimitDecision,
+};
+
+// Throttles requests to a scope, keyed by the authenticated user or else by the client IP
+pub async fn check_rate_limit(
+    scope: &'static str,
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let app_state = req
+        .app_data::<web::Data<AppState>>()
+        .ok_or(api_response::ApiResponse::new(
+            500,
+            "App state is not configured".to_string(),
+        ))?
+        .clone();
+
+    // Scopes without a policy are not throttled
+    let policy = match app_state.rate_limiter.policy(scope) {
+        Some(policy) => *policy,
+        None => return Ok(next.call(req).await?.map_into_left_body()),
+    };
+
+    // Claims are only present when the auth middleware has already run for this scope
+    let user_id = req.extensions().get::<Claims>().map(|claim| claim.user_id);
+    let key = match user_id {
+        Some(user_id) => format!("{}:user:{}", scope, user_id),
+        None => format!(
+            "{}:ip:{}",
+            scope,
+            req.peer_addr()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        ),
+    };
+
+    let decision = app_state
+        .rate_limiter
+        .store
+        .take(&key, &policy)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    match decision {
+        RateLimitDecision::Allowed { remaining } => {
+            let mut res = next.call(req).await?;
+            if let Ok(value) = HeaderValue::from_str(&remaining.to_string()) {
+                res.headers_mut()
+                    .insert(HeaderName::from_static("x-ratelimit-remaining"), value);
+            }
+            Ok(res.map_into_left_body())
+        }
+        RateLimitDecision::Limited { retry_after_secs } => {
+            let res = HttpResponse::TooManyRequests()
+                .insert_header((RETRY_AFTER, retry_after_secs.to_string()))
+                .body("Too many requests, please slow down");
+            Ok(req.into_response(res).map_into_right_body())
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::Instant;
+
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    Error,
+};
+use tracing::Instrument;
+
+use crate::utils::request_id::{self, REQUEST_ID_HEADER};
+
+// Probes and scrapes that would drown out real traffic in the logs
+const UNLOGGED_PATHS: [&str; 4] = ["/healthz", "/readyz", "/version", "/metrics"];
+
+// Wraps each request in a span tagged with its request id, echoes the id back in the
+// X-Request-Id header and logs the outcome once the response is ready
+pub async fn trace_request(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let id = request_id::from_header(
+        req.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let path = req.path().to_string();
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %path,
+    );
+    let started = Instant::now();
+
+    let res = request_id::scope(id.clone(), next.call(req))
+        .instrument(span.clone())
+        .await;
+
+    // Errors only become responses once they leave the middleware, after the request id is
+    // out of scope, so their bodies are rendered here while it is still available
+    let (res, status) = match res {
+        Ok(mut res) => {
+            insert_request_id(res.headers_mut(), &id);
+            let status = res.status();
+            (Ok(res.map_into_boxed_body()), status)
+        }
+        Err(err) => {
+            let mut err_res = request_id::scope(id.clone(), async { err.error_response() })
+                .instrument(span.clone())
+                .await;
+            insert_request_id(err_res.headers_mut(), &id);
+            let status = err_res.status();
+            (
+                Err(InternalError::from_response(err.to_string(), err_res).into()),
+                status,
+            )
+        }
+    };
+
+    if !UNLOGGED_PATHS.contains(&path.as_str()) {
+        let _entered = span.enter();
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        if status.is_server_error() {
+            tracing::error!(status = status.as_u16(), elapsed_ms, "request failed");
+        } else {
+            tracing::info!(status = status.as_u16(), elapsed_ms, "request completed");
+        }
+    }
+
+    res
+}
+
+fn insert_request_id(headers: &mut HeaderMap, id: &str) {
+    if let Ok(value) = HeaderValue::from_str(id) {
+        headers.insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+}
@@ -1,10 +1,26 @@
+pub mod api_doc;
 pub mod controllers;
 pub mod middleware;
 pub mod services;
 
+pub mod admin_routes;
+pub mod analytics_routes;
 pub mod auth_routes;
 pub mod club_routes;
+pub mod comment_routes;
+pub mod docs_routes;
+pub mod goal_routes;
+pub mod group_routes;
+pub mod health_routes;
+pub mod metrics_routes;
+pub mod plan_routes;
+pub mod session_routes;
+pub mod skill_routes;
+pub mod sync_pair_routes;
+pub mod turn_routes;
+pub mod two_factor_routes;
 pub mod user_routes;
+pub mod well_known_routes;
 
 use actix_web::web;
 
@@ -13,4 +29,19 @@ pub fn config(config: &mut web::ServiceConfig) {
     user_routes::config(config);
     auth_routes::config(config);
     club_routes::config(config);
+    session_routes::config(config);
+    turn_routes::config(config);
+    skill_routes::config(config);
+    comment_routes::config(config);
+    plan_routes::config(config);
+    goal_routes::config(config);
+    group_routes::config(config);
+    sync_pair_routes::config(config);
+    analytics_routes::config(config);
+    admin_routes::config(config);
+    two_factor_routes::config(config);
+    well_known_routes::config(config);
+    health_routes::config(config);
+    metrics_routes::config(config);
+    docs_routes::config(config);
 }
@@ -0,0 +1,18 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/plan")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("plan", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::plan_controller::create_plan)
+            .service(controllers::plan_controller::assign_plan)
+            .service(controllers::plan_controller::get_plans_by_athlete)
+            .service(controllers::plan_controller::get_plans_by_club)
+            .service(controllers::plan_controller::get_plan)
+            .service(controllers::plan_controller::delete_plan),
+    );
+}
@@ -0,0 +1,119 @@
+use actix_web::web;
+use chrono::Utc;
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, Set,
+};
+use tracing::instrument;
+
+use crate::{
+    entities,
+    utils::{api_response::ApiResponse, app_state, jwt::Claims},
+};
+
+use super::user_service::get_user_by_id;
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn ensure_admin(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: &Claims,
+) -> Result<entities::user::Model, ApiResponse> {
+    let user = get_user_by_id(&app_state.db, claim_data.user_id).await?;
+
+    if !user.is_admin {
+        return Err(ApiResponse::new(
+            403,
+            "Only admins can perform this action".to_string(),
+        ));
+    }
+
+    Ok(user)
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, active_only))]
+pub async fn get_lockouts(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    active_only: bool,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_admin(app_state, &claim_data).await?;
+
+    // Init query, most recent lockouts first
+    let mut query = entities::account_lockout::Entity::find()
+        .order_by_desc(entities::account_lockout::Column::LockedAt);
+
+    // Only show lockouts that are still in effect
+    if active_only {
+        query = query.filter(
+            Condition::all()
+                .add(entities::account_lockout::Column::ClearedAt.is_null())
+                .add(entities::account_lockout::Column::LockedUntil.gt(Utc::now().naive_utc())),
+        );
+    }
+
+    let lockouts = query
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let lockouts = lockouts
+        .iter()
+        .map(|lockout| {
+            format!(
+                "{{ 'lockout_id': {}, 'scope': {}, 'subject': {}, 'failed_attempts': {}, 'locked_at': {}, 'locked_until': {}, 'cleared_at': {}, 'cleared_by': {} }}",
+                lockout.lockout_id,
+                lockout.scope.to_value(),
+                lockout.subject,
+                lockout.failed_attempts,
+                lockout.locked_at,
+                lockout.locked_until,
+                lockout
+                    .cleared_at
+                    .map_or("null".to_string(), |cleared_at| cleared_at.to_string()),
+                lockout
+                    .cleared_by
+                    .map_or("null".to_string(), |cleared_by| cleared_by.to_string()),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", lockouts)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, lockout_id))]
+pub async fn clear_lockout(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    lockout_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let admin = ensure_admin(app_state, &claim_data).await?;
+
+    let lockout = entities::account_lockout::Entity::find_by_id(lockout_id)
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Lockout not found".to_string()))?;
+
+    if lockout.cleared_at.is_some() {
+        return Err(ApiResponse::new(
+            409,
+            "Lockout has already been cleared".to_string(),
+        ));
+    }
+
+    // Mark the lockout as cleared, which also resets the failed attempt count
+    let mut lockout = lockout.into_active_model();
+    lockout.cleared_at = Set(Some(Utc::now().naive_utc()));
+    lockout.cleared_by = Set(Some(admin.user_id));
+
+    lockout
+        .update(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Lockout cleared successfully".to_string(),
+    ))
+}
@@ -0,0 +1,180 @@
+use std::collections::{BTreeMap, HashMap};
+
+use actix_web::web;
+use chrono::{Days, NaiveDate, NaiveTime};
+use sea_orm::{ActiveEnum, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter};
+use tracing::instrument;
+
+use crate::{
+    entities::{self, sea_orm_active_enums::UserType},
+    utils::{
+        analytics::{Filter, Volume},
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        scoring::{self, Notation},
+    },
+};
+
+use super::{
+    club_member_service::{get_athlete_ids_by_club_id, get_members_by_club_id},
+    club_service::get_club_by_id,
+    session_service::ensure_can_view_athlete,
+    turn_service::roster_turns,
+    user_service::get_user_by_id,
+};
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_athlete_analytics(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+    filter: Filter,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let volumes = get_volumes(&app_state.db, &[athlete_id], &filter).await?;
+
+    Ok(ApiResponse::new(200, analytics_body(&filter, &volumes)))
+}
+
+// The whole roster's training added together, for the club's coaches
+#[instrument(skip_all, fields(user_id = claim_data.user_id, club_id))]
+pub async fn get_club_analytics(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    club_id: i32,
+    filter: Filter,
+) -> Result<ApiResponse, ApiResponse> {
+    get_club_by_id(&app_state.db, club_id).await?;
+
+    let viewer = get_user_by_id(&app_state.db, claim_data.user_id).await?;
+    let member_ids = get_members_by_club_id(&app_state.db, club_id)
+        .await?
+        .iter()
+        .map(|membership| membership.user_id)
+        .collect::<Vec<i32>>();
+    if viewer.user_type != UserType::Coach || !member_ids.contains(&viewer.user_id) {
+        return Err(ApiResponse::new(
+            401,
+            "Only the club's coaches can view its training analytics".to_string(),
+        ));
+    }
+
+    let athlete_ids = get_athlete_ids_by_club_id(&app_state.db, club_id).await?;
+
+    let volumes = get_volumes(&app_state.db, &athlete_ids, &filter).await?;
+
+    Ok(ApiResponse::new(200, analytics_body(&filter, &volumes)))
+}
+
+// The athletes' training keyed by the first day of each period, leaving out periods with none.
+// Turns fall in the period their session started in, and a synchronized turn between two of the
+// athletes is only counted once
+async fn get_volumes<C: ConnectionTrait>(
+    db: &C,
+    athlete_ids: &[i32],
+    filter: &Filter,
+) -> Result<BTreeMap<NaiveDate, Volume>, ApiResponse> {
+    let mut volumes = BTreeMap::<NaiveDate, Volume>::new();
+    if athlete_ids.is_empty() {
+        return Ok(volumes);
+    }
+
+    let mut in_range = Condition::all();
+    if let Some(event) = filter.event {
+        in_range = in_range.add(entities::session::Column::EventId.eq(event));
+    }
+    if let Some(from) = filter.from {
+        in_range =
+            in_range.add(entities::session::Column::TimeStart.gte(from.and_time(NaiveTime::MIN)));
+    }
+    if let Some(to) = filter.to.and_then(|to| to.checked_add_days(Days::new(1))) {
+        in_range =
+            in_range.add(entities::session::Column::TimeStart.lt(to.and_time(NaiveTime::MIN)));
+    }
+
+    let sessions = entities::session::Entity::find()
+        .filter(
+            in_range
+                .clone()
+                .add(entities::session::Column::UserId.is_in(athlete_ids.to_vec())),
+        )
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    for session in &sessions {
+        volumes
+            .entry(filter.period.start_of(session.time_start.date()))
+            .or_default()
+            .sessions += 1;
+    }
+
+    let turns = entities::turn::Entity::find()
+        .find_also_related(entities::session::Entity)
+        .filter(roster_turns(db, athlete_ids).await?)
+        .filter(in_range)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let mut skills = HashMap::<i32, Vec<entities::skill::Model>>::new();
+    for skill in entities::skill::Entity::find()
+        .filter(entities::skill::Column::TurnId.is_in(turns.iter().map(|(turn, _)| turn.turn_id)))
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+    {
+        skills.entry(skill.turn_id).or_default().push(skill);
+    }
+
+    for (turn, session) in &turns {
+        let Some(session) = session else {
+            continue;
+        };
+        let volume = volumes
+            .entry(filter.period.start_of(session.time_start.date()))
+            .or_default();
+        volume.turns += 1;
+        volume.difficulty += scoring::nearest_tenths(turn.total_difficulty);
+        for skill in skills.get(&turn.turn_id).into_iter().flatten() {
+            if let Some(notation) = Notation::parse(skill.fig_rep) {
+                volume.add_skill(&notation);
+            }
+        }
+    }
+
+    Ok(volumes)
+}
+
+fn analytics_body(filter: &Filter, volumes: &BTreeMap<NaiveDate, Volume>) -> String {
+    let buckets = volumes
+        .iter()
+        .map(|(start, volume)| {
+            format!(
+                "{{ 'start': {}, 'sessions': {}, 'turns': {}, 'skills': {}, 'total_difficulty': {}, 'somersaults': {}, 'twists': {} }}",
+                start,
+                volume.sessions,
+                volume.turns,
+                volume.skills,
+                scoring::to_points(volume.difficulty),
+                volume.somersaults,
+                volume.twists(),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        "{{ 'period': {}, 'event_id': {}, 'from': {}, 'to': {}, 'buckets': [ {} ] }}",
+        filter.period.name(),
+        filter
+            .event
+            .map_or("null".to_string(), |event| event.to_value()),
+        filter
+            .from
+            .map_or("null".to_string(), |from| from.to_string()),
+        filter.to.map_or("null".to_string(), |to| to.to_string()),
+        buckets,
+    )
+}
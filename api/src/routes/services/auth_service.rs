@@ -1,33 +1,34 @@
 use actix_web::web;
 use sha256::digest;
+use tracing::instrument;
 
-use crate::entities;
+use crate::entities::{self, sea_orm_active_enums::UserType};
+use crate::routes::services::{login_attempt_service, two_factor_service};
 use crate::utils::{
     api_response::ApiResponse,
     app_state,
-    jwt::encode_jwt,
+    jwt::{encode_jwt, TokenPurpose},
     request_models::auth_models::{LoginModel, RegisterModel},
 };
 
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, Set,
+};
 
+#[instrument(skip_all)]
 pub async fn register(
     app_state: &web::Data<app_state::AppState>,
     json: web::Json<RegisterModel>,
 ) -> Result<ApiResponse, ApiResponse> {
     // Make sure user type is valid
-    if json.user_type != "A" && json.user_type != "C" {
-        return Err(ApiResponse::new(
-            500,
-            "Invalid user type, must be A or C".to_string(),
-        ));
-    }
+    let user_type = UserType::try_from_value(&json.user_type)
+        .map_err(|_| ApiResponse::new(500, "Invalid user type, must be A or C".to_string()))?;
 
     let user = entities::user::Entity::find()
         .filter(
             Condition::all()
                 .add(entities::user::Column::Email.eq(&json.email))
-                .add(entities::user::Column::UserType.eq(&json.user_type)),
+                .add(entities::user::Column::UserType.eq(user_type)),
         )
         .one(&app_state.db)
         .await
@@ -47,38 +48,92 @@ pub async fn register(
         name_last: Set(json.name_last.clone()),
         email: Set(json.email.clone()),
         password: Set(digest(&json.password)),
-        user_type: Set(json.user_type.clone()),
+        user_type: Set(user_type),
         ..Default::default()
     }
     .insert(&app_state.db)
     .await
-    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    .map_err(|err| {
+        ApiResponse::from_db_conflict(err, "User with that email and type already exists")
+    })?;
 
     Ok(ApiResponse::new(200, format!("{}", user_model.user_id)))
 }
 
+#[instrument(skip_all)]
 pub async fn login_user(
     app_state: &web::Data<app_state::AppState>,
     json: web::Json<LoginModel>,
+    ip_address: String,
 ) -> Result<ApiResponse, ApiResponse> {
-    // Get the user
-    let user = entities::user::Entity::find()
-        .filter(
-            Condition::all()
-                .add(entities::user::Column::Email.eq(&json.email))
-                .add(entities::user::Column::Password.eq(digest(&json.password)))
-                .add(entities::user::Column::UserType.eq(&json.user_type)),
-        )
-        .one(&app_state.db)
-        .await
-        .map_err(|err| ApiResponse::new(500, err.to_string()))?
-        .ok_or(ApiResponse::new(
-            404,
-            "No user found for that email and password".to_string(),
-        ))?;
+    // Refuse to check credentials while the account or IP address is locked out
+    login_attempt_service::check_lockout(app_state, &json.email, &ip_address).await?;
+
+    // Get the user, an unknown user type can't match any account
+    let user = match UserType::try_from_value(&json.user_type) {
+        Ok(user_type) => entities::user::Entity::find()
+            .filter(
+                Condition::all()
+                    .add(entities::user::Column::Email.eq(&json.email))
+                    .add(entities::user::Column::UserType.eq(user_type)),
+            )
+            .one(&app_state.db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?,
+        Err(_) => None,
+    };
+
+    // Verify the password, without revealing whether the account exists
+    let user = match user {
+        Some(user) if user.password == digest(&json.password) => user,
+        _ => {
+            login_attempt_service::record_attempt(app_state, &json.email, &ip_address, false)
+                .await?;
+            return Err(ApiResponse::new(
+                401,
+                "Invalid email or password".to_string(),
+            ));
+        }
+    };
+
+    login_attempt_service::record_attempt(app_state, &json.email, &ip_address, true).await?;
+
+    // Users with two-factor enabled have to complete a second step before getting a token
+    if two_factor_service::is_enabled(app_state, user.user_id).await? {
+        let challenge_token = two_factor_service::encode_challenge_token(
+            app_state,
+            &user,
+            TokenPurpose::TwoFactorChallenge,
+        )?;
+
+        return Ok(ApiResponse::new(
+            200,
+            format!(
+                "{{ 'two_factor_required': true, 'challenge_token': {}}}",
+                challenge_token
+            ),
+        ));
+    }
+
+    // Staff of clubs that require two-factor must set it up before they can log in
+    if two_factor_service::is_required(app_state, &user).await? {
+        let enrollment_token = two_factor_service::encode_challenge_token(
+            app_state,
+            &user,
+            TokenPurpose::TwoFactorEnrollment,
+        )?;
+
+        return Err(ApiResponse::new(
+            403,
+            format!(
+                "{{ 'two_factor_setup_required': true, 'enrollment_token': {}}}",
+                enrollment_token
+            ),
+        ));
+    }
 
     // Create the jwt token
-    let token = encode_jwt(user.email, user.user_id)
+    let token = encode_jwt(&app_state.signing_keys, user.email, user.user_id)
         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
     Ok(ApiResponse::new(200, format!("{{ 'token': {}}}", token)))
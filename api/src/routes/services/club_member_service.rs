@@ -1,23 +1,26 @@
 use actix_web::web;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter, QuerySelect, Set, TransactionTrait,
 };
+use tracing::instrument;
 
 use crate::{
-    entities,
+    entities::{self, sea_orm_active_enums::UserType},
     utils::{api_response::ApiResponse, app_state, jwt::Claims},
 };
 
 use super::club_service;
 
-pub async fn get_member_by_user_id(
-    app_state: &web::Data<app_state::AppState>,
+#[instrument(skip_all, fields(user_id))]
+pub async fn get_member_by_user_id<C: ConnectionTrait>(
+    db: &C,
     user_id: i32,
 ) -> Result<entities::club_member::Model, ApiResponse> {
     // Get membership
     let membership = entities::club_member::Entity::find()
         .filter(Condition::all().add(entities::club_member::Column::UserId.eq(user_id)))
-        .one(&app_state.db)
+        .one(db)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?
         .ok_or(ApiResponse::new(404, "No club found for user".to_string()))?;
@@ -25,68 +28,88 @@ pub async fn get_member_by_user_id(
     Ok(membership)
 }
 
-// pub async fn get_members_by_club_id(
-//     app_state: &web::Data<app_state::AppState>,
-//     club_id: i32,
-// ) -> Result<Vec<entities::club_member::Model>, ApiResponse> {
-//     // Get membership
-//     let memberships = entities::club_member::Entity::find()
-//         .filter(Condition::all().add(entities::club_member::Column::ClubId.eq(club_id)))
-//         .all(&app_state.db)
-//         .await
-//         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
-
-//     Ok(memberships)
-// }
-
-pub async fn create_membership(
-    app_state: &web::Data<app_state::AppState>,
+#[instrument(skip_all, fields(club_id))]
+pub async fn get_members_by_club_id<C: ConnectionTrait>(
+    db: &C,
+    club_id: i32,
+) -> Result<Vec<entities::club_member::Model>, ApiResponse> {
+    // Get membership
+    let memberships = entities::club_member::Entity::find()
+        .filter(Condition::all().add(entities::club_member::Column::ClubId.eq(club_id)))
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(memberships)
+}
+
+// The athletes in the club, leaving out its coaches
+pub async fn get_athlete_ids_by_club_id<C: ConnectionTrait>(
+    db: &C,
+    club_id: i32,
+) -> Result<Vec<i32>, ApiResponse> {
+    let member_ids = get_members_by_club_id(db, club_id)
+        .await?
+        .iter()
+        .map(|membership| membership.user_id)
+        .collect::<Vec<i32>>();
+
+    entities::user::Entity::find()
+        .select_only()
+        .column(entities::user::Column::UserId)
+        .filter(
+            Condition::all()
+                .add(entities::user::Column::UserId.is_in(member_ids))
+                .add(entities::user::Column::UserType.eq(UserType::Athlete)),
+        )
+        .into_tuple::<i32>()
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, club_id))]
+pub async fn create_membership<C: ConnectionTrait>(
+    db: &C,
     claim_data: Claims,
     club_id: i32,
 ) -> Result<entities::club_member::Model, ApiResponse> {
     // Check if the user is a part of another club
-    if let Ok(_) = get_member_by_user_id(app_state, claim_data.user_id).await {
+    if get_member_by_user_id(db, claim_data.user_id).await.is_ok() {
         return Err(ApiResponse::new(
             409,
             "User is already a member of a club".to_string(),
         ));
     }
 
-    // Create the membership
-    match (entities::club_member::ActiveModel {
+    // Create the membership, the unique index catches a concurrent join the check above missed
+    entities::club_member::ActiveModel {
         user_id: Set(claim_data.user_id),
         club_id: Set(club_id),
         ..Default::default()
     }
-    .insert(&app_state.db)
-    .await)
-    {
-        Ok(member) => Ok(member),
-        Err(err) => Err(ApiResponse::new(500, err.to_string())),
-    }
+    .insert(db)
+    .await
+    .map_err(|err| ApiResponse::from_db_conflict(err, "User is already a member of a club"))
 }
 
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
 pub async fn leave_club(
     app_state: &web::Data<app_state::AppState>,
     claim_data: Claims,
 ) -> Result<ApiResponse, ApiResponse> {
-    // Ensure user is a member of a club
-    let member_result = get_member_by_user_id(app_state, claim_data.user_id).await;
-
-    if member_result.is_err() {
-        return Err(member_result.unwrap_err());
-    }
-
-    let membership = member_result.unwrap();
+    // Ownership can't change between the check and the delete
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
-    // Check if user is the owner
-    let result = club_service::is_owner(&app_state, claim_data.user_id, membership.club_id).await;
-    if result.is_err() {
-        return Err(result.unwrap_err());
-    }
+    // Ensure user is a member of a club
+    let membership = get_member_by_user_id(&txn, claim_data.user_id).await?;
 
-    // Reject if they are the owner
-    if result.unwrap() {
+    // Check if user is the owner, and reject if they are
+    if club_service::is_owner(&txn, claim_data.user_id, membership.club_id).await? {
         return Err(ApiResponse::new(
             409,
             "User cannot leave the club if they are the owner".to_string(),
@@ -97,14 +120,17 @@ pub async fn leave_club(
 
     // Delete the membership
     let delete_result = membership
-        .delete(&app_state.db)
+        .delete(&txn)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
     // Evaluate the result
     if delete_result.rows_affected == 1 {
-        return Ok(ApiResponse::new(200, "Successfully left club".to_string()));
+        txn.commit()
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        Ok(ApiResponse::new(200, "Successfully left club".to_string()))
     } else {
-        return Err(ApiResponse::new(500, "Could not leave club".to_string()));
+        Err(ApiResponse::new(500, "Could not leave club".to_string()))
     }
 }
@@ -1,11 +1,13 @@
 use actix_web::web;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter, Set, TransactionTrait,
 };
+use tracing::instrument;
 
 use crate::{
-    entities,
-    routes::services::{club_member_service, user_service},
+    entities::{self, sea_orm_active_enums::UserType},
+    routes::services::{club_member_service, two_factor_service, user_service},
     utils::{api_response::ApiResponse, app_state, jwt::Claims},
 };
 
@@ -32,8 +34,9 @@ use super::{club_member_service::get_member_by_user_id, user_service::get_user_b
 //     Ok(club)
 // }
 
-pub async fn get_club_by_id(
-    app_state: &web::Data<app_state::AppState>,
+#[instrument(skip_all, fields(club_id))]
+pub async fn get_club_by_id<C: ConnectionTrait>(
+    db: &C,
     club_id: i32,
 ) -> Result<entities::club::Model, ApiResponse> {
     // Search for clubs matching the input name
@@ -42,7 +45,7 @@ pub async fn get_club_by_id(
 
     // Get the club
     let club = query
-        .one(&app_state.db)
+        .one(db)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?
         .ok_or(ApiResponse::new(
@@ -53,6 +56,7 @@ pub async fn get_club_by_id(
     Ok(club)
 }
 
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
 pub async fn create_club(
     app_state: &web::Data<app_state::AppState>,
     claim_data: Claims,
@@ -62,27 +66,32 @@ pub async fn create_club(
     let filters = Some(
         Condition::all()
             .add(entities::user::Column::UserId.eq(claim_data.user_id))
-            .add(entities::user::Column::UserType.eq("C".to_string())),
+            .add(entities::user::Column::UserType.eq(UserType::Coach)),
     );
 
+    // The club and the owner's membership are created together or not at all
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
     // Search for a coach result with the current user_id
-    let coach_result = user_service::get_user(&app_state, claim_data.clone(), filters).await;
-
-    // Error handling/formatting result
-    if coach_result.is_err() {
-        if coach_result.as_ref().unwrap_err().status_code == 404 {
-            return Err(ApiResponse::new(
-                404,
-                "Coach account not found for that email".to_string(),
-            ));
-        } else {
-            return Err(coach_result.unwrap_err());
-        }
-    }
-    let coach = coach_result.unwrap();
+    let coach = user_service::get_user(&txn, claim_data.clone(), filters)
+        .await
+        .map_err(|err| {
+            // Error handling/formatting result
+            if err.status_code == 404 {
+                ApiResponse::new(404, "Coach account not found for that email".to_string())
+            } else {
+                err
+            }
+        })?;
 
     // Check if the coach is already a member of a club
-    if let Ok(_) = club_member_service::get_member_by_user_id(&app_state, claim_data.user_id).await
+    if club_member_service::get_member_by_user_id(&txn, claim_data.user_id)
+        .await
+        .is_ok()
     {
         return Err(ApiResponse::new(
             409,
@@ -91,11 +100,12 @@ pub async fn create_club(
     }
 
     // Check if the club already exists
-    if let Some(_) = entities::club::Entity::find()
+    if entities::club::Entity::find()
         .filter(entities::club::Column::Name.eq(club_name.clone().to_lowercase()))
-        .one(&app_state.db)
+        .one(&txn)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .is_some()
     {
         return Err(ApiResponse::new(
             409,
@@ -109,42 +119,41 @@ pub async fn create_club(
         owner_id: Set(coach.user_id),
         ..Default::default()
     }
-    .insert(&app_state.db)
+    .insert(&txn)
     .await
-    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    .map_err(|err| {
+        ApiResponse::from_db_conflict(
+            err,
+            "A club with that name already exists. Please try a different name",
+        )
+    })?;
 
     // Create the membership
-    let membership_result =
-        club_member_service::create_membership(&app_state, claim_data.clone(), club_model.club_id)
-            .await;
+    let membership =
+        club_member_service::create_membership(&txn, claim_data.clone(), club_model.club_id)
+            .await?;
 
-    // Return the created membership, or the error if something went wrong
-    if membership_result.is_ok() {
-        return Ok(ApiResponse::new(
-            200,
-            format!(
-                "{{ 'member_id': {}, 'club_id': {}, 'name': {} }}",
-                membership_result.unwrap().club_member_id,
-                club_model.club_id,
-                club_model.name
-            ),
-        ));
-    } else {
-        return Err(membership_result.unwrap_err());
-    }
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Return the created membership
+    Ok(ApiResponse::new(
+        200,
+        format!(
+            "{{ 'member_id': {}, 'club_id': {}, 'name': {} }}",
+            membership.club_member_id, club_model.club_id, club_model.name
+        ),
+    ))
 }
 
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
 pub async fn delete_club(
     app_state: &web::Data<app_state::AppState>,
     claim_data: Claims,
 ) -> Result<ApiResponse, ApiResponse> {
     // Check if user deleting is the club owner
-    let club = get_club_if_owner(&app_state, claim_data.user_id).await;
-
-    if club.is_err() {
-        return Err(club.unwrap_err());
-    }
-    let club = club.unwrap();
+    let club = get_club_if_owner(&app_state.db, claim_data.user_id).await?;
 
     // Delete the club
     let deleted_rows = club
@@ -155,30 +164,36 @@ pub async fn delete_club(
 
     // Validate deletion
     if deleted_rows.rows_affected == 1 {
-        return Ok(ApiResponse::new(
+        Ok(ApiResponse::new(
             200,
             "Club deleted successfully".to_string(),
-        ));
+        ))
     } else {
-        return Err(ApiResponse::new(
+        Err(ApiResponse::new(
             500,
             "Internal server error: Club could not be deleted".to_string(),
-        ));
+        ))
     }
 }
 
+#[instrument(skip_all, fields(user_id = claim_data.user_id, new_owner_id))]
 pub async fn transfer_ownership(
     app_state: &web::Data<app_state::AppState>,
     claim_data: Claims,
     new_owner_id: i32,
 ) -> Result<ApiResponse, ApiResponse> {
+    // Nobody can join, leave or take over the club while ownership moves
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
     // Get new owners user information
-    let user_result = get_user_by_id(&app_state, new_owner_id).await;
+    let new_owner = get_user_by_id(&txn, new_owner_id).await?;
 
     // Check that the new owner is a coach
-    if user_result.is_err() {
-        return Err(user_result.unwrap_err());
-    } else if user_result.unwrap().user_type != "C" {
+    if new_owner.user_type != UserType::Coach {
         return Err(ApiResponse::new(
             422,
             "New owner must be a coach".to_string(),
@@ -186,24 +201,13 @@ pub async fn transfer_ownership(
     }
 
     // Check that user owns the club
-    let club = get_club_if_owner(&app_state, claim_data.user_id).await;
+    let club = get_club_if_owner(&txn, claim_data.user_id).await?;
 
-    // Error handling/formatting
-    if club.is_err() {
-        return Err(club.unwrap_err());
-    }
-    let club = club.unwrap();
-
-    // Ensure the new owner is a coach in the club
-    let new_owner_membership = get_member_by_user_id(&app_state, new_owner_id).await;
-
-    // Handle if they are not a member of any club
-    if new_owner_membership.is_err() {
-        return Err(new_owner_membership.unwrap_err());
-    }
+    // Ensure the new owner is a coach in the club, failing if they are not a member of any club
+    let new_owner_membership = get_member_by_user_id(&txn, new_owner_id).await?;
 
     // Handle if they are not a member of the club
-    if new_owner_membership.unwrap().club_id != club.club_id {
+    if new_owner_membership.club_id != club.club_id {
         return Err(ApiResponse::new(
             403,
             "The new owner is not a member of the club".to_string(),
@@ -216,61 +220,80 @@ pub async fn transfer_ownership(
 
     // Update the club owner
     club_model
-        .update(&app_state.db)
+        .update(&txn)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
-    return Ok(ApiResponse::new(
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
         200,
         "Club owner updated successfully".to_string(),
-    ));
+    ))
 }
 
-pub async fn is_owner(
+#[instrument(skip_all, fields(user_id = claim_data.user_id, required))]
+pub async fn set_require_two_factor(
     app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    required: bool,
+) -> Result<ApiResponse, ApiResponse> {
+    // Check that user owns the club
+    let club = get_club_if_owner(&app_state.db, claim_data.user_id).await?;
+
+    // The owner has to lead by example, or they would lock themselves out
+    if required && !two_factor_service::is_enabled(app_state, claim_data.user_id).await? {
+        return Err(ApiResponse::new(
+            409,
+            "Enable two-factor authentication on your own account first".to_string(),
+        ));
+    }
+
+    let mut club_model = club.into_active_model();
+    club_model.require_two_factor = Set(required);
+
+    club_model
+        .update(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        format!("{{ 'require_two_factor': {} }}", required),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id, club_id))]
+pub async fn is_owner<C: ConnectionTrait>(
+    db: &C,
     user_id: i32,
     club_id: i32,
 ) -> Result<bool, ApiResponse> {
-    let club = get_club_by_id(&app_state, club_id).await;
-
-    // Error handling/formatting
-    if club.is_err() {
-        return Err(club.unwrap_err());
-    }
-    let club = club.unwrap();
+    let club = get_club_by_id(db, club_id).await?;
 
     // Make sure the user deleting is the owner
     Ok(club.owner_id == user_id)
 }
 
-pub async fn get_club_if_owner(
-    app_state: &web::Data<app_state::AppState>,
+#[instrument(skip_all, fields(user_id))]
+pub async fn get_club_if_owner<C: ConnectionTrait>(
+    db: &C,
     user_id: i32,
 ) -> Result<entities::club::Model, ApiResponse> {
     // Get the users membership
-    let membership = get_member_by_user_id(&app_state, user_id).await;
-
-    // Error handling/formatting
-    if membership.is_err() {
-        return Err(membership.unwrap_err());
-    }
-    let membership = membership.unwrap();
+    let membership = get_member_by_user_id(db, user_id).await?;
 
-    let club = get_club_by_id(&app_state, membership.club_id).await;
-
-    // Error handling/formatting
-    if club.is_err() {
-        return Err(club.unwrap_err());
-    }
-    let club = club.unwrap();
+    let club = get_club_by_id(db, membership.club_id).await?;
 
     // Make sure the user deleting is the owner
     if club.owner_id == user_id {
-        return Ok(club);
+        Ok(club)
     } else {
-        return Err(ApiResponse::new(
+        Err(ApiResponse::new(
             401,
             "User is not the owner of this club".to_string(),
-        ));
+        ))
     }
 }
@@ -0,0 +1,509 @@
+use std::collections::{BTreeMap, HashMap};
+
+use actix_web::web;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{self, sea_orm_active_enums::UserType},
+    utils::{
+        api_response::ApiResponse, app_state, jwt::Claims,
+        request_models::comment_models::CommentModel,
+    },
+};
+
+use super::{
+    club_member_service::{get_athlete_ids_by_club_id, get_member_by_user_id},
+    session_service::{ensure_can_view_athlete, get_session_by_id},
+    sync_pair_service::get_sync_turns,
+    turn_service::{ensure_can_view_turn, get_turn_by_id, roster_turns},
+    user_service::get_user_by_id,
+};
+
+const MAX_COMMENT_LENGTH: usize = 2000;
+
+// Comments can be left by anyone who can see the athlete's training: the athlete and the coaches
+// in their club. A synchronized turn's partner and their coaches can comment on that turn too
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn create_comment(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: CommentModel,
+) -> Result<ApiResponse, ApiResponse> {
+    let body = parse_body(&json.body)?;
+
+    let (session_id, turn_id, skill_id) = match (
+        json.session_id,
+        json.turn_id,
+        json.skill_id,
+        json.parent_comment_id,
+    ) {
+        (Some(session_id), None, None, None) => (session_id, None, None),
+        (None, Some(turn_id), None, None) => {
+            let turn = get_turn_by_id(&app_state.db, turn_id).await?;
+            (turn.session_id, Some(turn.turn_id), None)
+        }
+        (None, None, Some(skill_id), None) => {
+            let skill = get_skill_by_id(&app_state.db, skill_id).await?;
+            let turn = get_turn_by_id(&app_state.db, skill.turn_id).await?;
+            (turn.session_id, Some(turn.turn_id), Some(skill.skill_id))
+        }
+        (None, None, None, Some(parent_comment_id)) => {
+            let parent = get_comment_by_id(&app_state.db, parent_comment_id).await?;
+            if parent.deleted_at.is_some() {
+                return Err(ApiResponse::new(
+                    409,
+                    "Deleted comments cannot be replied to".to_string(),
+                ));
+            }
+            (parent.session_id, parent.turn_id, parent.skill_id)
+        }
+        _ => {
+            return Err(ApiResponse::new(
+                422,
+                "Comment on exactly one session, turn, skill or comment".to_string(),
+            ));
+        }
+    };
+
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+    ensure_can_view_thread(&app_state.db, claim_data.user_id, &session, turn_id).await?;
+
+    let comment = entities::comment::ActiveModel {
+        session_id: Set(session.session_id),
+        turn_id: Set(turn_id),
+        skill_id: Set(skill_id),
+        parent_comment_id: Set(json.parent_comment_id),
+        user_id: Set(claim_data.user_id),
+        body: Set(body),
+        created_at: Set(Utc::now().naive_utc()),
+        edited_at: Set(None),
+        deleted_at: Set(None),
+        ..Default::default()
+    }
+    .insert(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(201, comment_body(&comment, false)))
+}
+
+// The whole discussion of a session, oldest first. Replies point at the comment they answer.
+// A partner who only performed some of the session's synchronized turns sees those turns' threads
+#[instrument(skip_all, fields(user_id = claim_data.user_id, session_id))]
+pub async fn get_comments_by_session(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    session_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+    let visible = get_visible(&app_state.db, claim_data.user_id, &session).await?;
+
+    let comments = entities::comment::Entity::find()
+        .filter(entities::comment::Column::SessionId.eq(session.session_id))
+        .order_by_asc(entities::comment::Column::CommentId)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .filter(|comment| visible.includes(comment.turn_id))
+        .collect::<Vec<entities::comment::Model>>();
+    let read_at = get_read_at(&app_state.db, claim_data.user_id, vec![session.session_id])
+        .await?
+        .remove(&session.session_id);
+
+    let comments = comments
+        .iter()
+        .map(|comment| comment_body(comment, is_unread(comment, claim_data.user_id, read_at)))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", comments)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, comment_id))]
+pub async fn edit_comment(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    comment_id: i32,
+    body: String,
+) -> Result<ApiResponse, ApiResponse> {
+    let body = parse_body(&body)?;
+    let comment = get_own_comment(&app_state.db, claim_data.user_id, comment_id).await?;
+
+    let mut comment = comment.into_active_model();
+    comment.body = Set(body);
+    comment.edited_at = Set(Some(Utc::now().naive_utc()));
+    let comment = comment
+        .update(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, comment_body(&comment, false)))
+}
+
+// The comment stays in the thread with its text removed, so replies to it keep their place
+#[instrument(skip_all, fields(user_id = claim_data.user_id, comment_id))]
+pub async fn delete_comment(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    comment_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let comment = get_own_comment(&app_state.db, claim_data.user_id, comment_id).await?;
+
+    let mut comment = comment.into_active_model();
+    comment.body = Set(String::new());
+    comment.deleted_at = Set(Some(Utc::now().naive_utc()));
+    comment
+        .update(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Comment deleted successfully".to_string(),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, session_id))]
+pub async fn mark_session_read(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    session_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+    get_visible(&app_state.db, claim_data.user_id, &session).await?;
+
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let now = Utc::now().naive_utc();
+    let comment_read = entities::comment_read::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::comment_read::Column::UserId.eq(claim_data.user_id))
+                .add(entities::comment_read::Column::SessionId.eq(session.session_id)),
+        )
+        .one(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    match comment_read {
+        Some(comment_read) => {
+            let mut comment_read = comment_read.into_active_model();
+            comment_read.read_at = Set(now);
+            comment_read.update(&txn).await
+        }
+        None => {
+            entities::comment_read::ActiveModel {
+                user_id: Set(claim_data.user_id),
+                session_id: Set(session.session_id),
+                read_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+        }
+    }
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        format!(
+            "{{ 'session_id': {}, 'read_at': {} }}",
+            session.session_id, now
+        ),
+    ))
+}
+
+// Sessions with comments the user has not read yet: their own sessions for an athlete, and every
+// session in the club for a coach, along with the threads of synchronized turns they performed
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn get_unread(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+) -> Result<ApiResponse, ApiResponse> {
+    let user = get_user_by_id(&app_state.db, claim_data.user_id).await?;
+    let athlete_ids = match user.user_type {
+        UserType::Athlete => vec![user.user_id],
+        UserType::Coach => get_club_athletes(&app_state.db, user.user_id).await?,
+    };
+
+    let performed_turn_ids = entities::turn::Entity::find()
+        .select_only()
+        .column(entities::turn::Column::TurnId)
+        .filter(roster_turns(&app_state.db, &athlete_ids).await?)
+        .into_tuple::<i32>()
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let comments = entities::comment::Entity::find()
+        .find_also_related(entities::session::Entity)
+        .filter(
+            Condition::all()
+                .add(
+                    Condition::any()
+                        .add(entities::session::Column::UserId.is_in(athlete_ids))
+                        .add(entities::comment::Column::TurnId.is_in(performed_turn_ids)),
+                )
+                .add(entities::comment::Column::UserId.ne(user.user_id))
+                .add(entities::comment::Column::DeletedAt.is_null()),
+        )
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    let read_at = get_read_at(
+        &app_state.db,
+        user.user_id,
+        comments
+            .iter()
+            .map(|(comment, _)| comment.session_id)
+            .collect(),
+    )
+    .await?;
+
+    // Unread comments per session, keyed by session id
+    let mut unread = BTreeMap::<i32, (i32, u32)>::new();
+    for (comment, session) in &comments {
+        let Some(session) = session else {
+            continue;
+        };
+        if is_unread(
+            comment,
+            user.user_id,
+            read_at.get(&comment.session_id).copied(),
+        ) {
+            unread
+                .entry(session.session_id)
+                .or_insert((session.user_id, 0))
+                .1 += 1;
+        }
+    }
+
+    let unread = unread
+        .iter()
+        .map(|(session_id, (athlete_id, count))| {
+            format!(
+                "{{ 'session_id': {}, 'athlete_id': {}, 'unread': {} }}",
+                session_id, athlete_id, count
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", unread)))
+}
+
+async fn get_comment_by_id<C: ConnectionTrait>(
+    db: &C,
+    comment_id: i32,
+) -> Result<entities::comment::Model, ApiResponse> {
+    entities::comment::Entity::find_by_id(comment_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Comment not found".to_string()))
+}
+
+// Only the author can change a comment, and only while they can still see its thread
+async fn get_own_comment<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    comment_id: i32,
+) -> Result<entities::comment::Model, ApiResponse> {
+    let comment = get_comment_by_id(db, comment_id).await?;
+    if comment.user_id != user_id {
+        return Err(ApiResponse::new(
+            401,
+            "Only the author can change a comment".to_string(),
+        ));
+    }
+    if comment.deleted_at.is_some() {
+        return Err(ApiResponse::new(
+            409,
+            "Comment has already been deleted".to_string(),
+        ));
+    }
+
+    let session = get_session_by_id(db, comment.session_id).await?;
+    ensure_can_view_thread(db, user_id, &session, comment.turn_id).await?;
+
+    Ok(comment)
+}
+
+// Session threads follow the athlete's training, turn and skill threads follow the turn
+async fn ensure_can_view_thread<C: ConnectionTrait>(
+    db: &C,
+    viewer_id: i32,
+    session: &entities::session::Model,
+    turn_id: Option<i32>,
+) -> Result<(), ApiResponse> {
+    let Some(turn_id) = turn_id else {
+        return ensure_can_view_athlete(db, viewer_id, session.user_id).await;
+    };
+
+    let turn = get_turn_by_id(db, turn_id).await?;
+    let sync_turn = get_sync_turns(db, vec![turn.turn_id])
+        .await?
+        .remove(&turn.turn_id);
+    ensure_can_view_turn(db, viewer_id, &turn, sync_turn.as_ref()).await
+}
+
+// The part of a session's discussion a user can see
+enum Visible {
+    Session,
+    Turns(Vec<i32>),
+}
+
+impl Visible {
+    fn includes(&self, turn_id: Option<i32>) -> bool {
+        match self {
+            Visible::Session => true,
+            Visible::Turns(turn_ids) => turn_id.is_some_and(|turn_id| turn_ids.contains(&turn_id)),
+        }
+    }
+}
+
+// All of it for the athlete and their coaches, otherwise the synchronized turns the user can see
+// through the partner, and nothing when there are none
+async fn get_visible<C: ConnectionTrait>(
+    db: &C,
+    viewer_id: i32,
+    session: &entities::session::Model,
+) -> Result<Visible, ApiResponse> {
+    let denied = match ensure_can_view_athlete(db, viewer_id, session.user_id).await {
+        Ok(()) => return Ok(Visible::Session),
+        Err(denied) => denied,
+    };
+
+    let turns = entities::turn::Entity::find()
+        .filter(entities::turn::Column::SessionId.eq(session.session_id))
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    let sync_turns = get_sync_turns(db, turns.iter().map(|turn| turn.turn_id).collect()).await?;
+
+    let mut turn_ids = Vec::new();
+    for turn in &turns {
+        let Some(sync_turn) = sync_turns.get(&turn.turn_id) else {
+            continue;
+        };
+        match ensure_can_view_turn(db, viewer_id, turn, Some(sync_turn)).await {
+            Ok(()) => turn_ids.push(turn.turn_id),
+            Err(err) if err.status_code == 401 => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    if turn_ids.is_empty() {
+        return Err(denied);
+    }
+
+    Ok(Visible::Turns(turn_ids))
+}
+
+async fn get_skill_by_id<C: ConnectionTrait>(
+    db: &C,
+    skill_id: i32,
+) -> Result<entities::skill::Model, ApiResponse> {
+    entities::skill::Entity::find_by_id(skill_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Skill not found".to_string()))
+}
+
+// The athletes in the coach's club, none when they are not in one
+async fn get_club_athletes<C: ConnectionTrait>(
+    db: &C,
+    coach_id: i32,
+) -> Result<Vec<i32>, ApiResponse> {
+    let Ok(membership) = get_member_by_user_id(db, coach_id).await else {
+        return Ok(Vec::new());
+    };
+
+    get_athlete_ids_by_club_id(db, membership.club_id).await
+}
+
+// When the user last read each session's comments, keyed by session id
+async fn get_read_at<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    session_ids: Vec<i32>,
+) -> Result<HashMap<i32, NaiveDateTime>, ApiResponse> {
+    let comment_reads = entities::comment_read::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::comment_read::Column::UserId.eq(user_id))
+                .add(entities::comment_read::Column::SessionId.is_in(session_ids)),
+        )
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(comment_reads
+        .into_iter()
+        .map(|comment_read| (comment_read.session_id, comment_read.read_at))
+        .collect())
+}
+
+// A user's own comments and deleted comments are never unread
+fn is_unread(
+    comment: &entities::comment::Model,
+    user_id: i32,
+    read_at: Option<NaiveDateTime>,
+) -> bool {
+    comment.user_id != user_id
+        && comment.deleted_at.is_none()
+        && read_at.is_none_or(|read_at| comment.created_at > read_at)
+}
+
+fn parse_body(body: &str) -> Result<String, ApiResponse> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(ApiResponse::new(
+            422,
+            "A comment cannot be empty".to_string(),
+        ));
+    }
+    if body.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(ApiResponse::new(
+            422,
+            format!("A comment can be at most {} characters", MAX_COMMENT_LENGTH),
+        ));
+    }
+
+    Ok(body.to_string())
+}
+
+fn comment_body(comment: &entities::comment::Model, unread: bool) -> String {
+    let optional = |value: Option<i32>| value.map_or("null".to_string(), |id| id.to_string());
+
+    format!(
+        "{{ 'comment_id': {}, 'session_id': {}, 'turn_id': {}, 'skill_id': {}, 'parent_comment_id': {}, 'user_id': {}, 'body': {}, 'created_at': {}, 'edited_at': {}, 'deleted': {}, 'unread': {} }}",
+        comment.comment_id,
+        comment.session_id,
+        optional(comment.turn_id),
+        optional(comment.skill_id),
+        optional(comment.parent_comment_id),
+        comment.user_id,
+        comment.body,
+        comment.created_at,
+        comment
+            .edited_at
+            .map_or("null".to_string(), |edited_at| edited_at.to_string()),
+        comment.deleted_at.is_some(),
+        unread,
+    )
+}
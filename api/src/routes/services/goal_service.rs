@@ -0,0 +1,432 @@
+use actix_web::web;
+use chrono::{Days, NaiveDate, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait,
+    IntoActiveModel, ModelTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{
+        self,
+        sea_orm_active_enums::{Event, GoalKind, Position, UserType},
+    },
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::goal_models::GoalModel,
+        scoring::{self, Notation},
+    },
+};
+
+use super::{
+    session_service::ensure_can_view_athlete, turn_service::athlete_turns,
+    user_service::get_user_by_id,
+};
+
+const MAX_DIFFICULTY_TARGET_TENTHS: u32 = 500;
+const MAX_CONSISTENT_TARGET: u32 = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+    Active,
+    Completed,
+    Missed,
+}
+
+impl GoalStatus {
+    pub fn parse(status: &str) -> Option<GoalStatus> {
+        match status {
+            "ACTIVE" => Some(GoalStatus::Active),
+            "COMPLETED" => Some(GoalStatus::Completed),
+            "MISSED" => Some(GoalStatus::Missed),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            GoalStatus::Active => "ACTIVE",
+            GoalStatus::Completed => "COMPLETED",
+            GoalStatus::Missed => "MISSED",
+        }
+    }
+}
+
+// Athletes set their own goals, coaches can set them for the athletes in their club
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id = json.athlete_id))]
+pub async fn create_goal(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: GoalModel,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, json.athlete_id).await?;
+    let athlete = get_user_by_id(&app_state.db, json.athlete_id).await?;
+    if athlete.user_type != UserType::Athlete {
+        return Err(ApiResponse::new(
+            422,
+            "Goals can only be set for athletes".to_string(),
+        ));
+    }
+
+    let kind = GoalKind::try_from_value(&json.kind).map_err(|_| {
+        ApiResponse::new(
+            422,
+            "Invalid kind, must be DIFFICULTY or CONSISTENT_SKILL".to_string(),
+        )
+    })?;
+    let event = Event::try_from_value(&json.event)
+        .map_err(|_| ApiResponse::new(422, "Invalid event, must be DMT, TRA or TUM".to_string()))?;
+    let deadline = NaiveDate::parse_from_str(&json.deadline, "%Y-%m-%d")
+        .map_err(|_| ApiResponse::new(422, "Invalid deadline, must be YYYY-MM-DD".to_string()))?;
+    if deadline < Utc::now().date_naive() {
+        return Err(ApiResponse::new(
+            422,
+            "The deadline can't be in the past".to_string(),
+        ));
+    }
+    if kind == GoalKind::Difficulty && !scoring::has_tariff(event) {
+        return Err(ApiResponse::new(
+            422,
+            "DIFFICULTY goals are only for TRA, other events have no tariff".to_string(),
+        ));
+    }
+    let (target, skill) = parse_target(kind, &json)?;
+
+    // The goal may already be reached by training logged earlier today
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let goal = entities::goal::ActiveModel {
+        athlete_id: Set(athlete.user_id),
+        set_by: Set(claim_data.user_id),
+        kind: Set(kind),
+        event_id: Set(event),
+        target: Set(target as i32),
+        fig_rep: Set(skill.map(|(fig_rep, _)| fig_rep)),
+        position: Set(skill.map(|(_, position)| position)),
+        deadline: Set(deadline),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    evaluate_goals(&txn, &[athlete.user_id], event).await?;
+    let goal = get_goal_by_id(&txn, goal.goal_id).await?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        201,
+        get_goal_body(&app_state.db, &goal).await?,
+    ))
+}
+
+// The athlete's goals with their progress, the nearest deadline first
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_goals_by_athlete(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+    status: Option<GoalStatus>,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let goals = entities::goal::Entity::find()
+        .filter(entities::goal::Column::AthleteId.eq(athlete_id))
+        .order_by_asc(entities::goal::Column::Deadline)
+        .order_by_asc(entities::goal::Column::GoalId)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let today = Utc::now().date_naive();
+    let mut bodies = Vec::new();
+    for goal in &goals {
+        if status.is_some_and(|status| status != goal_status(goal, today)) {
+            continue;
+        }
+        bodies.push(get_goal_body(&app_state.db, goal).await?);
+    }
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", bodies.join(", "))))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, goal_id))]
+pub async fn get_goal(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    goal_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let goal = get_goal_by_id(&app_state.db, goal_id).await?;
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, goal.athlete_id).await?;
+
+    Ok(ApiResponse::new(
+        200,
+        get_goal_body(&app_state.db, &goal).await?,
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, goal_id))]
+pub async fn delete_goal(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    goal_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let goal = get_goal_by_id(&app_state.db, goal_id).await?;
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, goal.athlete_id).await?;
+
+    goal.delete(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Goal deleted successfully".to_string(),
+    ))
+}
+
+// Marks the athletes' open goals in an event completed once their training reaches them. Called
+// whenever a turn is logged or judged, inside the same transaction. A completed goal stays
+// completed even if the turns that reached it are later deleted
+#[instrument(skip_all, fields(?athlete_ids, ?event))]
+pub async fn evaluate_goals<C: ConnectionTrait>(
+    db: &C,
+    athlete_ids: &[i32],
+    event: Event,
+) -> Result<(), ApiResponse> {
+    let now = Utc::now().naive_utc();
+    let goals = entities::goal::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::goal::Column::AthleteId.is_in(athlete_ids.to_vec()))
+                .add(entities::goal::Column::EventId.eq(event))
+                .add(entities::goal::Column::CompletedAt.is_null())
+                .add(entities::goal::Column::Deadline.gte(now.date())),
+        )
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    for goal in goals {
+        if get_current(db, &goal).await? < goal.target as u32 {
+            continue;
+        }
+
+        let mut goal = goal.into_active_model();
+        goal.completed_at = Set(Some(now));
+        goal.update(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    Ok(())
+}
+
+#[instrument(skip_all, fields(goal_id))]
+pub async fn get_goal_by_id<C: ConnectionTrait>(
+    db: &C,
+    goal_id: i32,
+) -> Result<entities::goal::Model, ApiResponse> {
+    entities::goal::Entity::find_by_id(goal_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Goal not found".to_string()))
+}
+
+// A DD is given in points and kept in tenths, a number of landings must be whole
+fn parse_target(
+    kind: GoalKind,
+    json: &GoalModel,
+) -> Result<(u32, Option<(i32, Position)>), ApiResponse> {
+    match kind {
+        GoalKind::Difficulty => {
+            if json.fig_rep.is_some() || json.position.is_some() {
+                return Err(ApiResponse::new(
+                    422,
+                    "Only CONSISTENT_SKILL goals are for one skill".to_string(),
+                ));
+            }
+            let target = scoring::to_tenths(json.target, MAX_DIFFICULTY_TARGET_TENTHS)
+                .filter(|target| *target > 0)
+                .ok_or(ApiResponse::new(
+                    422,
+                    format!(
+                        "A DIFFICULTY target must be a DD from 0.1 to {}",
+                        scoring::to_points(MAX_DIFFICULTY_TARGET_TENTHS)
+                    ),
+                ))?;
+
+            Ok((target, None))
+        }
+        GoalKind::ConsistentSkill => {
+            if json.target.fract() != 0.0
+                || !(1.0..=MAX_CONSISTENT_TARGET as f32).contains(&json.target)
+            {
+                return Err(ApiResponse::new(
+                    422,
+                    format!(
+                        "A CONSISTENT_SKILL target must be a whole number of landings from 1 to {}",
+                        MAX_CONSISTENT_TARGET
+                    ),
+                ));
+            }
+            let fig_rep = json
+                .fig_rep
+                .filter(|fig_rep| Notation::parse(*fig_rep).is_some())
+                .ok_or(ApiResponse::new(
+                    422,
+                    "Invalid fig_rep, expected FIG notation such as 41".to_string(),
+                ))?;
+            let position = json
+                .position
+                .as_ref()
+                .and_then(|position| Position::try_from_value(position).ok())
+                .ok_or(ApiResponse::new(
+                    422,
+                    "Invalid position, must be TUCK, PIKE, STRAIGHT or NONE".to_string(),
+                ))?;
+
+            Ok((json.target as u32, Some((fig_rep, position))))
+        }
+    }
+}
+
+fn goal_status(goal: &entities::goal::Model, today: NaiveDate) -> GoalStatus {
+    if goal.completed_at.is_some() {
+        GoalStatus::Completed
+    } else if goal.deadline < today {
+        GoalStatus::Missed
+    } else {
+        GoalStatus::Active
+    }
+}
+
+// Only training in sessions started from the day the goal was set up to its deadline counts. The
+// best turn's DD for a DIFFICULTY goal, the consistent landings of the skill for a CONSISTENT_SKILL
+// goal. A synchronized turn counts for both partners
+async fn get_current<C: ConnectionTrait>(
+    db: &C,
+    goal: &entities::goal::Model,
+) -> Result<u32, ApiResponse> {
+    let from = goal.created_at.date().and_time(NaiveDateTime::MIN.time());
+    let until = goal
+        .deadline
+        .checked_add_days(Days::new(1))
+        .unwrap_or(goal.deadline)
+        .and_time(NaiveDateTime::MIN.time());
+
+    let turns = entities::turn::Entity::find()
+        .inner_join(entities::session::Entity)
+        .filter(
+            Condition::all()
+                .add(athlete_turns(db, goal.athlete_id).await?)
+                .add(entities::turn::Column::EventId.eq(goal.event_id))
+                .add(entities::session::Column::TimeStart.gte(from))
+                .add(entities::session::Column::TimeStart.lt(until)),
+        )
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    match goal.kind {
+        GoalKind::Difficulty => Ok(turns
+            .iter()
+            .map(|turn| scoring::nearest_tenths(turn.total_difficulty))
+            .max()
+            .unwrap_or(0)),
+        // Only judged attempts say whether the skill was landed consistently
+        GoalKind::ConsistentSkill => {
+            let deductions = entities::skill::Entity::find()
+                .select_only()
+                .column(entities::skill::Column::Deduction)
+                .filter(
+                    Condition::all()
+                        .add(
+                            entities::skill::Column::TurnId
+                                .is_in(turns.iter().map(|turn| turn.turn_id).collect::<Vec<i32>>()),
+                        )
+                        .add(entities::skill::Column::FigRep.eq(goal.fig_rep))
+                        .add(entities::skill::Column::Position.eq(goal.position))
+                        .add(entities::skill::Column::Deduction.is_not_null()),
+                )
+                .into_tuple::<Option<f32>>()
+                .all(db)
+                .await
+                .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+            Ok(deductions
+                .into_iter()
+                .flatten()
+                .filter(|deduction| scoring::is_consistent(scoring::nearest_tenths(*deduction)))
+                .count() as u32)
+        }
+    }
+}
+
+async fn get_goal_body<C: ConnectionTrait>(
+    db: &C,
+    goal: &entities::goal::Model,
+) -> Result<String, ApiResponse> {
+    let current = get_current(db, goal).await?;
+    let name = match (goal.fig_rep, goal.position) {
+        (Some(fig_rep), Some(position)) => entities::catalogue_skill::Entity::find()
+            .filter(
+                Condition::all()
+                    .add(entities::catalogue_skill::Column::EventId.eq(goal.event_id))
+                    .add(entities::catalogue_skill::Column::FigRep.eq(fig_rep))
+                    .add(entities::catalogue_skill::Column::Position.eq(position)),
+            )
+            .one(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?
+            .map(|catalogue_skill| catalogue_skill.name),
+        _ => None,
+    };
+
+    Ok(goal_body(goal, current, name))
+}
+
+fn goal_body(goal: &entities::goal::Model, current: u32, name: Option<String>) -> String {
+    let target = goal.target as u32;
+    // DD targets are shown in points like everywhere else
+    let show = |value: u32| match goal.kind {
+        GoalKind::Difficulty => scoring::to_points(value).to_string(),
+        GoalKind::ConsistentSkill => value.to_string(),
+    };
+    let status = goal_status(goal, Utc::now().date_naive());
+    let progress = match status {
+        GoalStatus::Completed => 100,
+        _ => scoring::percentage(current.min(target), target),
+    };
+
+    format!(
+        "{{ 'goal_id': {}, 'athlete_id': {}, 'set_by': {}, 'kind': {}, 'event_id': {}, 'target': {}, 'fig_rep': {}, 'position': {}, 'name': {}, 'deadline': {}, 'created_at': {}, 'completed_at': {}, 'current': {}, 'progress': {}, 'status': {} }}",
+        goal.goal_id,
+        goal.athlete_id,
+        goal.set_by,
+        goal.kind.to_value(),
+        goal.event_id.to_value(),
+        show(target),
+        goal.fig_rep.map_or("null".to_string(), |fig_rep| fig_rep.to_string()),
+        goal.position
+            .map_or("null".to_string(), |position| position.to_value()),
+        name.as_deref().unwrap_or("null"),
+        goal.deadline,
+        goal.created_at,
+        goal.completed_at
+            .map_or("null".to_string(), |completed_at| completed_at.to_string()),
+        show(current),
+        progress,
+        status.as_str(),
+    )
+}
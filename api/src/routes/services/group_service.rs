@@ -0,0 +1,308 @@
+use actix_web::web;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{self, sea_orm_active_enums::UserType},
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::group_models::{GroupMembersModel, GroupModel},
+    },
+};
+
+use super::{
+    club_member_service::{get_athlete_ids_by_club_id, get_member_by_user_id},
+    club_service::get_club_by_id,
+    user_service::get_user_by_id,
+};
+
+// Groups belong to the club of the coach who creates them
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn create_group(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: GroupModel,
+) -> Result<ApiResponse, ApiResponse> {
+    let club_id = get_coach_club_id(&app_state.db, claim_data.user_id).await?;
+    let name = json.name.trim();
+    if name.is_empty() {
+        return Err(ApiResponse::new(422, "A group needs a name".to_string()));
+    }
+    let athlete_ids = parse_athletes(&app_state.db, club_id, json.athlete_ids).await?;
+
+    // The group and its members are written together or not at all
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let group = entities::training_group::ActiveModel {
+        club_id: Set(club_id),
+        name: Set(name.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| {
+        ApiResponse::from_db_conflict(err, "A group with that name already exists in the club")
+    })?;
+    insert_members(&txn, group.training_group_id, &athlete_ids).await?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(201, group_body(&group, &athlete_ids)))
+}
+
+// Replaces the group's members with the athletes given
+#[instrument(skip_all, fields(user_id = claim_data.user_id, group_id))]
+pub async fn set_members(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    group_id: i32,
+    json: GroupMembersModel,
+) -> Result<ApiResponse, ApiResponse> {
+    let group = get_group_by_id(&app_state.db, group_id).await?;
+    ensure_group_coach(&app_state.db, claim_data.user_id, &group).await?;
+    let athlete_ids = parse_athletes(&app_state.db, group.club_id, json.athlete_ids).await?;
+
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    entities::training_group_member::Entity::delete_many()
+        .filter(
+            entities::training_group_member::Column::TrainingGroupId.eq(group.training_group_id),
+        )
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    insert_members(&txn, group.training_group_id, &athlete_ids).await?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, group_body(&group, &athlete_ids)))
+}
+
+// The club's groups in the order they were created
+#[instrument(skip_all, fields(user_id = claim_data.user_id, club_id))]
+pub async fn get_groups_by_club(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    club_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    get_club_by_id(&app_state.db, club_id).await?;
+    if get_coach_club_id(&app_state.db, claim_data.user_id).await? != club_id {
+        return Err(not_club_coach());
+    }
+
+    let groups = entities::training_group::Entity::find()
+        .filter(entities::training_group::Column::ClubId.eq(club_id))
+        .order_by_asc(entities::training_group::Column::TrainingGroupId)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let mut bodies = Vec::new();
+    for group in &groups {
+        let athlete_ids = get_group_athlete_ids(&app_state.db, group).await?;
+        bodies.push(group_body(group, &athlete_ids));
+    }
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", bodies.join(", "))))
+}
+
+// Plans already assigned through the group stay with its athletes
+#[instrument(skip_all, fields(user_id = claim_data.user_id, group_id))]
+pub async fn delete_group(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    group_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let group = get_group_by_id(&app_state.db, group_id).await?;
+    ensure_group_coach(&app_state.db, claim_data.user_id, &group).await?;
+
+    // Members reference the group, so they go first
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    entities::training_group_member::Entity::delete_many()
+        .filter(
+            entities::training_group_member::Column::TrainingGroupId.eq(group.training_group_id),
+        )
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    group
+        .delete(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Group deleted successfully".to_string(),
+    ))
+}
+
+// The athletes currently in the club's groups, for assigning a plan to them
+pub async fn get_athlete_ids_by_group_ids<C: ConnectionTrait>(
+    db: &C,
+    club_id: i32,
+    group_ids: &[i32],
+) -> Result<Vec<i32>, ApiResponse> {
+    let mut athlete_ids = Vec::new();
+    for group_id in group_ids {
+        let group = get_group_by_id(db, *group_id).await?;
+        if group.club_id != club_id {
+            return Err(ApiResponse::new(
+                422,
+                "Plans can only be assigned to the club's groups".to_string(),
+            ));
+        }
+        athlete_ids.extend(get_group_athlete_ids(db, &group).await?);
+    }
+
+    Ok(athlete_ids)
+}
+
+#[instrument(skip_all, fields(group_id))]
+async fn get_group_by_id<C: ConnectionTrait>(
+    db: &C,
+    group_id: i32,
+) -> Result<entities::training_group::Model, ApiResponse> {
+    entities::training_group::Entity::find_by_id(group_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Group not found".to_string()))
+}
+
+// Athletes who have left the club since they were added are no longer counted as members
+async fn get_group_athlete_ids<C: ConnectionTrait>(
+    db: &C,
+    group: &entities::training_group::Model,
+) -> Result<Vec<i32>, ApiResponse> {
+    let club_athlete_ids = get_athlete_ids_by_club_id(db, group.club_id).await?;
+
+    Ok(group
+        .find_related(entities::training_group_member::Entity)
+        .order_by_asc(entities::training_group_member::Column::AthleteId)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .iter()
+        .map(|member| member.athlete_id)
+        .filter(|athlete_id| club_athlete_ids.contains(athlete_id))
+        .collect())
+}
+
+async fn get_coach_club_id<C: ConnectionTrait>(db: &C, user_id: i32) -> Result<i32, ApiResponse> {
+    let user = get_user_by_id(db, user_id).await?;
+    match user.user_type {
+        UserType::Coach => get_member_by_user_id(db, user_id)
+            .await
+            .ok()
+            .map(|membership| membership.club_id),
+        UserType::Athlete => None,
+    }
+    .ok_or(not_club_coach())
+}
+
+async fn ensure_group_coach<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    group: &entities::training_group::Model,
+) -> Result<(), ApiResponse> {
+    if get_coach_club_id(db, user_id).await? != group.club_id {
+        return Err(not_club_coach());
+    }
+
+    Ok(())
+}
+
+fn not_club_coach() -> ApiResponse {
+    ApiResponse::new(
+        401,
+        "Only the club's coaches can manage its groups".to_string(),
+    )
+}
+
+// Sorted and without repeats, every one an athlete in the club
+async fn parse_athletes<C: ConnectionTrait>(
+    db: &C,
+    club_id: i32,
+    mut athlete_ids: Vec<i32>,
+) -> Result<Vec<i32>, ApiResponse> {
+    athlete_ids.sort_unstable();
+    athlete_ids.dedup();
+
+    let club_athlete_ids = get_athlete_ids_by_club_id(db, club_id).await?;
+    if athlete_ids
+        .iter()
+        .any(|athlete_id| !club_athlete_ids.contains(athlete_id))
+    {
+        return Err(ApiResponse::new(
+            422,
+            "Groups can only contain athletes in the club".to_string(),
+        ));
+    }
+
+    Ok(athlete_ids)
+}
+
+async fn insert_members<C: ConnectionTrait>(
+    db: &C,
+    group_id: i32,
+    athlete_ids: &[i32],
+) -> Result<(), ApiResponse> {
+    if athlete_ids.is_empty() {
+        return Ok(());
+    }
+
+    entities::training_group_member::Entity::insert_many(athlete_ids.iter().map(|athlete_id| {
+        entities::training_group_member::ActiveModel {
+            training_group_id: Set(group_id),
+            athlete_id: Set(*athlete_id),
+            ..Default::default()
+        }
+    }))
+    .exec(db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(())
+}
+
+fn group_body(group: &entities::training_group::Model, athlete_ids: &[i32]) -> String {
+    format!(
+        "{{ 'group_id': {}, 'club_id': {}, 'name': {}, 'athlete_ids': [ {} ] }}",
+        group.training_group_id,
+        group.club_id,
+        group.name,
+        athlete_ids
+            .iter()
+            .map(|athlete_id| athlete_id.to_string())
+            .collect::<Vec<String>>()
+            .join(", "),
+    )
+}
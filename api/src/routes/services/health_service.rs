@@ -0,0 +1,40 @@
+use actix_web::web;
+use migration::{Migrator, MigratorTrait};
+use tracing::instrument;
+
+use crate::utils::{api_response::ApiResponse, app_state};
+
+// Ready means the database answers and its schema matches the migrations built into this binary
+#[instrument(skip_all)]
+pub async fn check_ready(app_state: &web::Data<app_state::AppState>) -> Result<(), ApiResponse> {
+    app_state
+        .db
+        .ping()
+        .await
+        .map_err(|err| ApiResponse::new(503, format!("Database unavailable: {}", err)))?;
+
+    let pending = Migrator::get_pending_migrations(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(503, format!("Could not check migrations: {}", err)))?;
+
+    if !pending.is_empty() {
+        return Err(ApiResponse::new(
+            503,
+            format!("{} migrations are pending", pending.len()),
+        ));
+    }
+
+    Ok(())
+}
+
+// The name of the latest applied migration
+#[instrument(skip_all)]
+pub async fn schema_version(
+    app_state: &web::Data<app_state::AppState>,
+) -> Result<Option<String>, ApiResponse> {
+    let applied = Migrator::get_applied_migrations(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(applied.last().map(|migration| migration.name().to_string()))
+}
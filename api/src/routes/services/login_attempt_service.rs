@@ -0,0 +1,245 @@
+use actix_web::web;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    Set,
+};
+use tracing::instrument;
+
+use crate::{
+    entities,
+    utils::{api_response::ApiResponse, app_state, config::LoginConfig},
+};
+use entities::sea_orm_active_enums::LockoutScope;
+
+// Rejects the login if either the account or the IP address is currently locked out
+#[instrument(skip_all)]
+pub async fn check_lockout(
+    app_state: &web::Data<app_state::AppState>,
+    email: &str,
+    ip_address: &str,
+) -> Result<(), ApiResponse> {
+    let now = Utc::now().naive_utc();
+
+    let lockout = entities::account_lockout::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::account_lockout::Column::ClearedAt.is_null())
+                .add(entities::account_lockout::Column::LockedUntil.gt(now))
+                .add(
+                    Condition::any()
+                        .add(
+                            Condition::all()
+                                .add(
+                                    entities::account_lockout::Column::Scope
+                                        .eq(LockoutScope::Account),
+                                )
+                                .add(
+                                    entities::account_lockout::Column::Subject
+                                        .eq(normalize_email(email)),
+                                ),
+                        )
+                        .add(
+                            Condition::all()
+                                .add(entities::account_lockout::Column::Scope.eq(LockoutScope::Ip))
+                                .add(entities::account_lockout::Column::Subject.eq(ip_address)),
+                        ),
+                ),
+        )
+        .order_by_desc(entities::account_lockout::Column::LockedUntil)
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    match lockout {
+        Some(lockout) => Err(ApiResponse::new(
+            429,
+            format!(
+                "Too many failed login attempts. Try again in {} seconds",
+                (lockout.locked_until - now).num_seconds().max(1)
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+// Records a login attempt, locking the account or IP address if it has failed too many times
+#[instrument(skip_all, fields(successful))]
+pub async fn record_attempt(
+    app_state: &web::Data<app_state::AppState>,
+    email: &str,
+    ip_address: &str,
+    successful: bool,
+) -> Result<(), ApiResponse> {
+    let email = normalize_email(email);
+    let now = Utc::now().naive_utc();
+
+    entities::login_attempt::ActiveModel {
+        email: Set(email.clone()),
+        ip_address: Set(ip_address.to_string()),
+        successful: Set(successful),
+        attempted_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if successful {
+        return Ok(());
+    }
+
+    // Failures against the account stop counting once the user logs in successfully
+    let last_success = entities::login_attempt::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::login_attempt::Column::Email.eq(&email))
+                .add(entities::login_attempt::Column::Successful.eq(true)),
+        )
+        .order_by_desc(entities::login_attempt::Column::AttemptedAt)
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .map(|attempt| attempt.attempted_at);
+
+    let account_since =
+        counting_since(app_state, LockoutScope::Account, &email, last_success).await?;
+    let account_failures = entities::login_attempt::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::login_attempt::Column::Email.eq(&email))
+                .add(entities::login_attempt::Column::Successful.eq(false))
+                .add(entities::login_attempt::Column::AttemptedAt.gt(account_since)),
+        )
+        .count(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let login_config = app_state.config.login;
+
+    if account_failures >= login_config.account_lockout_threshold {
+        create_lockout(
+            app_state,
+            LockoutScope::Account,
+            &email,
+            account_failures,
+            login_config.account_lockout_threshold,
+        )
+        .await?;
+    }
+
+    // Failures from an IP address keep counting across accounts, so one success does not reset them
+    let ip_since = counting_since(app_state, LockoutScope::Ip, ip_address, None).await?;
+    let ip_failures = entities::login_attempt::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::login_attempt::Column::IpAddress.eq(ip_address))
+                .add(entities::login_attempt::Column::Successful.eq(false))
+                .add(entities::login_attempt::Column::AttemptedAt.gt(ip_since)),
+        )
+        .count(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if ip_failures >= login_config.ip_lockout_threshold {
+        create_lockout(
+            app_state,
+            LockoutScope::Ip,
+            ip_address,
+            ip_failures,
+            login_config.ip_lockout_threshold,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Attempts older than the window never count towards a lockout, so they can be dropped
+#[instrument(skip_all)]
+pub async fn prune_attempts(app_state: &web::Data<app_state::AppState>) -> Result<(), ApiResponse> {
+    let cutoff =
+        Utc::now().naive_utc() - Duration::hours(app_state.config.login.attempt_window_hours);
+
+    let result = entities::login_attempt::Entity::delete_many()
+        .filter(entities::login_attempt::Column::AttemptedAt.lt(cutoff))
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    tracing::debug!("Pruned {} login attempts", result.rows_affected);
+    Ok(())
+}
+
+// Finds the point in time from which failed attempts count towards a lockout
+async fn counting_since(
+    app_state: &web::Data<app_state::AppState>,
+    scope: LockoutScope,
+    subject: &str,
+    last_success: Option<NaiveDateTime>,
+) -> Result<NaiveDateTime, ApiResponse> {
+    let mut since =
+        Utc::now().naive_utc() - Duration::hours(app_state.config.login.attempt_window_hours);
+
+    if let Some(last_success) = last_success {
+        since = since.max(last_success);
+    }
+
+    // An admin clearing a lockout gives the subject a clean slate
+    let last_cleared = entities::account_lockout::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::account_lockout::Column::Scope.eq(scope))
+                .add(entities::account_lockout::Column::Subject.eq(subject))
+                .add(entities::account_lockout::Column::ClearedAt.is_not_null()),
+        )
+        .order_by_desc(entities::account_lockout::Column::ClearedAt)
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .and_then(|lockout| lockout.cleared_at);
+
+    if let Some(last_cleared) = last_cleared {
+        since = since.max(last_cleared);
+    }
+
+    Ok(since)
+}
+
+async fn create_lockout(
+    app_state: &web::Data<app_state::AppState>,
+    scope: LockoutScope,
+    subject: &str,
+    failures: u64,
+    threshold: u64,
+) -> Result<(), ApiResponse> {
+    let now = Utc::now().naive_utc();
+
+    entities::account_lockout::ActiveModel {
+        scope: Set(scope),
+        subject: Set(subject.to_string()),
+        failed_attempts: Set(failures as i32),
+        locked_at: Set(now),
+        locked_until: Set(now + lockout_duration(&app_state.config.login, failures, threshold)),
+        ..Default::default()
+    }
+    .insert(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(())
+}
+
+// Doubles the lockout for every failure past the threshold, up to the configured maximum
+pub fn lockout_duration(config: &LoginConfig, failures: u64, threshold: u64) -> Duration {
+    let exponent = failures.saturating_sub(threshold).min(32) as u32;
+    let seconds = config
+        .lockout_base_seconds
+        .saturating_mul(2_i64.saturating_pow(exponent));
+
+    Duration::seconds(seconds.min(config.lockout_max_seconds))
+}
+
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
@@ -1,4 +1,18 @@
+pub mod admin_service;
+pub mod analytics_service;
 pub mod auth_service;
 pub mod club_member_service;
 pub mod club_service;
+pub mod comment_service;
+pub mod goal_service;
+pub mod group_service;
+pub mod health_service;
+pub mod login_attempt_service;
+pub mod plan_service;
+pub mod session_service;
+pub mod skill_service;
+pub mod stats_service;
+pub mod sync_pair_service;
+pub mod turn_service;
+pub mod two_factor_service;
 pub mod user_service;
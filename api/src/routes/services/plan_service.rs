@@ -0,0 +1,703 @@
+use actix_web::web;
+use chrono::{NaiveDate, Utc};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{
+        self,
+        sea_orm_active_enums::{Event, Position, UserType},
+    },
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::plan_models::{AssignPlanModel, PlanItemModel, PlanModel},
+        scoring::{self, Notation},
+    },
+};
+
+use super::{
+    club_member_service::{get_athlete_ids_by_club_id, get_member_by_user_id},
+    club_service::get_club_by_id,
+    group_service::get_athlete_ids_by_group_ids,
+    session_service::ensure_can_view_athlete,
+    turn_service::athlete_turns,
+    user_service::get_user_by_id,
+};
+
+const MAX_PLAN_ITEMS: usize = 20;
+const MAX_REPETITIONS: u32 = 100;
+
+// One prescribed item: a skill counted wherever it is landed, or a whole turn of exactly these
+// skills in this order
+struct PlanItem {
+    whole_turn: bool,
+    repetitions: u32,
+    skills: Vec<(i32, Position)>,
+}
+
+// How far one athlete has got with a plan, with how many times each item has been done
+struct Progress {
+    athlete_id: i32,
+    sessions: usize,
+    done: Vec<u32>,
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn create_plan(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: PlanModel,
+) -> Result<ApiResponse, ApiResponse> {
+    // Plans belong to the club of the coach who writes them
+    let coach = get_user_by_id(&app_state.db, claim_data.user_id).await?;
+    let membership = match coach.user_type {
+        UserType::Coach => get_member_by_user_id(&app_state.db, coach.user_id)
+            .await
+            .ok(),
+        UserType::Athlete => None,
+    }
+    .ok_or(ApiResponse::new(
+        401,
+        "Only coaches in a club can create plans".to_string(),
+    ))?;
+
+    let event = Event::try_from_value(&json.event)
+        .map_err(|_| ApiResponse::new(422, "Invalid event, must be DMT, TRA or TUM".to_string()))?;
+    let scheduled_for =
+        NaiveDate::parse_from_str(&json.scheduled_for, "%Y-%m-%d").map_err(|_| {
+            ApiResponse::new(
+                422,
+                "Invalid scheduled_for date, must be YYYY-MM-DD".to_string(),
+            )
+        })?;
+    let name = json.name.trim();
+    if name.is_empty() {
+        return Err(ApiResponse::new(422, "A plan needs a name".to_string()));
+    }
+    let items = parse_items(&json.items)?;
+
+    // The plan and its items are written together or not at all
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let plan = entities::plan::ActiveModel {
+        club_id: Set(membership.club_id),
+        coach_id: Set(coach.user_id),
+        event_id: Set(event),
+        scheduled_for: Set(scheduled_for),
+        name: Set(name.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Items and their skills are numbered in the order they were given
+    for (index, item) in items.iter().enumerate() {
+        let plan_item = entities::plan_item::ActiveModel {
+            plan_id: Set(plan.plan_id),
+            item_num: Set(index as i32 + 1),
+            whole_turn: Set(item.whole_turn),
+            repetitions: Set(item.repetitions as i32),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        entities::plan_item_skill::Entity::insert_many(item.skills.iter().enumerate().map(
+            |(index, (fig_rep, position))| entities::plan_item_skill::ActiveModel {
+                plan_item_id: Set(plan_item.plan_item_id),
+                skill_num: Set(index as i32 + 1),
+                fig_rep: Set(*fig_rep),
+                position: Set(*position),
+                ..Default::default()
+            },
+        ))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(201, plan_body(&plan, &items, &[])))
+}
+
+// Assigning a plan again to an athlete who already has it leaves them as they were. Groups are
+// expanded to their athletes when the plan is assigned, later changes to a group don't follow
+#[instrument(skip_all, fields(user_id = claim_data.user_id, plan_id))]
+pub async fn assign_plan(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    plan_id: i32,
+    json: AssignPlanModel,
+) -> Result<ApiResponse, ApiResponse> {
+    let plan = get_plan_by_id(&app_state.db, plan_id).await?;
+    ensure_club_coach(&app_state.db, claim_data.user_id, plan.club_id).await?;
+
+    let club_athlete_ids = get_athlete_ids_by_club_id(&app_state.db, plan.club_id).await?;
+    let mut athlete_ids = json.athlete_ids;
+    if json.whole_club.unwrap_or(false) {
+        athlete_ids.extend(&club_athlete_ids);
+    }
+    if let Some(group_ids) = json.group_ids {
+        athlete_ids
+            .extend(get_athlete_ids_by_group_ids(&app_state.db, plan.club_id, &group_ids).await?);
+    }
+    athlete_ids.sort_unstable();
+    athlete_ids.dedup();
+
+    if athlete_ids.is_empty() {
+        return Err(ApiResponse::new(
+            422,
+            "Assign the plan to at least one athlete".to_string(),
+        ));
+    }
+    if athlete_ids
+        .iter()
+        .any(|athlete_id| !club_athlete_ids.contains(athlete_id))
+    {
+        return Err(ApiResponse::new(
+            422,
+            "Plans can only be assigned to athletes in the club".to_string(),
+        ));
+    }
+
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let assigned = get_assigned_athletes(&txn, plan.plan_id).await?;
+    let now = Utc::now().naive_utc();
+    for athlete_id in athlete_ids {
+        if assigned.contains(&athlete_id) {
+            continue;
+        }
+        entities::plan_assignment::ActiveModel {
+            plan_id: Set(plan.plan_id),
+            athlete_id: Set(athlete_id),
+            assigned_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|err| {
+            ApiResponse::from_db_conflict(err, "The plan is already assigned to this athlete")
+        })?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let assigned = get_assigned_athletes(&app_state.db, plan.plan_id).await?;
+    Ok(ApiResponse::new(
+        200,
+        get_plan_body(&app_state.db, &plan, &assigned).await?,
+    ))
+}
+
+// Coaches see how every athlete on the plan is getting on, athletes only see themselves
+#[instrument(skip_all, fields(user_id = claim_data.user_id, plan_id))]
+pub async fn get_plan(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    plan_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let plan = get_plan_by_id(&app_state.db, plan_id).await?;
+    let assigned = get_assigned_athletes(&app_state.db, plan.plan_id).await?;
+
+    let athlete_ids = if ensure_club_coach(&app_state.db, claim_data.user_id, plan.club_id)
+        .await
+        .is_ok()
+    {
+        assigned
+    } else if assigned.contains(&claim_data.user_id) {
+        vec![claim_data.user_id]
+    } else {
+        return Err(ApiResponse::new(
+            401,
+            "Only the club's coaches and the plan's athletes can view it".to_string(),
+        ));
+    };
+
+    Ok(ApiResponse::new(
+        200,
+        get_plan_body(&app_state.db, &plan, &athlete_ids).await?,
+    ))
+}
+
+// Every plan assigned to the athlete with their progress on it, the latest first
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_plans_by_athlete(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let plans = entities::plan::Entity::find()
+        .inner_join(entities::plan_assignment::Entity)
+        .filter(entities::plan_assignment::Column::AthleteId.eq(athlete_id))
+        .order_by_desc(entities::plan::Column::ScheduledFor)
+        .order_by_desc(entities::plan::Column::PlanId)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let mut bodies = Vec::new();
+    for plan in &plans {
+        bodies.push(get_plan_body(&app_state.db, plan, &[athlete_id]).await?);
+    }
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", bodies.join(", "))))
+}
+
+// Every plan in the club with the progress of each athlete it is assigned to, the latest first
+#[instrument(skip_all, fields(user_id = claim_data.user_id, club_id))]
+pub async fn get_plans_by_club(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    club_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    get_club_by_id(&app_state.db, club_id).await?;
+    ensure_club_coach(&app_state.db, claim_data.user_id, club_id).await?;
+
+    let plans = entities::plan::Entity::find()
+        .filter(entities::plan::Column::ClubId.eq(club_id))
+        .order_by_desc(entities::plan::Column::ScheduledFor)
+        .order_by_desc(entities::plan::Column::PlanId)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let mut bodies = Vec::new();
+    for plan in &plans {
+        let assigned = get_assigned_athletes(&app_state.db, plan.plan_id).await?;
+        bodies.push(get_plan_body(&app_state.db, plan, &assigned).await?);
+    }
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", bodies.join(", "))))
+}
+
+// Sessions trained against the plan are kept, they just no longer point at it
+#[instrument(skip_all, fields(user_id = claim_data.user_id, plan_id))]
+pub async fn delete_plan(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    plan_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let plan = get_plan_by_id(&txn, plan_id).await?;
+    ensure_club_coach(&txn, claim_data.user_id, plan.club_id).await?;
+
+    entities::session::Entity::update_many()
+        .set(entities::session::ActiveModel {
+            plan_id: Set(None),
+            ..Default::default()
+        })
+        .filter(entities::session::Column::PlanId.eq(plan.plan_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Assignments, items and their skills reference the plan, so they go first
+    let plan_item_ids = entities::plan_item::Entity::find()
+        .filter(entities::plan_item::Column::PlanId.eq(plan.plan_id))
+        .all(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .iter()
+        .map(|plan_item| plan_item.plan_item_id)
+        .collect::<Vec<i32>>();
+    entities::plan_item_skill::Entity::delete_many()
+        .filter(entities::plan_item_skill::Column::PlanItemId.is_in(plan_item_ids))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    entities::plan_item::Entity::delete_many()
+        .filter(entities::plan_item::Column::PlanId.eq(plan.plan_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    entities::plan_assignment::Entity::delete_many()
+        .filter(entities::plan_assignment::Column::PlanId.eq(plan.plan_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    plan.delete(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Plan deleted successfully".to_string(),
+    ))
+}
+
+#[instrument(skip_all, fields(plan_id))]
+pub async fn get_plan_by_id<C: ConnectionTrait>(
+    db: &C,
+    plan_id: i32,
+) -> Result<entities::plan::Model, ApiResponse> {
+    entities::plan::Entity::find_by_id(plan_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Plan not found".to_string()))
+}
+
+// A session can only be trained against a plan the athlete has been assigned
+pub async fn get_assigned_plan<C: ConnectionTrait>(
+    db: &C,
+    athlete_id: i32,
+    plan_id: i32,
+) -> Result<entities::plan::Model, ApiResponse> {
+    let plan = get_plan_by_id(db, plan_id).await?;
+    if !get_assigned_athletes(db, plan.plan_id)
+        .await?
+        .contains(&athlete_id)
+    {
+        return Err(ApiResponse::new(
+            401,
+            "Only athletes the plan is assigned to can train against it".to_string(),
+        ));
+    }
+
+    Ok(plan)
+}
+
+async fn ensure_club_coach<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    club_id: i32,
+) -> Result<(), ApiResponse> {
+    let user = get_user_by_id(db, user_id).await?;
+    let in_club = get_member_by_user_id(db, user_id)
+        .await
+        .is_ok_and(|membership| membership.club_id == club_id);
+    if user.user_type != UserType::Coach || !in_club {
+        return Err(ApiResponse::new(
+            401,
+            "Only the club's coaches can manage its plans".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn get_assigned_athletes<C: ConnectionTrait>(
+    db: &C,
+    plan_id: i32,
+) -> Result<Vec<i32>, ApiResponse> {
+    Ok(entities::plan_assignment::Entity::find()
+        .filter(entities::plan_assignment::Column::PlanId.eq(plan_id))
+        .order_by_asc(entities::plan_assignment::Column::AthleteId)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .iter()
+        .map(|plan_assignment| plan_assignment.athlete_id)
+        .collect())
+}
+
+async fn get_items<C: ConnectionTrait>(db: &C, plan_id: i32) -> Result<Vec<PlanItem>, ApiResponse> {
+    let plan_items = entities::plan_item::Entity::find()
+        .filter(entities::plan_item::Column::PlanId.eq(plan_id))
+        .order_by_asc(entities::plan_item::Column::ItemNum)
+        .find_with_related(entities::plan_item_skill::Entity)
+        .order_by_asc(entities::plan_item_skill::Column::SkillNum)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(plan_items
+        .into_iter()
+        .map(|(plan_item, skills)| PlanItem {
+            whole_turn: plan_item.whole_turn,
+            repetitions: plan_item.repetitions as u32,
+            skills: skills
+                .iter()
+                .map(|skill| (skill.fig_rep, skill.position))
+                .collect(),
+        })
+        .collect())
+}
+
+// Matches the athlete's turns in sessions trained against the plan to its items. A synchronized
+// turn counts for both partners when it was logged in a session against the plan
+async fn get_progress<C: ConnectionTrait>(
+    db: &C,
+    plan: &entities::plan::Model,
+    items: &[PlanItem],
+    athlete_id: i32,
+) -> Result<Progress, ApiResponse> {
+    let sessions = entities::session::Entity::find()
+        .filter(entities::session::Column::PlanId.eq(plan.plan_id))
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let turns = entities::turn::Entity::find()
+        .filter(
+            Condition::all()
+                .add(
+                    entities::turn::Column::SessionId.is_in(
+                        sessions
+                            .iter()
+                            .map(|session| session.session_id)
+                            .collect::<Vec<i32>>(),
+                    ),
+                )
+                .add(athlete_turns(db, athlete_id).await?),
+        )
+        .find_with_related(entities::skill::Entity)
+        .order_by_asc(entities::skill::Column::SkillNum)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .map(|(_, skills)| {
+            skills
+                .iter()
+                .map(|skill| (skill.fig_rep, skill.position))
+                .collect::<Vec<(i32, Position)>>()
+        })
+        .collect::<Vec<_>>();
+
+    let done = items
+        .iter()
+        .map(|item| {
+            let count = if item.whole_turn {
+                turns
+                    .iter()
+                    .filter(|skills| **skills == item.skills)
+                    .count()
+            } else {
+                turns
+                    .iter()
+                    .flatten()
+                    .filter(|skill| item.skills.contains(skill))
+                    .count()
+            };
+            count as u32
+        })
+        .collect();
+
+    Ok(Progress {
+        athlete_id,
+        sessions: sessions
+            .iter()
+            .filter(|session| session.user_id == athlete_id)
+            .count(),
+        done,
+    })
+}
+
+async fn get_plan_body<C: ConnectionTrait>(
+    db: &C,
+    plan: &entities::plan::Model,
+    athlete_ids: &[i32],
+) -> Result<String, ApiResponse> {
+    let items = get_items(db, plan.plan_id).await?;
+
+    let mut progress = Vec::new();
+    for athlete_id in athlete_ids {
+        progress.push(get_progress(db, plan, &items, *athlete_id).await?);
+    }
+
+    Ok(plan_body(plan, &items, &progress))
+}
+
+fn parse_items(items: &[PlanItemModel]) -> Result<Vec<PlanItem>, ApiResponse> {
+    if items.is_empty() || items.len() > MAX_PLAN_ITEMS {
+        return Err(ApiResponse::new(
+            422,
+            format!("A plan must have between 1 and {} items", MAX_PLAN_ITEMS),
+        ));
+    }
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let item_num = index + 1;
+
+            let whole_turn = match item.kind.as_str() {
+                "SKILL" if item.skills.len() == 1 => false,
+                "SKILL" => {
+                    return Err(ApiResponse::new(
+                        422,
+                        format!("Item {} is a SKILL and needs exactly one skill", item_num),
+                    ));
+                }
+                "TURN" if (1..=scoring::MAX_SKILLS).contains(&item.skills.len()) => true,
+                "TURN" => {
+                    return Err(ApiResponse::new(
+                        422,
+                        format!(
+                            "Item {} is a TURN and needs between 1 and {} skills",
+                            item_num,
+                            scoring::MAX_SKILLS
+                        ),
+                    ));
+                }
+                _ => {
+                    return Err(ApiResponse::new(
+                        422,
+                        format!("Item {} has an invalid kind, must be SKILL or TURN", item_num),
+                    ));
+                }
+            };
+            if !(1..=MAX_REPETITIONS).contains(&item.repetitions) {
+                return Err(ApiResponse::new(
+                    422,
+                    format!(
+                        "Item {} repetitions must be from 1 to {}",
+                        item_num, MAX_REPETITIONS
+                    ),
+                ));
+            }
+
+            let skills = item
+                .skills
+                .iter()
+                .enumerate()
+                .map(|(index, skill)| {
+                    let skill_num = index + 1;
+
+                    if Notation::parse(skill.fig_rep).is_none() {
+                        return Err(ApiResponse::new(
+                            422,
+                            format!(
+                                "Item {} skill {} has an invalid fig_rep, expected FIG notation such as 41",
+                                item_num, skill_num
+                            ),
+                        ));
+                    }
+                    let position = Position::try_from_value(&skill.position).map_err(|_| {
+                        ApiResponse::new(
+                            422,
+                            format!(
+                                "Item {} skill {} has an invalid position, must be TUCK, PIKE, STRAIGHT or NONE",
+                                item_num, skill_num
+                            ),
+                        )
+                    })?;
+
+                    Ok((skill.fig_rep, position))
+                })
+                .collect::<Result<Vec<(i32, Position)>, ApiResponse>>()?;
+
+            Ok(PlanItem {
+                whole_turn,
+                repetitions: item.repetitions,
+                skills,
+            })
+        })
+        .collect()
+}
+
+fn plan_body(plan: &entities::plan::Model, items: &[PlanItem], progress: &[Progress]) -> String {
+    let items_body = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let skills = item
+                .skills
+                .iter()
+                .map(|(fig_rep, position)| {
+                    format!(
+                        "{{ 'fig_rep': {}, 'position': {} }}",
+                        fig_rep,
+                        position.to_value()
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            let difficulty = scoring::has_tariff(plan.event_id).then(|| {
+                item.skills
+                    .iter()
+                    .filter_map(|(fig_rep, position)| {
+                        Notation::parse(*fig_rep)
+                            .and_then(|notation| notation.difficulty_tenths(plan.event_id, *position))
+                    })
+                    .sum()
+            });
+
+            format!(
+                "{{ 'item_num': {}, 'kind': {}, 'skills': [ {} ], 'repetitions': {}, 'difficulty': {} }}",
+                index + 1,
+                if item.whole_turn { "TURN" } else { "SKILL" },
+                skills,
+                item.repetitions,
+                difficulty.map_or("null".to_string(), |tenths| scoring::to_points(tenths).to_string()),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    // Doing an item more often than prescribed doesn't make up for another item
+    let repetitions = items.iter().map(|item| item.repetitions).sum();
+    let athletes_body = progress
+        .iter()
+        .map(|progress| {
+            let prescribed = items
+                .iter()
+                .zip(&progress.done)
+                .map(|(item, done)| (*done).min(item.repetitions))
+                .sum();
+            let done = progress
+                .done
+                .iter()
+                .map(|done| done.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            format!(
+                "{{ 'athlete_id': {}, 'sessions': {}, 'done': [ {} ], 'progress': {}, 'completed': {} }}",
+                progress.athlete_id,
+                progress.sessions,
+                done,
+                scoring::percentage(prescribed, repetitions),
+                prescribed == repetitions,
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        "{{ 'plan_id': {}, 'club_id': {}, 'coach_id': {}, 'event_id': {}, 'scheduled_for': {}, 'name': {}, 'items': [ {} ], 'athletes': [ {} ] }}",
+        plan.plan_id,
+        plan.club_id,
+        plan.coach_id,
+        plan.event_id.to_value(),
+        plan.scheduled_for,
+        plan.name,
+        items_body,
+        athletes_body,
+    )
+}
@@ -0,0 +1,280 @@
+use actix_web::web;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait,
+    EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{
+        self,
+        sea_orm_active_enums::{Event, SessionStatus, UserType},
+    },
+    utils::{api_response::ApiResponse, app_state, jwt::Claims},
+};
+
+use super::{
+    club_member_service::get_member_by_user_id, plan_service::get_assigned_plan,
+    user_service::get_user_by_id,
+};
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn start_session(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    event: Event,
+    summary: String,
+    plan_id: Option<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    // Only athletes log training
+    let athlete = get_user_by_id(&app_state.db, claim_data.user_id).await?;
+    if athlete.user_type != UserType::Athlete {
+        return Err(ApiResponse::new(
+            401,
+            "Only athletes can start sessions".to_string(),
+        ));
+    }
+
+    // A session trained against a plan counts towards it, so it has to be for the plan's event
+    if let Some(plan_id) = plan_id {
+        let plan = get_assigned_plan(&app_state.db, athlete.user_id, plan_id).await?;
+        if plan.event_id != event {
+            return Err(ApiResponse::new(
+                422,
+                "The session must be in the plan's event".to_string(),
+            ));
+        }
+    }
+
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // An athlete trains one session at a time
+    if get_open_session(&txn, athlete.user_id).await?.is_some() {
+        return Err(ApiResponse::new(
+            409,
+            "A session is already open, end it before starting another".to_string(),
+        ));
+    }
+
+    // Create the session, the unique index catches a concurrent start the check above missed
+    let now = Utc::now().naive_utc();
+    let session = entities::session::ActiveModel {
+        user_id: Set(athlete.user_id),
+        event_id: Set(event),
+        time_start: Set(now),
+        summary: Set(summary),
+        status: Set(SessionStatus::Open),
+        time_end: Set(None),
+        last_activity_at: Set(Some(now)),
+        plan_id: Set(plan_id),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| {
+        ApiResponse::from_db_conflict(
+            err,
+            "A session is already open, end it before starting another",
+        )
+    })?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    app_state.metrics.session_logged(event);
+
+    Ok(ApiResponse::new(201, session_body(&session)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, session_id))]
+pub async fn end_session(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    session_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+
+    if session.user_id != claim_data.user_id {
+        return Err(ApiResponse::new(
+            401,
+            "Only the athlete can end their session".to_string(),
+        ));
+    }
+
+    // Only an open session is updated, so a concurrent end or the sweeper can't be overwritten
+    let result = entities::session::Entity::update_many()
+        .set(entities::session::ActiveModel {
+            status: Set(SessionStatus::Closed),
+            time_end: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        })
+        .filter(
+            Condition::all()
+                .add(entities::session::Column::SessionId.eq(session_id))
+                .add(entities::session::Column::Status.eq(SessionStatus::Open)),
+        )
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(ApiResponse::new(
+            409,
+            "Session has already ended".to_string(),
+        ));
+    }
+
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+    Ok(ApiResponse::new(200, session_body(&session)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, session_id))]
+pub async fn get_session(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    session_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, session.user_id).await?;
+
+    Ok(ApiResponse::new(200, session_body(&session)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_sessions_by_athlete(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    // Most recent sessions first
+    let sessions = entities::session::Entity::find()
+        .filter(entities::session::Column::UserId.eq(athlete_id))
+        .order_by_desc(entities::session::Column::TimeStart)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let sessions = sessions
+        .iter()
+        .map(session_body)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", sessions)))
+}
+
+// Closes open sessions that have had no new turns for the idle period. The session is
+// recorded as ending at its last activity, not when the sweeper noticed it
+pub async fn close_idle_sessions(
+    app_state: &web::Data<app_state::AppState>,
+) -> Result<(), ApiResponse> {
+    let cutoff = Utc::now().naive_utc() - Duration::minutes(app_state.config.session_idle_minutes);
+
+    let result = entities::session::Entity::update_many()
+        .set(entities::session::ActiveModel {
+            status: Set(SessionStatus::AutoClosed),
+            ..Default::default()
+        })
+        .col_expr(
+            entities::session::Column::TimeEnd,
+            Expr::col(entities::session::Column::LastActivityAt).into(),
+        )
+        .filter(
+            Condition::all()
+                .add(entities::session::Column::Status.eq(SessionStatus::Open))
+                .add(entities::session::Column::LastActivityAt.lt(cutoff)),
+        )
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    tracing::debug!("Closed {} idle sessions", result.rows_affected);
+    Ok(())
+}
+
+#[instrument(skip_all, fields(session_id))]
+pub async fn get_session_by_id<C: ConnectionTrait>(
+    db: &C,
+    session_id: i32,
+) -> Result<entities::session::Model, ApiResponse> {
+    entities::session::Entity::find_by_id(session_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Session not found".to_string()))
+}
+
+pub async fn get_open_session<C: ConnectionTrait>(
+    db: &C,
+    athlete_id: i32,
+) -> Result<Option<entities::session::Model>, ApiResponse> {
+    entities::session::Entity::find()
+        .filter(
+            Condition::all()
+                .add(entities::session::Column::UserId.eq(athlete_id))
+                .add(entities::session::Column::Status.eq(SessionStatus::Open)),
+        )
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+// Training records are visible to the athlete and to coaches in the athlete's club
+pub async fn ensure_can_view_athlete<C: ConnectionTrait>(
+    db: &C,
+    viewer_id: i32,
+    athlete_id: i32,
+) -> Result<(), ApiResponse> {
+    if viewer_id == athlete_id {
+        return Ok(());
+    }
+
+    let denied = ApiResponse::new(
+        401,
+        "Only the athlete and their coaches can access their training".to_string(),
+    );
+
+    let viewer = get_user_by_id(db, viewer_id).await?;
+    if viewer.user_type != UserType::Coach {
+        return Err(denied);
+    }
+
+    let (Ok(coach_membership), Ok(athlete_membership)) = (
+        get_member_by_user_id(db, viewer_id).await,
+        get_member_by_user_id(db, athlete_id).await,
+    ) else {
+        return Err(denied);
+    };
+
+    if coach_membership.club_id != athlete_membership.club_id {
+        return Err(denied);
+    }
+
+    Ok(())
+}
+
+fn session_body(session: &entities::session::Model) -> String {
+    format!(
+        "{{ 'session_id': {}, 'user_id': {}, 'event_id': {}, 'status': {}, 'time_start': {}, 'time_end': {}, 'summary': {}, 'plan_id': {} }}",
+        session.session_id,
+        session.user_id,
+        session.event_id.to_value(),
+        session.status.to_value(),
+        session.time_start,
+        session
+            .time_end
+            .map_or("null".to_string(), |time_end| time_end.to_string()),
+        session.summary,
+        session
+            .plan_id
+            .map_or("null".to_string(), |plan_id| plan_id.to_string()),
+    )
+}
@@ -0,0 +1,275 @@
+use std::collections::{BTreeMap, HashMap};
+
+use actix_web::web;
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, ModelTrait,
+    QueryFilter, Set,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{
+        self,
+        sea_orm_active_enums::{Event, Position},
+    },
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::skill_models::CatalogueSkillModel,
+        scoring::{self, Notation},
+    },
+};
+
+use super::{
+    admin_service::ensure_admin, session_service::ensure_can_view_athlete,
+    turn_service::athlete_turns,
+};
+
+// How often an athlete has tried one skill, a figure in a given shape. Only judged attempts
+// say whether it was landed consistently
+#[derive(Default)]
+struct Attempts {
+    attempts: u32,
+    judged: u32,
+    consistent: u32,
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn get_catalogue(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    event: Option<Event>,
+) -> Result<ApiResponse, ApiResponse> {
+    let catalogue_skills = get_catalogue_skills(&app_state.db, event).await?;
+
+    let catalogue_skills = catalogue_skills
+        .iter()
+        .map(catalogue_skill_body)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", catalogue_skills)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, fig_rep = json.fig_rep))]
+pub async fn create_catalogue_skill(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: CatalogueSkillModel,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_admin(app_state, &claim_data).await?;
+
+    let event = Event::try_from_value(&json.event)
+        .map_err(|_| ApiResponse::new(422, "Invalid event, must be DMT, TRA or TUM".to_string()))?;
+    if Notation::parse(json.fig_rep).is_none() {
+        return Err(ApiResponse::new(
+            422,
+            "Invalid fig_rep, expected FIG notation such as 41".to_string(),
+        ));
+    }
+    let position = Position::try_from_value(&json.position).map_err(|_| {
+        ApiResponse::new(
+            422,
+            "Invalid position, must be TUCK, PIKE, STRAIGHT or NONE".to_string(),
+        )
+    })?;
+    let name = json.name.trim();
+    if name.is_empty() {
+        return Err(ApiResponse::new(422, "A skill needs a name".to_string()));
+    }
+
+    let catalogue_skill = entities::catalogue_skill::ActiveModel {
+        event_id: Set(event),
+        fig_rep: Set(json.fig_rep),
+        position: Set(position),
+        name: Set(name.to_string()),
+        ..Default::default()
+    }
+    .insert(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::from_db_conflict(err, "This skill is already in the catalogue"))?;
+
+    Ok(ApiResponse::new(
+        201,
+        catalogue_skill_body(&catalogue_skill),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, catalogue_skill_id))]
+pub async fn delete_catalogue_skill(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    catalogue_skill_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_admin(app_state, &claim_data).await?;
+
+    let catalogue_skill = entities::catalogue_skill::Entity::find_by_id(catalogue_skill_id)
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(
+            404,
+            "Catalogue skill not found".to_string(),
+        ))?;
+
+    catalogue_skill
+        .delete(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Catalogue skill deleted successfully".to_string(),
+    ))
+}
+
+// Every skill the athlete has performed, with when they first landed it, how often they have
+// tried it and how many of the judged attempts were consistent
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_repertoire(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+    event: Option<Event>,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let mut performed = Condition::all().add(athlete_turns(&app_state.db, athlete_id).await?);
+    if let Some(event) = event {
+        performed = performed.add(entities::skill::Column::EventId.eq(event));
+    }
+    let skills = entities::skill::Entity::find()
+        .inner_join(entities::turn::Entity)
+        .filter(performed)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Sorted by event, figure and shape
+    let mut repertoire = BTreeMap::<(String, i32, String), (Event, Position, Attempts)>::new();
+    for skill in &skills {
+        let (_, _, attempts) = repertoire
+            .entry(skill_key(skill.event_id, skill.fig_rep, skill.position))
+            .or_insert((skill.event_id, skill.position, Attempts::default()));
+        attempts.attempts += 1;
+        if let Some(deduction) = skill.deduction {
+            attempts.judged += 1;
+            if scoring::is_consistent(scoring::nearest_tenths(deduction)) {
+                attempts.consistent += 1;
+            }
+        }
+    }
+
+    let names = get_catalogue_skills(&app_state.db, event)
+        .await?
+        .into_iter()
+        .map(|catalogue_skill| {
+            (
+                skill_key(
+                    catalogue_skill.event_id,
+                    catalogue_skill.fig_rep,
+                    catalogue_skill.position,
+                ),
+                catalogue_skill.name,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let first_landed = entities::first_skill::Entity::find()
+        .filter(entities::first_skill::Column::UserId.eq(athlete_id))
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .map(|first_skill| {
+            (
+                skill_key(
+                    first_skill.event_id,
+                    first_skill.fig_rep,
+                    first_skill.position,
+                ),
+                first_skill.achieved_at,
+            )
+        })
+        .collect::<HashMap<_, NaiveDateTime>>();
+
+    let repertoire = repertoire
+        .iter()
+        .map(|(key, (event, position, attempts))| {
+            format!(
+                "{{ 'event_id': {}, 'fig_rep': {}, 'position': {}, 'name': {}, 'difficulty': {}, 'first_landed_at': {}, 'attempts': {}, 'judged': {}, 'consistent': {}, 'consistency_rate': {} }}",
+                event.to_value(),
+                key.1,
+                position.to_value(),
+                names.get(key).map_or("null", |name| name.as_str()),
+                difficulty(*event, key.1, *position),
+                first_landed
+                    .get(key)
+                    .map_or("null".to_string(), |achieved_at| achieved_at.to_string()),
+                attempts.attempts,
+                attempts.judged,
+                attempts.consistent,
+                scoring::percentage(attempts.consistent, attempts.judged),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", repertoire)))
+}
+
+// Sorted by event, figure and shape
+async fn get_catalogue_skills<C: ConnectionTrait>(
+    db: &C,
+    event: Option<Event>,
+) -> Result<Vec<entities::catalogue_skill::Model>, ApiResponse> {
+    let mut query = entities::catalogue_skill::Entity::find();
+    if let Some(event) = event {
+        query = query.filter(entities::catalogue_skill::Column::EventId.eq(event));
+    }
+
+    let mut catalogue_skills = query
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    catalogue_skills.sort_by_key(|catalogue_skill| {
+        skill_key(
+            catalogue_skill.event_id,
+            catalogue_skill.fig_rep,
+            catalogue_skill.position,
+        )
+    });
+
+    Ok(catalogue_skills)
+}
+
+fn skill_key(event: Event, fig_rep: i32, position: Position) -> (String, i32, String) {
+    (event.to_value(), fig_rep, position.to_value())
+}
+
+// The tariff is worked out from the notation rather than stored, so it follows the scoring rules.
+// Null for events without a tariff
+fn difficulty(event: Event, fig_rep: i32, position: Position) -> String {
+    let tenths = Notation::parse(fig_rep)
+        .and_then(|notation| notation.difficulty_tenths(event, position))
+        .map(scoring::to_points);
+
+    tenths.map_or("null".to_string(), |points| points.to_string())
+}
+
+fn catalogue_skill_body(catalogue_skill: &entities::catalogue_skill::Model) -> String {
+    format!(
+        "{{ 'catalogue_skill_id': {}, 'event_id': {}, 'fig_rep': {}, 'position': {}, 'name': {}, 'difficulty': {} }}",
+        catalogue_skill.catalogue_skill_id,
+        catalogue_skill.event_id.to_value(),
+        catalogue_skill.fig_rep,
+        catalogue_skill.position.to_value(),
+        catalogue_skill.name,
+        difficulty(
+            catalogue_skill.event_id,
+            catalogue_skill.fig_rep,
+            catalogue_skill.position
+        ),
+    )
+}
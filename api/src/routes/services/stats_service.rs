@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use actix_web::web;
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveEnum, ColumnTrait, Condition, ConnectionTrait, EntityTrait, Iterable, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{
+        self,
+        sea_orm_active_enums::{BestKind, Event},
+    },
+    utils::{api_response::ApiResponse, app_state, jwt::Claims, scoring},
+};
+
+use super::{session_service::ensure_can_view_athlete, turn_service::athlete_turns};
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_personal_bests(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let personal_bests = entities::personal_best::Entity::find()
+        .filter(entities::personal_best::Column::UserId.eq(athlete_id))
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    let first_skills = entities::first_skill::Entity::find()
+        .filter(entities::first_skill::Column::UserId.eq(athlete_id))
+        .order_by_asc(entities::first_skill::Column::SkillId)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Only the events the athlete has logged turns in
+    let events = Event::iter()
+        .filter(|event| {
+            personal_bests
+                .iter()
+                .any(|personal_best| personal_best.event_id == *event)
+        })
+        .map(|event| {
+            let best = |kind: BestKind| {
+                personal_bests
+                    .iter()
+                    .find(|personal_best| {
+                        personal_best.event_id == event && personal_best.kind == kind
+                    })
+                    .map_or("null".to_string(), personal_best_body)
+            };
+            let first_skills = first_skills
+                .iter()
+                .filter(|first_skill| first_skill.event_id == event)
+                .map(first_skill_body)
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            format!(
+                "{{ 'event_id': {}, 'highest_difficulty': {}, 'highest_score': {}, 'longest_routine': {}, 'first_skills': [ {} ] }}",
+                event.to_value(),
+                best(BestKind::HighestDifficulty),
+                best(BestKind::HighestScore),
+                best(BestKind::LongestRoutine),
+                first_skills,
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", events)))
+}
+
+// Works the athletes' bests in an event out again from their turns. Called whenever a turn is
+// logged, judged or deleted, inside the same transaction. Ties go to the earlier turn, and turns
+// and skills are numbered in the order they were logged
+#[instrument(skip_all, fields(?athlete_ids, ?event))]
+pub async fn update_personal_bests<C: ConnectionTrait>(
+    db: &C,
+    athlete_ids: &[i32],
+    event: Event,
+) -> Result<(), ApiResponse> {
+    for athlete_id in athlete_ids {
+        let performed = Condition::all()
+            .add(athlete_turns(db, *athlete_id).await?)
+            .add(entities::turn::Column::EventId.eq(event));
+
+        let mut bests = Vec::new();
+        // Difficulty is only scored in events with a tariff
+        let highest_difficulty = if scoring::has_tariff(event) {
+            get_best_turn(db, &performed, entities::turn::Column::TotalDifficulty).await?
+        } else {
+            None
+        };
+        if let Some(turn) = highest_difficulty {
+            bests.push((
+                BestKind::HighestDifficulty,
+                turn.turn_id,
+                turn.total_difficulty,
+            ));
+        }
+        // Unjudged turns have no execution in their total, so only judged turns are ranked
+        let judged = performed
+            .clone()
+            .add(entities::turn::Column::ExecutionScore.is_not_null());
+        if let Some(turn) = get_best_turn(db, &judged, entities::turn::Column::TotalScore).await? {
+            bests.push((BestKind::HighestScore, turn.turn_id, turn.total_score));
+        }
+
+        let longest_routine = entities::skill::Entity::find()
+            .select_only()
+            .column(entities::skill::Column::TurnId)
+            .column_as(entities::skill::Column::SkillId.count(), "skills")
+            .inner_join(entities::turn::Entity)
+            .filter(performed.clone())
+            .group_by(entities::skill::Column::TurnId)
+            .order_by_desc(entities::skill::Column::SkillId.count())
+            .order_by_asc(entities::skill::Column::TurnId)
+            .into_tuple::<(i32, i64)>()
+            .one(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        if let Some((turn_id, skills)) = longest_routine {
+            bests.push((BestKind::LongestRoutine, turn_id, skills as f32));
+        }
+
+        // Every skill logged was performed, so the first attempt at each one is its first success
+        let first_skill_ids = entities::skill::Entity::find()
+            .select_only()
+            .column_as(entities::skill::Column::SkillId.min(), "skill_id")
+            .inner_join(entities::turn::Entity)
+            .filter(performed)
+            .group_by(entities::skill::Column::FigRep)
+            .group_by(entities::skill::Column::Position)
+            .into_tuple::<i32>()
+            .all(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        let first_skills = entities::skill::Entity::find()
+            .filter(entities::skill::Column::SkillId.is_in(first_skill_ids))
+            .all(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        let session_starts = get_session_starts(
+            db,
+            bests
+                .iter()
+                .map(|(_, turn_id, _)| *turn_id)
+                .chain(first_skills.iter().map(|skill| skill.turn_id))
+                .collect(),
+        )
+        .await?;
+
+        // Replace the stored bests with the new ones
+        entities::personal_best::Entity::delete_many()
+            .filter(
+                Condition::all()
+                    .add(entities::personal_best::Column::UserId.eq(*athlete_id))
+                    .add(entities::personal_best::Column::EventId.eq(event)),
+            )
+            .exec(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        entities::first_skill::Entity::delete_many()
+            .filter(
+                Condition::all()
+                    .add(entities::first_skill::Column::UserId.eq(*athlete_id))
+                    .add(entities::first_skill::Column::EventId.eq(event)),
+            )
+            .exec(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        if !bests.is_empty() {
+            entities::personal_best::Entity::insert_many(bests.into_iter().map(
+                |(kind, turn_id, value)| entities::personal_best::ActiveModel {
+                    user_id: Set(*athlete_id),
+                    event_id: Set(event),
+                    kind: Set(kind),
+                    turn_id: Set(turn_id),
+                    value: Set(value),
+                    achieved_at: Set(session_starts[&turn_id]),
+                    ..Default::default()
+                },
+            ))
+            .exec(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        }
+
+        if !first_skills.is_empty() {
+            entities::first_skill::Entity::insert_many(first_skills.into_iter().map(|skill| {
+                entities::first_skill::ActiveModel {
+                    user_id: Set(*athlete_id),
+                    event_id: Set(event),
+                    fig_rep: Set(skill.fig_rep),
+                    position: Set(skill.position),
+                    turn_id: Set(skill.turn_id),
+                    skill_id: Set(skill.skill_id),
+                    achieved_at: Set(session_starts[&skill.turn_id]),
+                    ..Default::default()
+                }
+            }))
+            .exec(db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_best_turn<C: ConnectionTrait>(
+    db: &C,
+    performed: &Condition,
+    column: entities::turn::Column,
+) -> Result<Option<entities::turn::Model>, ApiResponse> {
+    entities::turn::Entity::find()
+        .filter(performed.clone())
+        .order_by_desc(column)
+        .order_by_asc(entities::turn::Column::TurnId)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+// When the session each turn was logged in started, keyed by turn id
+async fn get_session_starts<C: ConnectionTrait>(
+    db: &C,
+    turn_ids: Vec<i32>,
+) -> Result<HashMap<i32, NaiveDateTime>, ApiResponse> {
+    let turns = entities::turn::Entity::find()
+        .filter(entities::turn::Column::TurnId.is_in(turn_ids))
+        .find_also_related(entities::session::Entity)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(turns
+        .into_iter()
+        .filter_map(|(turn, session)| Some((turn.turn_id, session?.time_start)))
+        .collect())
+}
+
+fn personal_best_body(personal_best: &entities::personal_best::Model) -> String {
+    format!(
+        "{{ 'turn_id': {}, 'value': {}, 'achieved_at': {} }}",
+        personal_best.turn_id, personal_best.value, personal_best.achieved_at,
+    )
+}
+
+fn first_skill_body(first_skill: &entities::first_skill::Model) -> String {
+    format!(
+        "{{ 'fig_rep': {}, 'position': {}, 'turn_id': {}, 'skill_id': {}, 'achieved_at': {} }}",
+        first_skill.fig_rep,
+        first_skill.position.to_value(),
+        first_skill.turn_id,
+        first_skill.skill_id,
+        first_skill.achieved_at,
+    )
+}
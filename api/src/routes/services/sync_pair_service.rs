@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use actix_web::web;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, ModelTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{self, sea_orm_active_enums::UserType},
+    utils::{api_response::ApiResponse, app_state, jwt::Claims},
+};
+
+use super::{session_service::ensure_can_view_athlete, user_service::get_user_by_id};
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, partner_id))]
+pub async fn create_sync_pair(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    partner_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    if partner_id == claim_data.user_id {
+        return Err(ApiResponse::new(
+            422,
+            "An athlete cannot pair with themselves".to_string(),
+        ));
+    }
+
+    let athlete = get_user_by_id(&app_state.db, claim_data.user_id).await?;
+    if athlete.user_type != UserType::Athlete {
+        return Err(ApiResponse::new(
+            401,
+            "Only athletes can form sync pairs".to_string(),
+        ));
+    }
+
+    let partner = get_user_by_id(&app_state.db, partner_id).await?;
+    if partner.user_type != UserType::Athlete {
+        return Err(ApiResponse::new(
+            422,
+            "Sync partners must both be athletes".to_string(),
+        ));
+    }
+
+    // Partners often train at different clubs, so the pair only counts once the partner accepts.
+    // The lower id is always athlete one, so the unique index sees a pair the same both ways
+    let sync_pair = entities::sync_pair::ActiveModel {
+        athlete_one_id: Set(athlete.user_id.min(partner.user_id)),
+        athlete_two_id: Set(athlete.user_id.max(partner.user_id)),
+        requested_by: Set(athlete.user_id),
+        created_at: Set(Utc::now().naive_utc()),
+        accepted_at: Set(None),
+        ..Default::default()
+    }
+    .insert(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::from_db_conflict(err, "These athletes are already a sync pair"))?;
+
+    Ok(ApiResponse::new(201, sync_pair_body(&sync_pair)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, sync_pair_id))]
+pub async fn accept_sync_pair(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    sync_pair_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let sync_pair = get_sync_pair_by_id(&app_state.db, sync_pair_id).await?;
+    if partner_of(&sync_pair, claim_data.user_id) != Some(sync_pair.requested_by) {
+        return Err(ApiResponse::new(
+            401,
+            "Only the invited athlete can accept the sync pair".to_string(),
+        ));
+    }
+
+    // Only moves a pending pair, so accepting twice is caught without a separate read
+    let updated = entities::sync_pair::Entity::update_many()
+        .set(entities::sync_pair::ActiveModel {
+            accepted_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        })
+        .filter(entities::sync_pair::Column::SyncPairId.eq(sync_pair.sync_pair_id))
+        .filter(entities::sync_pair::Column::AcceptedAt.is_null())
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    if updated.rows_affected == 0 {
+        return Err(ApiResponse::new(
+            409,
+            "Sync pair has already been accepted".to_string(),
+        ));
+    }
+
+    let sync_pair = get_sync_pair_by_id(&app_state.db, sync_pair_id).await?;
+    Ok(ApiResponse::new(200, sync_pair_body(&sync_pair)))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_sync_pairs_by_athlete(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let sync_pairs = get_sync_pairs(&app_state.db, athlete_id)
+        .await?
+        .iter()
+        .map(sync_pair_body)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(ApiResponse::new(200, format!("[ {} ]", sync_pairs)))
+}
+
+// Either partner can split the pair or turn down the request, as long as it has no turns that
+// would lose their partner
+#[instrument(skip_all, fields(user_id = claim_data.user_id, sync_pair_id))]
+pub async fn delete_sync_pair(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    sync_pair_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let sync_pair = get_sync_pair_by_id(&txn, sync_pair_id).await?;
+    if partner_of(&sync_pair, claim_data.user_id).is_none() {
+        return Err(ApiResponse::new(
+            401,
+            "Only the pair's athletes can delete it".to_string(),
+        ));
+    }
+
+    let turns = entities::sync_turn::Entity::find()
+        .filter(entities::sync_turn::Column::SyncPairId.eq(sync_pair.sync_pair_id))
+        .count(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    if turns > 0 {
+        return Err(ApiResponse::new(
+            409,
+            "A sync pair with logged turns cannot be deleted".to_string(),
+        ));
+    }
+
+    sync_pair
+        .delete(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Sync pair deleted successfully".to_string(),
+    ))
+}
+
+#[instrument(skip_all, fields(sync_pair_id))]
+pub async fn get_sync_pair_by_id<C: ConnectionTrait>(
+    db: &C,
+    sync_pair_id: i32,
+) -> Result<entities::sync_pair::Model, ApiResponse> {
+    entities::sync_pair::Entity::find_by_id(sync_pair_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Sync pair not found".to_string()))
+}
+
+pub async fn get_sync_pairs<C: ConnectionTrait>(
+    db: &C,
+    athlete_id: i32,
+) -> Result<Vec<entities::sync_pair::Model>, ApiResponse> {
+    entities::sync_pair::Entity::find()
+        .filter(
+            Condition::any()
+                .add(entities::sync_pair::Column::AthleteOneId.eq(athlete_id))
+                .add(entities::sync_pair::Column::AthleteTwoId.eq(athlete_id)),
+        )
+        .order_by_asc(entities::sync_pair::Column::SyncPairId)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+// The synchronization details of each synchronized turn, keyed by turn id
+pub async fn get_sync_turns<C: ConnectionTrait>(
+    db: &C,
+    turn_ids: Vec<i32>,
+) -> Result<HashMap<i32, entities::sync_turn::Model>, ApiResponse> {
+    let sync_turns = entities::sync_turn::Entity::find()
+        .filter(entities::sync_turn::Column::TurnId.is_in(turn_ids))
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(sync_turns
+        .into_iter()
+        .map(|sync_turn| (sync_turn.turn_id, sync_turn))
+        .collect())
+}
+
+// The other athlete in the pair, or None when the athlete is not in it
+pub fn partner_of(sync_pair: &entities::sync_pair::Model, athlete_id: i32) -> Option<i32> {
+    if sync_pair.athlete_one_id == athlete_id {
+        Some(sync_pair.athlete_two_id)
+    } else if sync_pair.athlete_two_id == athlete_id {
+        Some(sync_pair.athlete_one_id)
+    } else {
+        None
+    }
+}
+
+fn sync_pair_body(sync_pair: &entities::sync_pair::Model) -> String {
+    format!(
+        "{{ 'sync_pair_id': {}, 'athlete_one_id': {}, 'athlete_two_id': {}, 'requested_by': {}, 'created_at': {}, 'accepted_at': {} }}",
+        sync_pair.sync_pair_id,
+        sync_pair.athlete_one_id,
+        sync_pair.athlete_two_id,
+        sync_pair.requested_by,
+        sync_pair.created_at,
+        sync_pair
+            .accepted_at
+            .map_or("null".to_string(), |accepted_at| accepted_at.to_string()),
+    )
+}
@@ -0,0 +1,845 @@
+use actix_web::web;
+use chrono::Utc;
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait,
+    IntoActiveModel, ModelTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{
+        self,
+        sea_orm_active_enums::{Direction, Event, Position, SessionStatus},
+    },
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        jwt::Claims,
+        request_models::turn_models::{ExecutionModel, SkillModel, TurnModel},
+        scoring::{self, Breakdown, Notation},
+    },
+};
+
+use super::{
+    goal_service::evaluate_goals,
+    session_service::{ensure_can_view_athlete, get_session_by_id},
+    stats_service::update_personal_bests,
+    sync_pair_service::{get_sync_pair_by_id, get_sync_turns, partner_of},
+};
+
+// A skill from a request, checked and ready to score
+struct ScoredSkill {
+    fig_rep: i32,
+    notation: Notation,
+    direction: Direction,
+    position: Position,
+    deduction: Option<u32>,
+    time_of_flight: Option<u32>,
+    landing_zone: Option<u32>,
+}
+
+impl ScoredSkill {
+    // The tariff depends on the session's event, and is stored as 0 where it isn't scored
+    fn difficulty(&self, event: Event) -> u32 {
+        self.notation
+            .difficulty_tenths(event, self.position)
+            .unwrap_or(0)
+    }
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, session_id = json.session_id))]
+pub async fn create_turn(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    json: TurnModel,
+) -> Result<ApiResponse, ApiResponse> {
+    let skills = score_skills(&json.skills)?;
+    let landing_deduction = judged_landing(&skills, json.landing_deduction)?;
+    let time_of_flight = total_time_of_flight(&skills, json.time_of_flight)?;
+    let horizontal = horizontal_score(&skills, json.horizontal_deduction)?;
+    let penalty = parse_deduction(
+        json.penalty.unwrap_or(0.0),
+        scoring::MAX_PENALTY_TENTHS,
+        "Penalty",
+    )?;
+    let synchronization = synchronization_score(&skills, json.sync_pair_id, json.sync_score)?;
+
+    // The turn, its skills and the session's activity are written together or not at all
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let session = get_session_by_id(&txn, json.session_id).await?;
+    if session.user_id != claim_data.user_id {
+        return Err(ApiResponse::new(
+            401,
+            "Only the athlete can log turns in their session".to_string(),
+        ));
+    }
+    if session.status != SessionStatus::Open {
+        return Err(ApiResponse::new(409, "Session has ended".to_string()));
+    }
+
+    // A synchronized turn is logged once by either partner and shows in both their logs
+    let sync_pair = match json.sync_pair_id {
+        Some(sync_pair_id) => {
+            let sync_pair = get_sync_pair_by_id(&txn, sync_pair_id).await?;
+            if partner_of(&sync_pair, session.user_id).is_none() {
+                return Err(ApiResponse::new(
+                    401,
+                    "Only the pair's athletes can log their synchronized turns".to_string(),
+                ));
+            }
+            if sync_pair.accepted_at.is_none() {
+                return Err(ApiResponse::new(
+                    409,
+                    "Sync pair has not been accepted yet".to_string(),
+                ));
+            }
+            if session.event_id != Event::Tra {
+                return Err(ApiResponse::new(
+                    422,
+                    "Synchronized turns can only be logged in trampoline sessions".to_string(),
+                ));
+            }
+            Some(sync_pair)
+        }
+        None => None,
+    };
+
+    let breakdown = Breakdown {
+        difficulty: skills
+            .iter()
+            .map(|skill| skill.difficulty(session.event_id))
+            .sum(),
+        execution: landing_deduction.map(|landing_deduction| {
+            scoring::execution_tenths(
+                &skills
+                    .iter()
+                    .filter_map(|skill| skill.deduction)
+                    .collect::<Vec<u32>>(),
+                landing_deduction,
+            )
+        }),
+        time_of_flight,
+        horizontal,
+        synchronization,
+        penalty,
+    };
+
+    // Create the turn
+    let turn = entities::turn::ActiveModel {
+        session_id: Set(session.session_id),
+        user_id: Set(session.user_id),
+        note: Set(json.note),
+        event_id: Set(session.event_id),
+        total_difficulty: Set(scoring::to_points(breakdown.difficulty)),
+        landing_deduction: Set(landing_deduction.map(scoring::to_points)),
+        execution_score: Set(breakdown.execution.map(scoring::to_points)),
+        time_of_flight: Set(time_of_flight.map(scoring::milliseconds_to_seconds)),
+        horizontal_score: Set(horizontal.map(scoring::to_points)),
+        penalty: Set(scoring::to_points(penalty)),
+        total_score: Set(breakdown.total_points()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Create the skills, numbered in the order they were performed
+    entities::skill::Entity::insert_many(skills.iter().enumerate().map(|(index, skill)| {
+        entities::skill::ActiveModel {
+            turn_id: Set(turn.turn_id),
+            event_id: Set(session.event_id),
+            skill_num: Set(index as i32 + 1),
+            fig_rep: Set(skill.fig_rep),
+            direction: Set(skill.direction),
+            position: Set(skill.position),
+            difficulty: Set(scoring::to_points(skill.difficulty(session.event_id))),
+            deduction: Set(skill.deduction.map(scoring::to_points)),
+            time_of_flight: Set(skill.time_of_flight.map(scoring::milliseconds_to_seconds)),
+            landing_zone: Set(skill.landing_zone.map(|zone| zone as i32)),
+            ..Default::default()
+        }
+    }))
+    .exec(&txn)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let sync_turn = match &sync_pair {
+        Some(sync_pair) => Some(
+            entities::sync_turn::ActiveModel {
+                turn_id: Set(turn.turn_id),
+                sync_pair_id: Set(sync_pair.sync_pair_id),
+                sync_score: Set(synchronization.map(scoring::thousandths_to_points)),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?,
+        ),
+        None => None,
+    };
+
+    let performers = match &sync_pair {
+        Some(sync_pair) => vec![sync_pair.athlete_one_id, sync_pair.athlete_two_id],
+        None => vec![session.user_id],
+    };
+    update_personal_bests(&txn, &performers, session.event_id).await?;
+    evaluate_goals(&txn, &performers, session.event_id).await?;
+
+    // Keeps the session from being closed as idle
+    entities::session::ActiveModel {
+        session_id: Set(session.session_id),
+        last_activity_at: Set(Some(Utc::now().naive_utc())),
+        ..Default::default()
+    }
+    .update(&txn)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let skills = get_skills(&txn, turn.turn_id).await?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    app_state.metrics.turn_logged(session.event_id);
+
+    Ok(ApiResponse::new(
+        201,
+        turn_body(&turn, &skills, sync_turn.as_ref()),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, turn_id))]
+pub async fn get_turn(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    turn_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let turn = get_turn_by_id(&app_state.db, turn_id).await?;
+    let sync_turn = get_sync_turn(&app_state.db, turn.turn_id).await?;
+    ensure_can_view_turn(&app_state.db, claim_data.user_id, &turn, sync_turn.as_ref()).await?;
+
+    let skills = get_skills(&app_state.db, turn.turn_id).await?;
+    Ok(ApiResponse::new(
+        200,
+        turn_body(&turn, &skills, sync_turn.as_ref()),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, session_id))]
+pub async fn get_turns_by_session(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    session_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let session = get_session_by_id(&app_state.db, session_id).await?;
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, session.user_id).await?;
+
+    // Turns in the order they were logged, each with its skills
+    let turns = entities::turn::Entity::find()
+        .filter(entities::turn::Column::SessionId.eq(session_id))
+        .order_by_asc(entities::turn::Column::TurnId)
+        .find_with_related(entities::skill::Entity)
+        .order_by_asc(entities::skill::Column::SkillNum)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        turns_body(&app_state.db, &turns).await?,
+    ))
+}
+
+// An athlete's log: every turn they logged and every synchronized turn their partners logged
+// with them, most recent first
+#[instrument(skip_all, fields(user_id = claim_data.user_id, athlete_id))]
+pub async fn get_turns_by_athlete(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    athlete_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    ensure_can_view_athlete(&app_state.db, claim_data.user_id, athlete_id).await?;
+
+    let turns = entities::turn::Entity::find()
+        .filter(athlete_turns(&app_state.db, athlete_id).await?)
+        .order_by_desc(entities::turn::Column::TurnId)
+        .find_with_related(entities::skill::Entity)
+        .order_by_asc(entities::skill::Column::SkillNum)
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        turns_body(&app_state.db, &turns).await?,
+    ))
+}
+
+// Athletes and their coaches can both judge a turn, which replaces any earlier deductions
+#[instrument(skip_all, fields(user_id = claim_data.user_id, turn_id))]
+pub async fn score_turn(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    turn_id: i32,
+    json: ExecutionModel,
+) -> Result<ApiResponse, ApiResponse> {
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let turn = get_turn_by_id(&txn, turn_id).await?;
+    let sync_turn = get_sync_turn(&txn, turn.turn_id).await?;
+    ensure_can_view_turn(&txn, claim_data.user_id, &turn, sync_turn.as_ref()).await?;
+
+    let skills = get_skills(&txn, turn.turn_id).await?;
+    if json.deductions.len() != skills.len() {
+        return Err(ApiResponse::new(
+            422,
+            format!("Expected {} deductions, one per skill", skills.len()),
+        ));
+    }
+
+    let deductions = json
+        .deductions
+        .iter()
+        .enumerate()
+        .map(|(index, deduction)| {
+            parse_deduction(
+                *deduction,
+                scoring::MAX_SKILL_DEDUCTION_TENTHS,
+                &format!("Skill {} deduction", index + 1),
+            )
+        })
+        .collect::<Result<Vec<u32>, ApiResponse>>()?;
+    let landing_deduction = parse_deduction(
+        json.landing_deduction,
+        scoring::MAX_LANDING_DEDUCTION_TENTHS,
+        "Landing deduction",
+    )?;
+
+    for (skill, deduction) in skills.into_iter().zip(&deductions) {
+        let mut skill = skill.into_active_model();
+        skill.deduction = Set(Some(scoring::to_points(*deduction)));
+        skill
+            .update(&txn)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    let breakdown = Breakdown {
+        execution: Some(scoring::execution_tenths(&deductions, landing_deduction)),
+        ..stored_breakdown(&turn, sync_turn.as_ref())
+    };
+
+    let mut turn = turn.into_active_model();
+    turn.landing_deduction = Set(Some(scoring::to_points(landing_deduction)));
+    turn.execution_score = Set(breakdown.execution.map(scoring::to_points));
+    turn.total_score = Set(breakdown.total_points());
+    let turn = turn
+        .update(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let performers = get_performers(&txn, &turn, sync_turn.as_ref()).await?;
+    update_personal_bests(&txn, &performers, turn.event_id).await?;
+    evaluate_goals(&txn, &performers, turn.event_id).await?;
+
+    let skills = get_skills(&txn, turn.turn_id).await?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        turn_body(&turn, &skills, sync_turn.as_ref()),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id, turn_id))]
+pub async fn delete_turn(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    turn_id: i32,
+) -> Result<ApiResponse, ApiResponse> {
+    let txn = app_state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let turn = get_turn_by_id(&txn, turn_id).await?;
+    if turn.user_id != claim_data.user_id {
+        return Err(ApiResponse::new(
+            401,
+            "Only the athlete can delete their turn".to_string(),
+        ));
+    }
+    let sync_turn = get_sync_turn(&txn, turn.turn_id).await?;
+    let performers = get_performers(&txn, &turn, sync_turn.as_ref()).await?;
+    let event = turn.event_id;
+
+    // Comments, skills and the synchronization details reference the turn, so they go first
+    entities::comment::Entity::delete_many()
+        .filter(entities::comment::Column::TurnId.eq(turn.turn_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    entities::skill::Entity::delete_many()
+        .filter(entities::skill::Column::TurnId.eq(turn.turn_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    entities::sync_turn::Entity::delete_many()
+        .filter(entities::sync_turn::Column::TurnId.eq(turn.turn_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    turn.delete(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    update_personal_bests(&txn, &performers, event).await?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Turn deleted successfully".to_string(),
+    ))
+}
+
+#[instrument(skip_all, fields(turn_id))]
+pub async fn get_turn_by_id<C: ConnectionTrait>(
+    db: &C,
+    turn_id: i32,
+) -> Result<entities::turn::Model, ApiResponse> {
+    entities::turn::Entity::find_by_id(turn_id)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Turn not found".to_string()))
+}
+
+// Both partners of a synchronized turn, and their coaches, can see it
+pub async fn ensure_can_view_turn<C: ConnectionTrait>(
+    db: &C,
+    viewer_id: i32,
+    turn: &entities::turn::Model,
+    sync_turn: Option<&entities::sync_turn::Model>,
+) -> Result<(), ApiResponse> {
+    let denied = match ensure_can_view_athlete(db, viewer_id, turn.user_id).await {
+        Ok(()) => return Ok(()),
+        Err(denied) => denied,
+    };
+    let Some(sync_turn) = sync_turn else {
+        return Err(denied);
+    };
+
+    let sync_pair = get_sync_pair_by_id(db, sync_turn.sync_pair_id).await?;
+    match partner_of(&sync_pair, turn.user_id) {
+        Some(partner_id) => ensure_can_view_athlete(db, viewer_id, partner_id).await,
+        None => Err(denied),
+    }
+}
+
+// Matches every turn the athlete performed: their own, and synchronized turns their partners
+// logged with them
+pub async fn athlete_turns<C: ConnectionTrait>(
+    db: &C,
+    athlete_id: i32,
+) -> Result<Condition, ApiResponse> {
+    roster_turns(db, &[athlete_id]).await
+}
+
+// The same for a group of athletes, with one query for the synchronized turns of all of them
+pub async fn roster_turns<C: ConnectionTrait>(
+    db: &C,
+    athlete_ids: &[i32],
+) -> Result<Condition, ApiResponse> {
+    let sync_turn_ids = entities::sync_turn::Entity::find()
+        .select_only()
+        .column(entities::sync_turn::Column::TurnId)
+        .inner_join(entities::sync_pair::Entity)
+        .filter(
+            Condition::any()
+                .add(entities::sync_pair::Column::AthleteOneId.is_in(athlete_ids.to_vec()))
+                .add(entities::sync_pair::Column::AthleteTwoId.is_in(athlete_ids.to_vec())),
+        )
+        .into_tuple::<i32>()
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(Condition::any()
+        .add(entities::turn::Column::UserId.is_in(athlete_ids.to_vec()))
+        .add(entities::turn::Column::TurnId.is_in(sync_turn_ids)))
+}
+
+// The athletes who performed the turn, both partners when it was synchronized
+async fn get_performers<C: ConnectionTrait>(
+    db: &C,
+    turn: &entities::turn::Model,
+    sync_turn: Option<&entities::sync_turn::Model>,
+) -> Result<Vec<i32>, ApiResponse> {
+    let mut athlete_ids = vec![turn.user_id];
+    if let Some(sync_turn) = sync_turn {
+        let sync_pair = get_sync_pair_by_id(db, sync_turn.sync_pair_id).await?;
+        athlete_ids.extend(partner_of(&sync_pair, turn.user_id));
+    }
+
+    Ok(athlete_ids)
+}
+
+async fn get_sync_turn<C: ConnectionTrait>(
+    db: &C,
+    turn_id: i32,
+) -> Result<Option<entities::sync_turn::Model>, ApiResponse> {
+    Ok(get_sync_turns(db, vec![turn_id]).await?.remove(&turn_id))
+}
+
+async fn get_skills<C: ConnectionTrait>(
+    db: &C,
+    turn_id: i32,
+) -> Result<Vec<entities::skill::Model>, ApiResponse> {
+    entities::skill::Entity::find()
+        .filter(entities::skill::Column::TurnId.eq(turn_id))
+        .order_by_asc(entities::skill::Column::SkillNum)
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+fn score_skills(skills: &[SkillModel]) -> Result<Vec<ScoredSkill>, ApiResponse> {
+    if skills.is_empty() || skills.len() > scoring::MAX_SKILLS {
+        return Err(ApiResponse::new(
+            422,
+            format!(
+                "A turn must have between 1 and {} skills",
+                scoring::MAX_SKILLS
+            ),
+        ));
+    }
+
+    skills
+        .iter()
+        .enumerate()
+        .map(|(index, skill)| {
+            let skill_num = index + 1;
+
+            let notation = Notation::parse(skill.fig_rep).ok_or(ApiResponse::new(
+                422,
+                format!(
+                    "Skill {} has an invalid fig_rep, expected FIG notation such as 41",
+                    skill_num
+                ),
+            ))?;
+            let direction = Direction::try_from_value(&skill.direction).map_err(|_| {
+                ApiResponse::new(
+                    422,
+                    format!(
+                        "Skill {} has an invalid direction, must be FORWARD, BACKWARD or NONE",
+                        skill_num
+                    ),
+                )
+            })?;
+            let position = Position::try_from_value(&skill.position).map_err(|_| {
+                ApiResponse::new(
+                    422,
+                    format!(
+                        "Skill {} has an invalid position, must be TUCK, PIKE, STRAIGHT or NONE",
+                        skill_num
+                    ),
+                )
+            })?;
+            let deduction = skill
+                .deduction
+                .map(|deduction| {
+                    parse_deduction(
+                        deduction,
+                        scoring::MAX_SKILL_DEDUCTION_TENTHS,
+                        &format!("Skill {} deduction", skill_num),
+                    )
+                })
+                .transpose()?;
+
+            let time_of_flight = skill
+                .time_of_flight
+                .map(|seconds| {
+                    parse_time_of_flight(
+                        seconds,
+                        scoring::MAX_SKILL_TIME_OF_FLIGHT,
+                        &format!("Skill {}", skill_num),
+                    )
+                })
+                .transpose()?;
+            if skill
+                .landing_zone
+                .is_some_and(|zone| zone > scoring::MAX_LANDING_ZONE)
+            {
+                return Err(ApiResponse::new(
+                    422,
+                    format!(
+                        "Skill {} landing zone must be from 0 to {}",
+                        skill_num,
+                        scoring::MAX_LANDING_ZONE
+                    ),
+                ));
+            }
+
+            Ok(ScoredSkill {
+                fig_rep: skill.fig_rep,
+                notation,
+                direction,
+                position,
+                deduction,
+                time_of_flight,
+                landing_zone: skill.landing_zone,
+            })
+        })
+        .collect()
+}
+
+// A turn is judged when every skill has a deduction, and then its landing counts too even if
+// nothing was taken off for it. Until then execution is left unscored
+fn judged_landing(
+    skills: &[ScoredSkill],
+    landing_deduction: Option<f32>,
+) -> Result<Option<u32>, ApiResponse> {
+    let judged = skills
+        .iter()
+        .filter(|skill| skill.deduction.is_some())
+        .count();
+
+    match (judged, landing_deduction) {
+        (0, None) => Ok(None),
+        (0, Some(_)) => Err(ApiResponse::new(
+            422,
+            "A landing deduction needs a deduction for every skill".to_string(),
+        )),
+        (count, landing_deduction) if count == skills.len() => parse_deduction(
+            landing_deduction.unwrap_or(0.0),
+            scoring::MAX_LANDING_DEDUCTION_TENTHS,
+            "Landing deduction",
+        )
+        .map(Some),
+        _ => Err(ApiResponse::new(
+            422,
+            "Every skill needs a deduction when any skill has one".to_string(),
+        )),
+    }
+}
+
+// Time of flight is timed either per skill or for the whole turn
+fn total_time_of_flight(
+    skills: &[ScoredSkill],
+    total: Option<f32>,
+) -> Result<Option<u32>, ApiResponse> {
+    let timed = skills
+        .iter()
+        .filter_map(|skill| skill.time_of_flight)
+        .collect::<Vec<u32>>();
+
+    match (timed.len(), total) {
+        (0, None) => Ok(None),
+        (0, Some(total)) => {
+            parse_time_of_flight(total, scoring::MAX_TURN_TIME_OF_FLIGHT, "The turn").map(Some)
+        }
+        (_, Some(_)) => Err(ApiResponse::new(
+            422,
+            "Give time of flight per skill or for the whole turn, not both".to_string(),
+        )),
+        (count, None) if count == skills.len() => {
+            Ok(Some(timed.into_iter().fold(0, u32::saturating_add)))
+        }
+        _ => Err(ApiResponse::new(
+            422,
+            "Every skill needs a time of flight when any skill has one".to_string(),
+        )),
+    }
+}
+
+// Horizontal displacement comes from each skill's landing zone, or a total deduction for the turn
+fn horizontal_score(
+    skills: &[ScoredSkill],
+    total_deduction: Option<f32>,
+) -> Result<Option<u32>, ApiResponse> {
+    let zones = skills
+        .iter()
+        .filter_map(|skill| skill.landing_zone)
+        .collect::<Vec<u32>>();
+
+    let deduction = match (zones.len(), total_deduction) {
+        (0, None) => return Ok(None),
+        (0, Some(total)) => parse_deduction(
+            total,
+            scoring::MAX_LANDING_ZONE * skills.len() as u32,
+            "Horizontal deduction",
+        )?,
+        (_, Some(_)) => {
+            return Err(ApiResponse::new(
+                422,
+                "Give landing zones per skill or a horizontal deduction, not both".to_string(),
+            ))
+        }
+        (count, None) if count == skills.len() => zones.iter().sum(),
+        _ => {
+            return Err(ApiResponse::new(
+                422,
+                "Every skill needs a landing zone when any skill has one".to_string(),
+            ))
+        }
+    };
+
+    Ok(Some(scoring::horizontal_tenths(skills.len(), deduction)))
+}
+
+// Synchronization is only scored for pairs, up to 2.0 for each skill
+fn synchronization_score(
+    skills: &[ScoredSkill],
+    sync_pair_id: Option<i32>,
+    sync_score: Option<f32>,
+) -> Result<Option<u32>, ApiResponse> {
+    let Some(sync_score) = sync_score else {
+        return Ok(None);
+    };
+    if sync_pair_id.is_none() {
+        return Err(ApiResponse::new(
+            422,
+            "A synchronization score needs a sync pair".to_string(),
+        ));
+    }
+
+    let max_thousandths = scoring::MAX_SKILL_SYNCHRONIZATION_THOUSANDTHS * skills.len() as u32;
+    scoring::to_thousandths(sync_score, max_thousandths)
+        .map(Some)
+        .ok_or(ApiResponse::new(
+            422,
+            format!(
+                "Synchronization score must be from 0.0 to {}",
+                scoring::thousandths_to_points(max_thousandths)
+            ),
+        ))
+}
+
+// The score as it was stored, for working out the total again when one part changes
+fn stored_breakdown(
+    turn: &entities::turn::Model,
+    sync_turn: Option<&entities::sync_turn::Model>,
+) -> Breakdown {
+    Breakdown {
+        difficulty: scoring::nearest_tenths(turn.total_difficulty),
+        execution: turn.execution_score.map(scoring::nearest_tenths),
+        time_of_flight: turn.time_of_flight.map(scoring::nearest_milliseconds),
+        horizontal: turn.horizontal_score.map(scoring::nearest_tenths),
+        synchronization: sync_turn
+            .and_then(|sync_turn| sync_turn.sync_score)
+            .map(scoring::nearest_thousandths),
+        penalty: scoring::nearest_tenths(turn.penalty),
+    }
+}
+
+fn parse_time_of_flight(
+    seconds: f32,
+    max_milliseconds: u32,
+    name: &str,
+) -> Result<u32, ApiResponse> {
+    scoring::to_milliseconds(seconds, max_milliseconds).ok_or(ApiResponse::new(
+        422,
+        format!(
+            "{} time of flight must be from 0.0 to {} seconds",
+            name,
+            scoring::milliseconds_to_seconds(max_milliseconds)
+        ),
+    ))
+}
+
+fn parse_deduction(points: f32, max_tenths: u32, name: &str) -> Result<u32, ApiResponse> {
+    scoring::to_tenths(points, max_tenths).ok_or(ApiResponse::new(
+        422,
+        format!(
+            "{} must be in tenths from 0.0 to {}",
+            name,
+            scoring::to_points(max_tenths)
+        ),
+    ))
+}
+
+async fn turns_body<C: ConnectionTrait>(
+    db: &C,
+    turns: &[(entities::turn::Model, Vec<entities::skill::Model>)],
+) -> Result<String, ApiResponse> {
+    let sync_turns =
+        get_sync_turns(db, turns.iter().map(|(turn, _)| turn.turn_id).collect()).await?;
+
+    let turns = turns
+        .iter()
+        .map(|(turn, skills)| turn_body(turn, skills, sync_turns.get(&turn.turn_id)))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(format!("[ {} ]", turns))
+}
+
+fn turn_body(
+    turn: &entities::turn::Model,
+    skills: &[entities::skill::Model],
+    sync_turn: Option<&entities::sync_turn::Model>,
+) -> String {
+    let skills = skills
+        .iter()
+        .map(|skill| {
+            format!(
+                "{{ 'skill_id': {}, 'skill_num': {}, 'fig_rep': {}, 'direction': {}, 'position': {}, 'difficulty': {}, 'deduction': {}, 'time_of_flight': {}, 'landing_zone': {} }}",
+                skill.skill_id,
+                skill.skill_num,
+                skill.fig_rep,
+                skill.direction.to_value(),
+                skill.position.to_value(),
+                scored(skill.event_id, skill.difficulty),
+                optional(skill.deduction),
+                optional(skill.time_of_flight),
+                optional(skill.landing_zone),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        "{{ 'turn_id': {}, 'session_id': {}, 'user_id': {}, 'event_id': {}, 'note': {}, 'total_difficulty': {}, 'execution_score': {}, 'landing_deduction': {}, 'time_of_flight': {}, 'horizontal_score': {}, 'penalty': {}, 'sync_pair_id': {}, 'sync_score': {}, 'total_score': {}, 'skills': [ {} ] }}",
+        turn.turn_id,
+        turn.session_id,
+        turn.user_id,
+        turn.event_id.to_value(),
+        turn.note,
+        scored(turn.event_id, turn.total_difficulty),
+        optional(turn.execution_score),
+        optional(turn.landing_deduction),
+        optional(turn.time_of_flight),
+        optional(turn.horizontal_score),
+        turn.penalty,
+        optional(sync_turn.map(|sync_turn| sync_turn.sync_pair_id)),
+        optional(sync_turn.and_then(|sync_turn| sync_turn.sync_score)),
+        turn.total_score,
+        skills,
+    )
+}
+
+// Difficulty shows as null in events without a tariff
+fn scored(event: Event, difficulty: f32) -> String {
+    optional(scoring::has_tariff(event).then_some(difficulty))
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or("null".to_string(), |value| value.to_string())
+}
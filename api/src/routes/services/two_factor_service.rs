@@ -0,0 +1,554 @@
+use actix_web::web;
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel,
+    QueryFilter, Set,
+};
+use sha256::digest;
+use totp_rs::{Algorithm, Secret, TOTP};
+use tracing::instrument;
+
+use crate::{
+    entities::{self, sea_orm_active_enums::UserType},
+    utils::{
+        api_response::ApiResponse,
+        app_state,
+        encryption::{is_encrypted, EncryptionKey},
+        jwt::{decode_jwt, encode_jwt, encode_purpose_jwt, Claims, TokenPurpose},
+    },
+};
+
+use super::{club_member_service, club_service, login_attempt_service, user_service};
+
+const ISSUER: &str = "Bounce";
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECONDS: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+// Login tokens handed out between the password and two-factor steps
+pub const CHALLENGE_TOKEN_MINUTES: i64 = 5;
+pub const ENROLLMENT_TOKEN_MINUTES: i64 = 15;
+
+#[instrument(skip_all, fields(user_id))]
+pub async fn get_totp(
+    app_state: &web::Data<app_state::AppState>,
+    user_id: i32,
+) -> Result<Option<entities::user_totp::Model>, ApiResponse> {
+    entities::user_totp::Entity::find_by_id(user_id)
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+#[instrument(skip_all, fields(user_id))]
+pub async fn is_enabled(
+    app_state: &web::Data<app_state::AppState>,
+    user_id: i32,
+) -> Result<bool, ApiResponse> {
+    Ok(get_totp(app_state, user_id)
+        .await?
+        .is_some_and(|totp| totp.enabled))
+}
+
+// Coaches are club staff, so they must use two-factor if their club requires it
+#[instrument(skip_all, fields(user_id = user.user_id))]
+pub async fn is_required(
+    app_state: &web::Data<app_state::AppState>,
+    user: &entities::user::Model,
+) -> Result<bool, ApiResponse> {
+    if user.user_type != UserType::Coach {
+        return Ok(false);
+    }
+
+    let membership =
+        match club_member_service::get_member_by_user_id(&app_state.db, user.user_id).await {
+            Ok(membership) => membership,
+            Err(err) if err.status_code == 404 => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+    let club = club_service::get_club_by_id(&app_state.db, membership.club_id).await?;
+
+    Ok(club.require_two_factor)
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn begin_enrollment(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+) -> Result<ApiResponse, ApiResponse> {
+    let existing = get_totp(app_state, claim_data.user_id).await?;
+
+    if existing.as_ref().is_some_and(|totp| totp.enabled) {
+        return Err(ApiResponse::new(
+            409,
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+
+    // Generate a fresh secret, replacing any enrollment that was never confirmed
+    let secret = Secret::generate_secret().to_encoded().to_string();
+    let totp = build_totp(&secret, &claim_data.email)?;
+    let stored_secret = encrypt_secret(
+        &app_state.config.encryption_key,
+        claim_data.user_id,
+        &secret,
+    )?;
+
+    match existing {
+        Some(existing) => {
+            let mut existing = existing.into_active_model();
+            existing.secret = Set(stored_secret);
+            existing.last_used_step = Set(None);
+            existing.created_at = Set(Utc::now().naive_utc());
+            existing
+                .update(&app_state.db)
+                .await
+                .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        }
+        None => {
+            entities::user_totp::ActiveModel {
+                user_id: Set(claim_data.user_id),
+                secret: Set(stored_secret),
+                enabled: Set(false),
+                last_used_step: Set(None),
+                created_at: Set(Utc::now().naive_utc()),
+                enabled_at: Set(None),
+            }
+            .insert(&app_state.db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        }
+    }
+
+    Ok(ApiResponse::new(
+        200,
+        format!(
+            "{{ 'secret': {}, 'provisioning_uri': {} }}",
+            secret,
+            totp.get_url()
+        ),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn confirm_enrollment(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    code: String,
+) -> Result<ApiResponse, ApiResponse> {
+    let totp = get_totp(app_state, claim_data.user_id)
+        .await?
+        .ok_or(ApiResponse::new(
+            404,
+            "Two-factor enrollment has not been started".to_string(),
+        ))?;
+
+    if totp.enabled {
+        return Err(ApiResponse::new(
+            409,
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+
+    // Recovery codes do not exist yet, so only an authenticator code can confirm enrollment
+    let step = check_totp_code(app_state, &totp, &claim_data.email, &code)?
+        .ok_or(ApiResponse::new(401, "Invalid two-factor code".to_string()))?;
+
+    let mut totp = totp.into_active_model();
+    totp.enabled = Set(true);
+    totp.enabled_at = Set(Some(Utc::now().naive_utc()));
+    totp.last_used_step = Set(Some(step));
+    totp.update(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let recovery_codes = replace_recovery_codes(app_state, claim_data.user_id).await?;
+
+    // Finish logging in users who were sent here by their club's two-factor requirement
+    let token = match claim_data.purpose {
+        TokenPurpose::TwoFactorEnrollment => Some(
+            encode_jwt(
+                &app_state.signing_keys,
+                claim_data.email.clone(),
+                claim_data.user_id,
+            )
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?,
+        ),
+        _ => None,
+    };
+
+    Ok(ApiResponse::new(
+        200,
+        format!(
+            "{{ 'recovery_codes': [ {} ], 'token': {} }}",
+            recovery_codes.join(", "),
+            token.unwrap_or("null".to_string())
+        ),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn disable(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    password: String,
+    code: String,
+) -> Result<ApiResponse, ApiResponse> {
+    let user = user_service::get_user_by_id(&app_state.db, claim_data.user_id).await?;
+
+    // Make sure password is correct
+    if user.password != digest(password) {
+        return Err(ApiResponse::new(401, "Password is incorrect".to_string()));
+    }
+
+    let totp = get_totp(app_state, claim_data.user_id)
+        .await?
+        .filter(|totp| totp.enabled)
+        .ok_or(ApiResponse::new(
+            404,
+            "Two-factor authentication is not enabled".to_string(),
+        ))?;
+
+    if is_required(app_state, &user).await? {
+        return Err(ApiResponse::new(
+            409,
+            "Your club requires two-factor authentication for staff".to_string(),
+        ));
+    }
+
+    if !verify_code(app_state, &totp, &user.email, &code).await? {
+        return Err(ApiResponse::new(401, "Invalid two-factor code".to_string()));
+    }
+
+    // Remove the secret and any remaining recovery codes
+    entities::recovery_code::Entity::delete_many()
+        .filter(entities::recovery_code::Column::UserId.eq(claim_data.user_id))
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    totp.into_active_model()
+        .delete(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(
+        200,
+        "Two-factor authentication disabled".to_string(),
+    ))
+}
+
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn regenerate_recovery_codes(
+    app_state: &web::Data<app_state::AppState>,
+    claim_data: Claims,
+    code: String,
+) -> Result<ApiResponse, ApiResponse> {
+    let totp = get_totp(app_state, claim_data.user_id)
+        .await?
+        .filter(|totp| totp.enabled)
+        .ok_or(ApiResponse::new(
+            404,
+            "Two-factor authentication is not enabled".to_string(),
+        ))?;
+
+    if !verify_code(app_state, &totp, &claim_data.email, &code).await? {
+        return Err(ApiResponse::new(401, "Invalid two-factor code".to_string()));
+    }
+
+    let recovery_codes = replace_recovery_codes(app_state, claim_data.user_id).await?;
+
+    Ok(ApiResponse::new(
+        200,
+        format!("{{ 'recovery_codes': [ {} ] }}", recovery_codes.join(", ")),
+    ))
+}
+
+// Second step of logging in, exchanging a challenge token and a code for an access token
+#[instrument(skip_all)]
+pub async fn verify_login(
+    app_state: &web::Data<app_state::AppState>,
+    challenge_token: String,
+    code: String,
+    ip_address: String,
+) -> Result<ApiResponse, ApiResponse> {
+    let invalid = || ApiResponse::new(401, "Invalid or expired two-factor challenge".to_string());
+
+    let claims = decode_jwt(&app_state.signing_keys, challenge_token)
+        .map_err(|_| invalid())?
+        .claims;
+
+    if claims.purpose != TokenPurpose::TwoFactorChallenge {
+        return Err(invalid());
+    }
+    let jti = claims.jti.clone().ok_or_else(invalid)?;
+
+    // Challenges are single use, so a leaked token can't be exchanged again
+    let used = entities::used_token::Entity::find_by_id(jti.clone())
+        .one(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    if used.is_some() {
+        return Err(invalid());
+    }
+
+    // Guessing codes counts towards the same lockouts as guessing passwords
+    login_attempt_service::check_lockout(app_state, &claims.email, &ip_address).await?;
+
+    let totp = get_totp(app_state, claims.user_id)
+        .await?
+        .filter(|totp| totp.enabled)
+        .ok_or_else(invalid)?;
+
+    if !verify_code(app_state, &totp, &claims.email, &code).await? {
+        login_attempt_service::record_attempt(app_state, &claims.email, &ip_address, false).await?;
+        return Err(ApiResponse::new(401, "Invalid two-factor code".to_string()));
+    }
+
+    // The primary key stops two requests racing to use the same challenge
+    let inserted = entities::used_token::Entity::insert(entities::used_token::ActiveModel {
+        jti: Set(jti),
+        used_at: Set(Utc::now().naive_utc()),
+        expires_at: Set(DateTime::from_timestamp(claims.exp as i64, 0)
+            .unwrap_or_default()
+            .naive_utc()),
+    })
+    .on_conflict(
+        OnConflict::column(entities::used_token::Column::Jti)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    if inserted == 0 {
+        return Err(invalid());
+    }
+
+    login_attempt_service::record_attempt(app_state, &claims.email, &ip_address, true).await?;
+
+    // Create the jwt token
+    let token = encode_jwt(&app_state.signing_keys, claims.email, claims.user_id)
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, format!("{{ 'token': {}}}", token)))
+}
+
+pub fn encode_challenge_token(
+    app_state: &web::Data<app_state::AppState>,
+    user: &entities::user::Model,
+    purpose: TokenPurpose,
+) -> Result<String, ApiResponse> {
+    let minutes = match purpose {
+        TokenPurpose::TwoFactorEnrollment => ENROLLMENT_TOKEN_MINUTES,
+        _ => CHALLENGE_TOKEN_MINUTES,
+    };
+
+    encode_purpose_jwt(
+        &app_state.signing_keys,
+        user.email.clone(),
+        user.user_id,
+        purpose,
+        Duration::minutes(minutes),
+    )
+    .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+// Used challenges only need remembering until they would have expired anyway
+#[instrument(skip_all)]
+pub async fn prune_used_tokens(
+    app_state: &web::Data<app_state::AppState>,
+) -> Result<(), ApiResponse> {
+    let result = entities::used_token::Entity::delete_many()
+        .filter(entities::used_token::Column::ExpiresAt.lt(Utc::now().naive_utc()))
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    tracing::debug!("Pruned {} used tokens", result.rows_affected);
+    Ok(())
+}
+
+// Secrets stored before they were encrypted at rest are encrypted in place on startup
+#[instrument(skip_all)]
+pub async fn encrypt_stored_secrets(
+    app_state: &web::Data<app_state::AppState>,
+) -> Result<(), ApiResponse> {
+    let plaintext: Vec<entities::user_totp::Model> = entities::user_totp::Entity::find()
+        .all(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .filter(|totp| !is_encrypted(&totp.secret))
+        .collect();
+
+    for totp in plaintext.iter() {
+        let secret = encrypt_secret(&app_state.config.encryption_key, totp.user_id, &totp.secret)?;
+
+        let mut totp = totp.clone().into_active_model();
+        totp.secret = Set(secret);
+        totp.update(&app_state.db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    if !plaintext.is_empty() {
+        tracing::info!("Encrypted {} two-factor secrets", plaintext.len());
+    }
+    Ok(())
+}
+
+// Accepts either a current authenticator code or an unused recovery code. Each is claimed with a
+// conditional update, so when two logins race with the same code only one of them gets it
+async fn verify_code(
+    app_state: &web::Data<app_state::AppState>,
+    totp: &entities::user_totp::Model,
+    email: &str,
+    code: &str,
+) -> Result<bool, ApiResponse> {
+    if let Some(step) = check_totp_code(app_state, totp, email, code)? {
+        // Remember the step so the same code cannot be replayed
+        let claimed = entities::user_totp::Entity::update_many()
+            .set(entities::user_totp::ActiveModel {
+                last_used_step: Set(Some(step)),
+                ..Default::default()
+            })
+            .filter(entities::user_totp::Column::UserId.eq(totp.user_id))
+            .filter(
+                Condition::any()
+                    .add(entities::user_totp::Column::LastUsedStep.is_null())
+                    .add(entities::user_totp::Column::LastUsedStep.lt(step)),
+            )
+            .exec(&app_state.db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        return Ok(claimed.rows_affected == 1);
+    }
+
+    // Recovery codes are single use
+    let claimed = entities::recovery_code::Entity::update_many()
+        .set(entities::recovery_code::ActiveModel {
+            used_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        })
+        .filter(
+            Condition::all()
+                .add(entities::recovery_code::Column::UserId.eq(totp.user_id))
+                .add(entities::recovery_code::Column::CodeHash.eq(hash_recovery_code(code)))
+                .add(entities::recovery_code::Column::UsedAt.is_null()),
+        )
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(claimed.rows_affected == 1)
+}
+
+// Returns the time step the code matched, allowing one step of clock drift either way
+fn check_totp_code(
+    app_state: &web::Data<app_state::AppState>,
+    totp: &entities::user_totp::Model,
+    email: &str,
+    code: &str,
+) -> Result<Option<i64>, ApiResponse> {
+    let secret = app_state
+        .config
+        .encryption_key
+        .decrypt(&totp.secret, &secret_context(totp.user_id))
+        .map_err(|err| ApiResponse::new(500, err))?;
+    let generator = build_totp(&secret, email)?;
+    let now = Utc::now().timestamp() as u64;
+    let code = code.trim();
+
+    for offset in [0_i64, -1, 1] {
+        let time = (now as i64 + offset * TOTP_STEP_SECONDS as i64) as u64;
+        let step = (time / TOTP_STEP_SECONDS) as i64;
+
+        if totp.last_used_step.is_some_and(|last| step <= last) {
+            continue;
+        }
+
+        if generator.generate(time) == code {
+            return Ok(Some(step));
+        }
+    }
+
+    Ok(None)
+}
+
+fn encrypt_secret(key: &EncryptionKey, user_id: i32, secret: &str) -> Result<String, ApiResponse> {
+    key.encrypt(secret, &secret_context(user_id))
+        .map_err(|err| ApiResponse::new(500, err))
+}
+
+// Ties each encrypted secret to its user
+fn secret_context(user_id: i32) -> String {
+    format!("user_totp:{}", user_id)
+}
+
+fn build_totp(secret: &str, email: &str) -> Result<TOTP, ApiResponse> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|err| ApiResponse::new(500, format!("{:?}", err)))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP_SECONDS,
+        secret,
+        Some(ISSUER.to_string()),
+        email.replace(':', ""),
+    )
+    .map_err(|err| ApiResponse::new(500, err.to_string()))
+}
+
+// Replaces any existing recovery codes, returning the new ones in plain text
+async fn replace_recovery_codes(
+    app_state: &web::Data<app_state::AppState>,
+    user_id: i32,
+) -> Result<Vec<String>, ApiResponse> {
+    entities::recovery_code::Entity::delete_many()
+        .filter(entities::recovery_code::Column::UserId.eq(user_id))
+        .exec(&app_state.db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let raw: String = rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(10)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect();
+            format!("{}-{}", &raw[..5], &raw[5..])
+        })
+        .collect();
+
+    // Only the hashes are stored
+    entities::recovery_code::Entity::insert_many(codes.iter().map(|code| {
+        entities::recovery_code::ActiveModel {
+            user_id: Set(user_id),
+            code_hash: Set(hash_recovery_code(code)),
+            used_at: Set(None),
+            ..Default::default()
+        }
+    }))
+    .exec(&app_state.db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(codes)
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    digest(normalized)
+}
@@ -1,8 +1,10 @@
 use actix_web::web;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter, Set,
 };
 use sha256::digest;
+use tracing::instrument;
 
 use crate::{
     entities,
@@ -12,6 +14,7 @@ use crate::{
     },
 };
 
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
 pub async fn update_user(
     app_state: &web::Data<app_state::AppState>,
     user_data: web::Json<UpdateUserModel>,
@@ -36,14 +39,14 @@ pub async fn update_user(
         user_model.email = Set(email.clone());
     }
 
-    user_model
-        .update(&app_state.db)
-        .await
-        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    user_model.update(&app_state.db).await.map_err(|err| {
+        ApiResponse::from_db_conflict(err, "User with that email and type already exists")
+    })?;
 
     Ok(ApiResponse::new(200, "User updated!".to_string()))
 }
 
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
 pub async fn reset_password(
     app_state: &web::Data<app_state::AppState>,
     claim_data: Claims,
@@ -51,13 +54,7 @@ pub async fn reset_password(
     new_pass: String,
 ) -> Result<ApiResponse, ApiResponse> {
     // Get user model
-    let user = get_user(&app_state, claim_data.clone(), None).await;
-
-    // Error handling/formatting
-    if user.is_err() {
-        return Err(user.unwrap_err());
-    }
-    let user = user.unwrap();
+    let user = get_user(&app_state.db, claim_data.clone(), None).await?;
 
     // Make sure old password is correct
     if user.password != digest(old_pass) {
@@ -80,8 +77,9 @@ pub async fn reset_password(
     ))
 }
 
-pub async fn get_user(
-    app_state: &web::Data<app_state::AppState>,
+#[instrument(skip_all, fields(user_id = claim_data.user_id))]
+pub async fn get_user<C: ConnectionTrait>(
+    db: &C,
     claim_data: Claims,
     filters: Option<Condition>,
 ) -> Result<entities::user::Model, ApiResponse> {
@@ -97,7 +95,7 @@ pub async fn get_user(
 
     // Find the user
     let user = query
-        .one(&app_state.db)
+        .one(db)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?
         .ok_or(ApiResponse::new(404, "User not found".to_string()))?;
@@ -105,12 +103,13 @@ pub async fn get_user(
     Ok(user)
 }
 
-pub async fn get_user_by_id(
-    app_state: &web::Data<app_state::AppState>,
+#[instrument(skip_all, fields(user_id))]
+pub async fn get_user_by_id<C: ConnectionTrait>(
+    db: &C,
     user_id: i32,
 ) -> Result<entities::user::Model, ApiResponse> {
     let user_model = entities::user::Entity::find_by_id(user_id)
-        .one(&app_state.db)
+        .one(db)
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
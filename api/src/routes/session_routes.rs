@@ -0,0 +1,16 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/session")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("session", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::session_controller::start_session)
+            .service(controllers::session_controller::get_sessions_by_athlete)
+            .service(controllers::session_controller::get_session)
+            .service(controllers::session_controller::end_session),
+    );
+}
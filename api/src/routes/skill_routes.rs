@@ -0,0 +1,16 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/skill")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("skill", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::skill_controller::get_catalogue)
+            .service(controllers::skill_controller::create_catalogue_skill)
+            .service(controllers::skill_controller::delete_catalogue_skill)
+            .service(controllers::skill_controller::get_repertoire),
+    );
+}
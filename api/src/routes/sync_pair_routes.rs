@@ -0,0 +1,16 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/sync-pair")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("sync-pair", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::sync_pair_controller::create_sync_pair)
+            .service(controllers::sync_pair_controller::accept_sync_pair)
+            .service(controllers::sync_pair_controller::get_sync_pairs_by_athlete)
+            .service(controllers::sync_pair_controller::delete_sync_pair),
+    );
+}
@@ -0,0 +1,18 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/turn")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("turn", req, next)
+            }))
+            .wrap(from_fn(middleware::auth_middleware::check_auth_middleware))
+            .service(controllers::turn_controller::create_turn)
+            .service(controllers::turn_controller::get_turns_by_session)
+            .service(controllers::turn_controller::get_turns_by_athlete)
+            .service(controllers::turn_controller::get_turn)
+            .service(controllers::turn_controller::score_turn)
+            .service(controllers::turn_controller::delete_turn),
+    );
+}
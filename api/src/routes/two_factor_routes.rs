@@ -0,0 +1,18 @@
+use super::{controllers, middleware};
+use actix_web::{middleware::from_fn, web};
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/two-factor")
+            .wrap(from_fn(|req, next| {
+                middleware::rate_limit_middleware::check_rate_limit("two-factor", req, next)
+            }))
+            .wrap(from_fn(
+                middleware::auth_middleware::check_two_factor_setup_middleware,
+            ))
+            .service(controllers::two_factor_controller::enroll)
+            .service(controllers::two_factor_controller::confirm_enrollment)
+            .service(controllers::two_factor_controller::disable)
+            .service(controllers::two_factor_controller::regenerate_recovery_codes),
+    );
+}
@@ -0,0 +1,7 @@
+use super::controllers;
+use actix_web::web;
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config
+        .service(web::scope("/.well-known").service(controllers::well_known_controller::get_jwks));
+}
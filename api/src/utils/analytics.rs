@@ -0,0 +1,120 @@
+use chrono::{Datelike, Days, NaiveDate};
+
+use crate::entities::sea_orm_active_enums::Event;
+
+use super::scoring::Notation;
+
+// How training volume is bucketed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    pub fn parse(value: &str) -> Option<Period> {
+        match value {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            "month" => Some(Period::Month),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+
+    // The first day of the period a date falls in. Weeks start on a Monday
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date
+                .checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+                .unwrap_or(date),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+// Which training to count and how to bucket it. Both ends of the date range are included
+pub struct Filter {
+    pub period: Period,
+    pub event: Option<Event>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+// The training done in one period
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Volume {
+    pub sessions: u32,
+    pub turns: u32,
+    pub skills: u32,
+    pub difficulty: u32, // Tenths
+    pub somersaults: u32,
+    pub half_twists: u32,
+}
+
+impl Volume {
+    // Counts a performed skill's completed somersaults and its twists
+    pub fn add_skill(&mut self, notation: &Notation) {
+        self.skills += 1;
+        self.somersaults += notation.somersaults();
+        self.half_twists += notation.total_half_twists();
+    }
+
+    // Twists are counted in full twists, a barani is half a twist
+    pub fn twists(&self) -> f32 {
+        self.half_twists as f32 / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn buckets_dates_by_period() {
+        // A Thursday
+        let thursday = date("2025-03-06");
+
+        assert_eq!(Period::Day.start_of(thursday), thursday);
+        assert_eq!(Period::Week.start_of(thursday), date("2025-03-03"));
+        assert_eq!(
+            Period::Week.start_of(date("2025-03-03")),
+            date("2025-03-03")
+        );
+        assert_eq!(
+            Period::Week.start_of(date("2025-03-09")),
+            date("2025-03-03")
+        );
+        assert_eq!(Period::Month.start_of(thursday), date("2025-03-01"));
+        // Weeks run across the end of a month
+        assert_eq!(
+            Period::Week.start_of(date("2025-03-01")),
+            date("2025-02-24")
+        );
+    }
+
+    #[test]
+    fn counts_somersaults_and_twists() {
+        let mut volume = Volume::default();
+        for fig_rep in [41, 822, 12000] {
+            volume.add_skill(&Notation::parse(fig_rep).unwrap());
+        }
+
+        assert_eq!(volume.skills, 3);
+        assert_eq!(volume.somersaults, 6);
+        assert_eq!(volume.twists(), 2.5);
+    }
+}
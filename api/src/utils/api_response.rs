@@ -1,6 +1,9 @@
 use std::fmt::Display;
 
 use actix_web::{body::BoxBody, http::StatusCode, web, HttpResponse, Responder, ResponseError};
+use sea_orm::{DbErr, SqlErr};
+
+use super::request_id;
 
 #[derive(Debug)]
 pub struct ApiResponse {
@@ -17,13 +20,39 @@ impl ApiResponse {
             repsonse_code: StatusCode::from_u16(status_code).unwrap(),
         }
     }
+
+    // A unique constraint violation means another request got there first, so it is a conflict
+    pub fn from_db_conflict(err: DbErr, conflict_message: &str) -> ApiResponse {
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                ApiResponse::new(409, conflict_message.to_string())
+            }
+            _ => ApiResponse::new(500, err.to_string()),
+        }
+    }
+
+    // Error bodies carry the request id, so a report from a client can be matched to the logs
+    fn render_body(&self) -> String {
+        if self.status_code < 400 {
+            return self.body.clone();
+        }
+
+        if self.status_code >= 500 {
+            tracing::error!(status = self.status_code, error = %self.body, "request error");
+        }
+
+        match request_id::current() {
+            Some(id) => format!("{{ 'error': {}, 'request_id': {} }}", self.body, id),
+            None => self.body.clone(),
+        }
+    }
 }
 
 impl Responder for ApiResponse {
     type Body = BoxBody;
 
     fn respond_to(self, _req: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
-        let body = BoxBody::new(web::BytesMut::from(self.body.as_bytes()));
+        let body = BoxBody::new(web::BytesMut::from(self.render_body().as_bytes()));
         HttpResponse::new(self.repsonse_code).set_body(body)
     }
 }
@@ -44,7 +73,7 @@ impl ResponseError for ApiResponse {
     }
 
     fn error_response(&self) -> HttpResponse<BoxBody> {
-        let body = BoxBody::new(web::BytesMut::from(self.body.as_bytes()));
+        let body = BoxBody::new(web::BytesMut::from(self.render_body().as_bytes()));
         HttpResponse::new(self.status_code()).set_body(body)
     }
 }
@@ -1,5 +1,15 @@
 use sea_orm::DatabaseConnection;
 
+use super::{
+    config::Config, jobs::JobRunner, metrics::Metrics, rate_limiter::RateLimiter,
+    signing_keys::KeyStore,
+};
+
 pub struct AppState {
+    pub config: Config,
     pub db: DatabaseConnection,
+    pub jobs: JobRunner,
+    pub metrics: Metrics,
+    pub rate_limiter: RateLimiter,
+    pub signing_keys: KeyStore,
 }
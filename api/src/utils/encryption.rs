@@ -0,0 +1,135 @@
+use std::{fmt, str::FromStr};
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+
+// Marks values encrypted with AES-256-GCM, followed by the base64 nonce and ciphertext
+const PREFIX: &str = "v1:";
+const KEY_LEN: usize = 32;
+
+// Key for secrets stored in the database, such as TOTP secrets and token signing keys
+#[derive(Clone)]
+pub struct EncryptionKey([u8; KEY_LEN]);
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+impl FromStr for EncryptionKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let bytes = STANDARD
+            .decode(value.trim())
+            .map_err(|_| "expected base64, e.g. from `openssl rand -base64 32`".to_string())?;
+
+        bytes
+            .try_into()
+            .map(EncryptionKey)
+            .map_err(|_| format!("expected {} bytes of base64", KEY_LEN))
+    }
+}
+
+impl EncryptionKey {
+    // The context is authenticated along with the value, so a value copied into another row
+    // fails to decrypt
+    pub fn encrypt(&self, plaintext: &str, context: &str) -> Result<String, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "Failed to generate a nonce".to_string())?;
+
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(context.as_bytes()),
+                &mut sealed,
+            )
+            .map_err(|_| "Failed to encrypt value".to_string())?;
+
+        let mut stored = nonce.to_vec();
+        stored.append(&mut sealed);
+        Ok(format!("{}{}", PREFIX, URL_SAFE_NO_PAD.encode(stored)))
+    }
+
+    pub fn decrypt(&self, stored: &str, context: &str) -> Result<String, String> {
+        let invalid = || "Failed to decrypt value".to_string();
+
+        let encoded = stored.strip_prefix(PREFIX).ok_or_else(invalid)?;
+        let mut sealed = URL_SAFE_NO_PAD.decode(encoded).map_err(|_| invalid())?;
+        if sealed.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+
+        let mut ciphertext = sealed.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&sealed).map_err(|_| invalid())?;
+        let plaintext = self
+            .key()
+            .open_in_place(nonce, Aad::from(context.as_bytes()), &mut ciphertext)
+            .map_err(|_| invalid())?;
+
+        String::from_utf8(plaintext.to_vec()).map_err(|_| invalid())
+    }
+
+    fn key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("key is 32 bytes"))
+    }
+}
+
+// Values written before encryption at rest was added are stored as they are
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> EncryptionKey {
+        STANDARD.encode([7u8; KEY_LEN]).parse().unwrap()
+    }
+
+    #[test]
+    fn round_trips_within_the_same_context() {
+        let stored = key().encrypt("JBSWY3DPEHPK3PXP", "user_totp:1").unwrap();
+
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("JBSWY3DPEHPK3PXP"));
+        assert_eq!(
+            key().decrypt(&stored, "user_totp:1").unwrap(),
+            "JBSWY3DPEHPK3PXP"
+        );
+        // A fresh nonce each time
+        assert_ne!(
+            stored,
+            key().encrypt("JBSWY3DPEHPK3PXP", "user_totp:1").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_other_contexts_keys_and_plaintext() {
+        let stored = key().encrypt("JBSWY3DPEHPK3PXP", "user_totp:1").unwrap();
+        let other_key: EncryptionKey = STANDARD.encode([8u8; KEY_LEN]).parse().unwrap();
+
+        assert!(key().decrypt(&stored, "user_totp:2").is_err());
+        assert!(other_key.decrypt(&stored, "user_totp:1").is_err());
+        assert!(key().decrypt("JBSWY3DPEHPK3PXP", "user_totp:1").is_err());
+        assert!(key().decrypt("v1:AAAA", "user_totp:1").is_err());
+    }
+
+    #[test]
+    fn keys_must_be_32_bytes_of_base64() {
+        assert!("not base64!".parse::<EncryptionKey>().is_err());
+        assert!(STANDARD.encode([7u8; 16]).parse::<EncryptionKey>().is_err());
+        assert!(format!("{:?}", key()) == "[REDACTED]");
+    }
+}
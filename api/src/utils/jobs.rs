@@ -0,0 +1,65 @@
+use std::{fmt::Display, future::Future, sync::Mutex, time::Duration};
+
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::Instrument;
+
+// Runs periodic background work and stops it cleanly when the server shuts down
+pub struct JobRunner {
+    stop: watch::Sender<bool>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Default for JobRunner {
+    fn default() -> Self {
+        JobRunner {
+            stop: watch::channel(false).0,
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl JobRunner {
+    // Runs `job` right away and then every `every`. Failures are logged and retried on the
+    // next tick, so one bad run doesn't stop the job
+    pub fn register<F, Fut, E>(&self, name: &'static str, every: Duration, job: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let mut stop = self.stop.subscribe();
+
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                // A run in progress is allowed to finish, stopping only happens between runs
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stop.changed() => break,
+                }
+
+                if let Err(err) = job().instrument(tracing::info_span!("job", name)).await {
+                    tracing::error!(job = name, "Job failed: {}", err);
+                }
+            }
+
+            tracing::info!(job = name, "Job stopped");
+        });
+
+        self.handles.lock().unwrap().push(handle);
+    }
+
+    // Signals every job to stop and waits for any run in progress
+    pub async fn shutdown(&self) {
+        self.stop.send_replace(true);
+
+        let handles = std::mem::take(&mut *self.handles.lock().unwrap());
+        for handle in handles {
+            if let Err(err) = handle.await {
+                tracing::error!("Job panicked: {}", err);
+            }
+        }
+    }
+}
@@ -1,11 +1,24 @@
 use std::future;
 
 use actix_web::{FromRequest, HttpMessage};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, Header, TokenData, Validation,
+};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use super::constants;
+use super::signing_keys::KeyStore;
+
+// What a token may be used for. Only access tokens are accepted by the regular auth middleware
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum TokenPurpose {
+    #[default]
+    Access,
+    TwoFactorChallenge,
+    TwoFactorEnrollment,
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Claims {
@@ -13,6 +26,11 @@ pub struct Claims {
     pub iat: usize,
     pub email: String,
     pub user_id: i32,
+    #[serde(default)]
+    pub purpose: TokenPurpose,
+    // Lets single-use tokens be marked as used
+    #[serde(default)]
+    pub jti: Option<String>,
 }
 
 impl FromRequest for Claims {
@@ -31,33 +49,74 @@ impl FromRequest for Claims {
     }
 }
 
-pub fn encode_jwt(email: String, user_id: i32) -> Result<String, jsonwebtoken::errors::Error> {
+pub fn encode_jwt(
+    keys: &KeyStore,
+    email: String,
+    user_id: i32,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    encode_purpose_jwt(
+        keys,
+        email,
+        user_id,
+        TokenPurpose::Access,
+        Duration::hours(24),
+    )
+}
+
+pub fn encode_purpose_jwt(
+    keys: &KeyStore,
+    email: String,
+    user_id: i32,
+    purpose: TokenPurpose,
+    expire: Duration,
+) -> Result<String, jsonwebtoken::errors::Error> {
     let now = Utc::now();
-    let expire = Duration::hours(24);
 
     let claim = Claims {
         exp: (now + expire).timestamp() as usize,
         iat: now.timestamp() as usize,
         email,
         user_id,
+        purpose,
+        jti: Some(URL_SAFE_NO_PAD.encode(rand::thread_rng().gen::<[u8; 16]>())),
     };
 
-    let secret = constants::SECRET.clone();
+    // Sign with the active key, naming it in the header so verifiers can find it
+    let (kid, encoding_key) = keys
+        .active_key()
+        .ok_or(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat)?;
 
-    encode(
-        &Header::default(),
-        &claim,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(kid);
+
+    encode(&header, &claim, &encoding_key)
 }
 
-pub fn decode_jwt(jwt: String) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
-    let secret = constants::SECRET.clone();
-    let claim_data: Result<TokenData<Claims>, jsonwebtoken::errors::Error> = decode(
-        &jwt,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    );
+pub fn decode_jwt(
+    keys: &KeyStore,
+    jwt: String,
+) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
+    let header = decode_header(&jwt)?;
 
-    claim_data
+    match header.kid {
+        Some(kid) => {
+            let decoding_key = keys
+                .decoding_key(&kid)
+                .ok_or(jsonwebtoken::errors::ErrorKind::InvalidSignature)?;
+
+            decode(&jwt, &decoding_key, &Validation::new(Algorithm::EdDSA))
+        }
+        None => {
+            // Tokens from before key rotation have no kid and were signed with SECRET
+            let secret = keys
+                .legacy_secret()
+                .ok_or(jsonwebtoken::errors::ErrorKind::InvalidSignature)?;
+
+            decode(
+                &jwt,
+                &DecodingKey::from_secret(secret.expose().as_ref()),
+                &Validation::new(Algorithm::HS256),
+            )
+        }
+    }
 }
@@ -0,0 +1,152 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use sea_orm::{metric::Info, ActiveEnum, ConnectionTrait, DatabaseConnection, DbBackend, Iterable};
+
+use crate::entities::sea_orm_active_enums::Event;
+
+// Queries are much faster than whole requests, so they get finer buckets
+const DB_QUERY_BUCKETS: [f64; 11] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+];
+
+// Every metric the server exposes on /metrics
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests: IntCounterVec, // Labelled by method, route and status
+    pub http_request_duration: HistogramVec, // Labelled by method and route
+    pub db_query_duration: HistogramVec, // Labelled by operation and outcome
+    db_pool_connections: IntGaugeVec, // Labelled by state
+    sessions_logged: IntCounterVec,   // Labelled by event
+    turns_logged: IntCounterVec,      // Labelled by event
+}
+
+impl Metrics {
+    pub fn new() -> Result<Metrics, prometheus::Error> {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )?;
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time taken to handle HTTP requests",
+            ),
+            &["method", "route"],
+        )?;
+        let db_query_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "db_query_duration_seconds",
+                "Time taken by database queries",
+            )
+            .buckets(DB_QUERY_BUCKETS.to_vec()),
+            &["operation", "outcome"],
+        )?;
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Connections in the database pool"),
+            &["state"],
+        )?;
+        let sessions_logged = IntCounterVec::new(
+            Opts::new("sessions_logged_total", "Training sessions logged"),
+            &["event"],
+        )?;
+        let turns_logged =
+            IntCounterVec::new(Opts::new("turns_logged_total", "Turns logged"), &["event"])?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(db_query_duration.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+        registry.register(Box::new(sessions_logged.clone()))?;
+        registry.register(Box::new(turns_logged.clone()))?;
+
+        // Start every event at zero, so rates work before the first session is logged
+        for event in Event::iter() {
+            sessions_logged.with_label_values(&[&event.to_value()]);
+            turns_logged.with_label_values(&[&event.to_value()]);
+        }
+
+        Ok(Metrics {
+            registry,
+            http_requests,
+            http_request_duration,
+            db_query_duration,
+            db_pool_connections,
+            sessions_logged,
+            turns_logged,
+        })
+    }
+
+    // Times every query run through the connection
+    pub fn instrument(&self, db: &mut DatabaseConnection) {
+        let db_query_duration = self.db_query_duration.clone();
+
+        db.set_metric_callback(move |info: &Info<'_>| {
+            let outcome = if info.failed { "error" } else { "ok" };
+            db_query_duration
+                .with_label_values(&[query_operation(&info.statement.sql), outcome])
+                .observe(info.elapsed.as_secs_f64());
+        });
+    }
+
+    pub fn session_logged(&self, event: Event) {
+        self.sessions_logged
+            .with_label_values(&[&event.to_value()])
+            .inc();
+    }
+
+    pub fn turn_logged(&self, event: Event) {
+        self.turns_logged
+            .with_label_values(&[&event.to_value()])
+            .inc();
+    }
+
+    // Renders every metric in the Prometheus text format, refreshing the pool gauges first
+    pub fn render(&self, db: &DatabaseConnection) -> Result<String, prometheus::Error> {
+        if let Some((size, idle)) = pool_usage(db) {
+            self.db_pool_connections
+                .with_label_values(&["idle"])
+                .set(idle as i64);
+            self.db_pool_connections
+                .with_label_values(&["active"])
+                .set(size as i64 - idle as i64);
+        }
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+
+        String::from_utf8(buffer).map_err(|err| prometheus::Error::Msg(err.to_string()))
+    }
+}
+
+// The statement's leading keyword, so the label stays bounded whatever the query
+fn query_operation(sql: &str) -> &'static str {
+    let keyword = sql.split_whitespace().next().unwrap_or_default();
+
+    match keyword.to_uppercase().as_str() {
+        "SELECT" => "select",
+        "INSERT" => "insert",
+        "UPDATE" => "update",
+        "DELETE" => "delete",
+        _ => "other",
+    }
+}
+
+// (total, idle) connections in the pool
+fn pool_usage(db: &DatabaseConnection) -> Option<(u32, usize)> {
+    match db.get_database_backend() {
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => {
+            let pool = db.get_sqlite_connection_pool();
+            Some((pool.size(), pool.num_idle()))
+        }
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => {
+            let pool = db.get_postgres_connection_pool();
+            Some((pool.size(), pool.num_idle()))
+        }
+        _ => None,
+    }
+}
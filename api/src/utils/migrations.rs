@@ -0,0 +1,268 @@
+use std::{convert::Infallible, future::Future, time::Duration as StdDuration};
+
+use chrono::{Duration, Utc};
+use migration::{Migrator, MigratorTrait};
+use rand::Rng;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, Schema, Set, SqlErr,
+};
+
+use crate::entities;
+
+// Held locks are refreshed this often, so a long migration isn't mistaken for a dead replica
+const LOCK_HEARTBEAT_SECONDS: u64 = 30;
+// A lock not refreshed for this long is assumed to belong to a replica that died holding it
+const LOCK_STALE_SECONDS: i64 = 2 * 60;
+// How long to wait for another replica to release a lock
+const LOCK_WAIT_SECONDS: u64 = 15 * 60;
+
+// Work only one replica may do at a time. Each is a row in migration_lock that replicas race
+// to insert
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lock {
+    Migrations = 1,
+    KeyRotation = 2,
+}
+
+impl Lock {
+    fn activity(self) -> &'static str {
+        match self {
+            Lock::Migrations => "migrating",
+            Lock::KeyRotation => "rotating signing keys",
+        }
+    }
+}
+
+// What to do with pending migrations when the server starts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MigrationMode {
+    Skip,
+    Apply,
+    Check,
+}
+
+pub async fn run(db: &DatabaseConnection, mode: MigrationMode) -> Result<(), DbErr> {
+    match mode {
+        MigrationMode::Skip => Ok(()),
+        MigrationMode::Check => check(db).await,
+        MigrationMode::Apply => apply(db).await,
+    }
+}
+
+// Refuses to start if the schema is behind the migrations built into this binary
+async fn check(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let pending = Migrator::get_pending_migrations(db).await?;
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let names = pending
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect::<Vec<String>>();
+
+    Err(DbErr::Custom(format!(
+        "The database schema is behind, pending migrations: {}",
+        names.join(", ")
+    )))
+}
+
+// Applies pending migrations while holding the startup lock
+async fn apply(db: &DatabaseConnection) -> Result<(), DbErr> {
+    with_lock(db, Lock::Migrations, async {
+        let pending = Migrator::get_pending_migrations(db).await?;
+        for migration in &pending {
+            tracing::info!("Applying migration {}", migration.name());
+        }
+        Migrator::up(db, None).await
+    })
+    .await
+}
+
+// Runs `work` once no other replica holds the lock, refreshing the lock until it is done
+pub async fn with_lock<T>(
+    db: &DatabaseConnection,
+    lock: Lock,
+    work: impl Future<Output = Result<T, DbErr>>,
+) -> Result<T, DbErr> {
+    let holder = acquire_lock(db, lock).await?;
+
+    let result = tokio::select! {
+        result = work => result,
+        never = keep_alive(db, &holder) => match never {},
+    };
+
+    // The work is done either way, so a lock left behind only delays the next holder
+    if let Err(err) = release_lock(db, &holder).await {
+        tracing::warn!(
+            "Failed to release the lock for {}, it will go stale in {} seconds: {}",
+            lock.activity(),
+            LOCK_STALE_SECONDS,
+            err
+        );
+    }
+    result
+}
+
+async fn acquire_lock(db: &DatabaseConnection, lock: Lock) -> Result<String, DbErr> {
+    // The lock has to exist before any migration has run, so it is not created by one
+    let backend = db.get_database_backend();
+    let create_table = Schema::new(backend)
+        .create_table_from_entity(entities::migration_lock::Entity)
+        .if_not_exists()
+        .to_owned();
+    db.execute(backend.build(&create_table)).await?;
+
+    let holder = format!(
+        "{}-{:016x}",
+        std::process::id(),
+        rand::thread_rng().gen::<u64>()
+    );
+    let started = Utc::now();
+
+    loop {
+        let now = Utc::now().naive_utc();
+
+        entities::migration_lock::Entity::delete_many()
+            .filter(entities::migration_lock::Column::Id.eq(lock as i32))
+            .filter(
+                entities::migration_lock::Column::LockedAt
+                    .lt(now - Duration::seconds(LOCK_STALE_SECONDS)),
+            )
+            .exec(db)
+            .await?;
+
+        let inserted = entities::migration_lock::ActiveModel {
+            id: Set(lock as i32),
+            locked_by: Set(holder.clone()),
+            locked_at: Set(now),
+        }
+        .insert(db)
+        .await;
+
+        match inserted {
+            Ok(_) => return Ok(holder),
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                if Utc::now() - started > Duration::seconds(LOCK_WAIT_SECONDS as i64) {
+                    return Err(DbErr::Custom(format!(
+                        "Timed out waiting for another server to finish {}",
+                        lock.activity()
+                    )));
+                }
+
+                tracing::info!("Waiting for another server to finish {}", lock.activity());
+                tokio::time::sleep(StdDuration::from_secs(1)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// Polled alongside the work, so the lock stays fresh without a separate task
+async fn keep_alive(db: &DatabaseConnection, holder: &str) -> Infallible {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(LOCK_HEARTBEAT_SECONDS));
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let refreshed = entities::migration_lock::Entity::update_many()
+            .col_expr(
+                entities::migration_lock::Column::LockedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(entities::migration_lock::Column::LockedBy.eq(holder))
+            .exec(db)
+            .await;
+
+        if let Err(err) = refreshed {
+            tracing::warn!("Failed to refresh lock {}: {}", holder, err);
+        }
+    }
+}
+
+async fn release_lock(db: &DatabaseConnection, holder: &str) -> Result<(), DbErr> {
+    entities::migration_lock::Entity::delete_many()
+        .filter(entities::migration_lock::Column::LockedBy.eq(holder))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use sea_orm::{ConnectOptions, Database};
+
+    use super::*;
+
+    async fn connect() -> DatabaseConnection {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options).await.unwrap();
+
+        // Creates the lock table
+        with_lock(&db, Lock::Migrations, async { Ok(()) })
+            .await
+            .unwrap();
+        db
+    }
+
+    async fn held_by(db: &DatabaseConnection, lock: Lock, holder: &str, age_seconds: i64) {
+        entities::migration_lock::ActiveModel {
+            id: Set(lock as i32),
+            locked_by: Set(holder.to_string()),
+            locked_at: Set(Utc::now().naive_utc() - Duration::seconds(age_seconds)),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn takes_over_locks_left_by_dead_replicas() {
+        let db = connect().await;
+        held_by(&db, Lock::Migrations, "dead", LOCK_STALE_SECONDS + 1).await;
+
+        let result = with_lock(&db, Lock::Migrations, async { Ok(7) }).await;
+
+        assert_eq!(result.unwrap(), 7);
+        let locks = entities::migration_lock::Entity::find().all(&db).await;
+        assert!(locks.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn waits_for_live_holders_of_the_same_lock_only() {
+        let db = connect().await;
+        held_by(&db, Lock::KeyRotation, "other", 0).await;
+
+        let waited = tokio::time::timeout(
+            StdDuration::from_secs(2),
+            with_lock(&db, Lock::KeyRotation, async { Ok(()) }),
+        )
+        .await;
+        assert!(waited.is_err());
+
+        let result = with_lock(&db, Lock::Migrations, async { Ok(()) }).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn releases_the_lock_when_the_work_fails() {
+        let db = connect().await;
+
+        let result = with_lock(&db, Lock::Migrations, async {
+            Err::<(), DbErr>(DbErr::Custom("migration failed".to_string()))
+        })
+        .await;
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Custom Error: migration failed"
+        );
+        let locks = entities::migration_lock::Entity::find().all(&db).await;
+        assert!(locks.unwrap().is_empty());
+    }
+}
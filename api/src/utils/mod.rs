@@ -1,5 +1,14 @@
+pub mod analytics;
 pub mod api_response;
 pub mod app_state;
-pub mod constants;
+pub mod config;
+pub mod encryption;
+pub mod jobs;
 pub mod jwt;
+pub mod metrics;
+pub mod migrations;
+pub mod rate_limiter;
+pub mod request_id;
 pub mod request_models;
+pub mod scoring;
+pub mod signing_keys;
@@ -0,0 +1,290 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, QuerySelect, Set, TransactionTrait,
+};
+
+use crate::entities;
+
+// Token bucket settings for a single route scope
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimitPolicy {
+    pub capacity: f64,
+    pub refill_per_second: f64,
+}
+
+pub enum RateLimitDecision {
+    Allowed { remaining: u64 },
+    Limited { retry_after_secs: u64 },
+}
+
+// Where token buckets are kept between requests
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    async fn take(&self, key: &str, policy: &RateLimitPolicy) -> Result<RateLimitDecision, DbErr>;
+}
+
+pub struct RateLimiter {
+    pub store: Box<dyn RateLimitStore>,
+    pub policies: HashMap<String, RateLimitPolicy>,
+}
+
+impl RateLimiter {
+    pub fn policy(&self, scope: &str) -> Option<&RateLimitPolicy> {
+        self.policies.get(scope)
+    }
+}
+
+// Enough for every user and client IP a single server sees at once
+const DEFAULT_MAX_BUCKETS: usize = 100_000;
+
+// One bucket in memory, with when it will have refilled to capacity
+#[derive(Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: NaiveDateTime,
+    full_at: NaiveDateTime,
+}
+
+// Buckets kept in process memory, reset whenever the server restarts. Holds at most max_buckets,
+// so a flood of distinct IPs or users can't grow it without bound
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    max_buckets: usize,
+}
+
+impl MemoryStore {
+    pub fn new(max_buckets: usize) -> Self {
+        MemoryStore {
+            buckets: Mutex::new(HashMap::new()),
+            max_buckets: max_buckets.max(1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore::new(DEFAULT_MAX_BUCKETS)
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn take(&self, key: &str, policy: &RateLimitPolicy) -> Result<RateLimitDecision, DbErr> {
+        let now = Utc::now().naive_utc();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if !buckets.contains_key(key) && buckets.len() >= self.max_buckets {
+            evict(&mut buckets, self.max_buckets, now);
+        }
+
+        let (tokens, updated_at) = buckets.get(key).map_or((policy.capacity, now), |bucket| {
+            (bucket.tokens, bucket.updated_at)
+        });
+        let (tokens, decision) = take_token(tokens, updated_at, now, policy);
+
+        buckets.insert(
+            key.to_string(),
+            Bucket {
+                tokens,
+                updated_at: now,
+                full_at: full_at(tokens, now, policy),
+            },
+        );
+        Ok(decision)
+    }
+}
+
+// A bucket that has refilled is the same as no bucket, so those go first. If the store is still
+// full, the buckets left untouched the longest make room, which only ever loosens their limit
+fn evict(buckets: &mut HashMap<String, Bucket>, max_buckets: usize, now: NaiveDateTime) {
+    buckets.retain(|_, bucket| bucket.full_at > now);
+    if buckets.len() < max_buckets {
+        return;
+    }
+
+    // Drop the oldest tenth at once, so a steady stream of new keys doesn't sort on every request
+    let mut by_age = buckets
+        .iter()
+        .map(|(key, bucket)| (bucket.updated_at, key.clone()))
+        .collect::<Vec<(NaiveDateTime, String)>>();
+    by_age.sort_unstable();
+    let excess = buckets.len() + 1 - max_buckets;
+    for (_, key) in by_age.into_iter().take(excess.max(max_buckets / 10)) {
+        buckets.remove(&key);
+    }
+}
+
+fn full_at(tokens: f64, now: NaiveDateTime, policy: &RateLimitPolicy) -> NaiveDateTime {
+    if policy.refill_per_second <= 0.0 {
+        return NaiveDateTime::MAX;
+    }
+
+    let seconds = (policy.capacity - tokens).max(0.0) / policy.refill_per_second;
+    now.checked_add_signed(chrono::Duration::milliseconds(
+        (seconds * 1000.0).ceil() as i64
+    ))
+    .unwrap_or(NaiveDateTime::MAX)
+}
+
+// Buckets kept in the rate_limit_bucket table, so limits hold across restarts and replicas
+pub struct DatabaseStore {
+    pub db: DatabaseConnection,
+}
+
+#[async_trait]
+impl RateLimitStore for DatabaseStore {
+    async fn take(&self, key: &str, policy: &RateLimitPolicy) -> Result<RateLimitDecision, DbErr> {
+        let now = Utc::now().naive_utc();
+        let txn = self.db.begin().await?;
+
+        // A full bucket for a new key. When another request or replica created it first this does
+        // nothing, instead of failing on the primary key
+        entities::rate_limit_bucket::Entity::insert(entities::rate_limit_bucket::ActiveModel {
+            bucket_key: Set(key.to_string()),
+            tokens: Set(policy.capacity),
+            updated_at: Set(now),
+        })
+        .on_conflict(
+            OnConflict::column(entities::rate_limit_bucket::Column::BucketKey)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+
+        // Postgres locks the row until commit, so concurrent requests take their tokens in turn.
+        // SQLite already holds the database's write lock from the insert
+        let bucket = entities::rate_limit_bucket::Entity::find_by_id(key.to_string())
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound(key.to_string()))?;
+        let (tokens, decision) = take_token(bucket.tokens, bucket.updated_at, now, policy);
+
+        let mut bucket = bucket.into_active_model();
+        bucket.tokens = Set(tokens);
+        bucket.updated_at = Set(now);
+        bucket.update(&txn).await?;
+
+        txn.commit().await?;
+        Ok(decision)
+    }
+}
+
+// Refills the bucket for the time that has passed, then tries to take a single token from it
+fn take_token(
+    tokens: f64,
+    updated_at: NaiveDateTime,
+    now: NaiveDateTime,
+    policy: &RateLimitPolicy,
+) -> (f64, RateLimitDecision) {
+    let elapsed = (now - updated_at).num_milliseconds().max(0) as f64 / 1000.0;
+    let tokens = (tokens + elapsed * policy.refill_per_second).min(policy.capacity);
+
+    if tokens >= 1.0 {
+        let tokens = tokens - 1.0;
+        (
+            tokens,
+            RateLimitDecision::Allowed {
+                remaining: tokens.floor() as u64,
+            },
+        )
+    } else {
+        let retry_after_secs = if policy.refill_per_second > 0.0 {
+            ((1.0 - tokens) / policy.refill_per_second).ceil() as u64
+        } else {
+            u64::MAX
+        };
+        (
+            tokens,
+            RateLimitDecision::Limited {
+                retry_after_secs: retry_after_secs.max(1),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: RateLimitPolicy = RateLimitPolicy {
+        capacity: 2.0,
+        refill_per_second: 0.5,
+    };
+
+    #[actix_web::test]
+    async fn limits_once_the_bucket_is_empty() {
+        let store = MemoryStore::default();
+
+        for remaining in [1, 0] {
+            match store.take("user:1", &POLICY).await.unwrap() {
+                RateLimitDecision::Allowed { remaining: left } => assert_eq!(left, remaining),
+                RateLimitDecision::Limited { .. } => panic!("limited too early"),
+            }
+        }
+        match store.take("user:1", &POLICY).await.unwrap() {
+            RateLimitDecision::Limited { retry_after_secs } => assert_eq!(retry_after_secs, 2),
+            RateLimitDecision::Allowed { .. } => panic!("not limited"),
+        }
+
+        // Other keys have their own bucket
+        assert!(matches!(
+            store.take("user:2", &POLICY).await.unwrap(),
+            RateLimitDecision::Allowed { remaining: 1 }
+        ));
+    }
+
+    #[actix_web::test]
+    async fn never_holds_more_than_max_buckets() {
+        let store = MemoryStore::new(10);
+
+        for ip in 0..100 {
+            store.take(&format!("ip:{}", ip), &POLICY).await.unwrap();
+        }
+        assert!(store.len() <= 10);
+
+        // The newest key is kept
+        assert!(matches!(
+            store.take("ip:99", &POLICY).await.unwrap(),
+            RateLimitDecision::Allowed { remaining: 0 }
+        ));
+    }
+
+    #[test]
+    fn evicts_refilled_buckets_before_active_ones() {
+        let now = Utc::now().naive_utc();
+        let bucket = |tokens: f64, age: i64| {
+            let updated_at = now - chrono::Duration::seconds(age);
+            Bucket {
+                tokens,
+                updated_at,
+                full_at: full_at(tokens, updated_at, &POLICY),
+            }
+        };
+        let mut buckets = HashMap::from([
+            ("refilled".to_string(), bucket(0.0, 60)),
+            ("oldest".to_string(), bucket(0.0, 3)),
+            ("newest".to_string(), bucket(0.0, 1)),
+        ]);
+
+        evict(&mut buckets, 3, now);
+        assert_eq!(buckets.len(), 2);
+        assert!(!buckets.contains_key("refilled"));
+
+        evict(&mut buckets, 2, now);
+        assert_eq!(buckets.keys().collect::<Vec<_>>(), vec!["newest"]);
+    }
+}
@@ -0,0 +1,36 @@
+use rand::Rng;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Longer ids from clients are replaced, so they can't bloat every log line
+const MAX_REQUEST_ID_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// Keeps the caller's id so a request can be followed across services, or makes a new one
+pub fn from_header(header: Option<&str>) -> String {
+    match header {
+        Some(id)
+            if !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LENGTH
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            id.to_string()
+        }
+        _ => format!("{:032x}", rand::thread_rng().gen::<u128>()),
+    }
+}
+
+// Runs a request with its id available to anything it calls
+pub async fn scope<F: std::future::Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+// The id of the request being handled, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
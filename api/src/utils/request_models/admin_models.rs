@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LockoutQueryModel {
+    /// Only return lockouts that have not expired or been cleared
+    pub active: Option<bool>,
+}
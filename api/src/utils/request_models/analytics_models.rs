@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnalyticsQueryModel {
+    /// day, week or month, defaults to week
+    pub period: Option<String>,
+    /// Only count one event, DMT, TRA or TUM
+    pub event: Option<String>,
+    /// The first day to count, as YYYY-MM-DD
+    pub from: Option<String>,
+    /// The last day to count, as YYYY-MM-DD
+    pub to: Option<String>,
+}
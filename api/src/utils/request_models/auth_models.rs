@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct RegisterModel {
     pub user_type: String,
     pub name_first: String,
@@ -9,9 +10,15 @@ pub struct RegisterModel {
     pub password: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct LoginModel {
     pub user_type: String,
     pub email: String,
     pub password: String,
 }
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorLoginModel {
+    pub challenge_token: String,
+    pub code: String,
+}
@@ -1,11 +1,17 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ClubModel {
     pub name: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TransferOwnerModel {
     pub new_owner_id: i32,
 }
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RequireTwoFactorModel {
+    pub required: bool,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CommentModel {
+    /// Comment on a whole session
+    pub session_id: Option<i32>,
+    /// Comment on one turn
+    pub turn_id: Option<i32>,
+    /// Comment on one skill within a turn
+    pub skill_id: Option<i32>,
+    /// Reply to another comment, on the same session, turn or skill
+    pub parent_comment_id: Option<i32>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct EditCommentModel {
+    pub body: String,
+}
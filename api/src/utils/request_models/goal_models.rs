@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GoalModel {
+    /// The athlete working towards the goal
+    pub athlete_id: i32,
+    /// DIFFICULTY to perform a turn of at least the target DD, or CONSISTENT_SKILL to land one
+    /// skill consistently the target number of times
+    pub kind: String,
+    /// DMT, TRA or TUM
+    pub event: String,
+    /// A DD such as 14.0, or a number of landings
+    pub target: f32,
+    /// The skill to land, for CONSISTENT_SKILL goals. FIG notation with dashes written as 0
+    pub fig_rep: Option<i32>,
+    /// TUCK, PIKE, STRAIGHT or NONE, for CONSISTENT_SKILL goals
+    pub position: Option<String>,
+    /// The last day to reach the goal, as YYYY-MM-DD
+    pub deadline: String,
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GoalQueryModel {
+    /// Only list goals that are ACTIVE, COMPLETED or MISSED
+    pub status: Option<String>,
+}
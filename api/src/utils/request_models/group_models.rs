@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GroupModel {
+    pub name: String,
+    /// Athletes in the club to put in the group
+    pub athlete_ids: Vec<i32>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GroupMembersModel {
+    /// The athletes in the club who make up the group, replacing its current members
+    pub athlete_ids: Vec<i32>,
+}
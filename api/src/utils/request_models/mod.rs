@@ -1,3 +1,14 @@
+pub mod admin_models;
+pub mod analytics_models;
 pub mod auth_models;
 pub mod club_models;
+pub mod comment_models;
+pub mod goal_models;
+pub mod group_models;
+pub mod plan_models;
+pub mod session_models;
+pub mod skill_models;
+pub mod sync_pair_models;
+pub mod turn_models;
+pub mod two_factor_models;
 pub mod user_models;
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PlanSkillModel {
+    /// FIG notation with dashes written as 0, e.g. 41 for a barani
+    pub fig_rep: i32,
+    /// TUCK, PIKE, STRAIGHT or NONE
+    pub position: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PlanItemModel {
+    /// SKILL to count one skill wherever it is landed, or TURN to count turns of exactly these skills
+    pub kind: String,
+    pub skills: Vec<PlanSkillModel>,
+    /// How many times the skill or turn should be done
+    pub repetitions: u32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PlanModel {
+    /// DMT, TRA or TUM
+    pub event: String,
+    /// The day the plan is for, as YYYY-MM-DD
+    pub scheduled_for: String,
+    pub name: String,
+    pub items: Vec<PlanItemModel>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AssignPlanModel {
+    /// Athletes in the club to assign the plan to
+    pub athlete_ids: Vec<i32>,
+    /// Also assign the plan to the athletes currently in these groups of the club
+    pub group_ids: Option<Vec<i32>>,
+    /// Also assign the plan to every athlete currently in the club
+    pub whole_club: Option<bool>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct StartSessionModel {
+    /// DMT, TRA or TUM
+    pub event: String,
+    pub summary: String,
+    /// The plan the session is trained against, which must be for the same event
+    pub plan_id: Option<i32>,
+}
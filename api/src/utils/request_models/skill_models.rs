@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CatalogueSkillModel {
+    /// DMT, TRA or TUM
+    pub event: String,
+    /// FIG notation with dashes written as 0, e.g. 41 for a barani
+    pub fig_rep: i32,
+    /// TUCK, PIKE, STRAIGHT or NONE
+    pub position: String,
+    /// What the skill is called, e.g. Barani
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SkillQueryModel {
+    /// Only list skills in one event, DMT, TRA or TUM
+    pub event: Option<String>,
+}
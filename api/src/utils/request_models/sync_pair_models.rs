@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SyncPairModel {
+    /// The athlete to pair with, who has to accept before the pair can log turns
+    pub partner_id: i32,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SkillModel {
+    /// FIG notation with dashes written as 0, e.g. 41 for a barani
+    pub fig_rep: i32,
+    /// FORWARD, BACKWARD or NONE
+    pub direction: String,
+    /// TUCK, PIKE, STRAIGHT or NONE
+    pub position: String,
+    /// Execution deduction from 0.0 to 0.5, in tenths, when the turn was judged
+    pub deduction: Option<f32>,
+    /// Seconds from take-off to landing
+    pub time_of_flight: Option<f32>,
+    /// Zones out from the centre of the bed the skill landed, from 0 to 3
+    pub landing_zone: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TurnModel {
+    pub session_id: i32,
+    pub note: String,
+    /// Landing deduction from 0.0 to 1.0, in tenths, when the turn was judged
+    pub landing_deduction: Option<f32>,
+    /// Total seconds in the air, when it was not timed per skill
+    pub time_of_flight: Option<f32>,
+    /// Total horizontal displacement deduction in tenths, when landing zones were not recorded
+    pub horizontal_deduction: Option<f32>,
+    /// Penalties taken off the total, in tenths
+    pub penalty: Option<f32>,
+    /// The pair performing the turn when it was synchronized, on trampoline only
+    pub sync_pair_id: Option<i32>,
+    /// Synchronization score from the measuring system, up to 2.0 per skill
+    pub sync_score: Option<f32>,
+    pub skills: Vec<SkillModel>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ExecutionModel {
+    /// One deduction per skill, in the order they were performed
+    pub deductions: Vec<f32>,
+    pub landing_deduction: f32,
+}
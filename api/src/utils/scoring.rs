@@ -0,0 +1,396 @@
+use crate::entities::sea_orm_active_enums::{Event, Position};
+
+// Skills in a full trampoline routine
+pub const MAX_SKILLS: usize = 10;
+// Execution deductions are given in tenths of a point
+pub const MAX_SKILL_DEDUCTION_TENTHS: u32 = 5;
+pub const MAX_LANDING_DEDUCTION_TENTHS: u32 = 10;
+pub const MAX_PENALTY_TENTHS: u32 = 100;
+// Landing zones count out from the centre of the bed, each costing 0.1 of displacement
+pub const MAX_LANDING_ZONE: u32 = 3;
+// No skill is in the air for more than 3 seconds, in milliseconds
+pub const MAX_SKILL_TIME_OF_FLIGHT: u32 = 3000;
+pub const MAX_TURN_TIME_OF_FLIGHT: u32 = MAX_SKILL_TIME_OF_FLIGHT * MAX_SKILLS as u32;
+// Synchronized pairs score up to 2.0 for each skill landed together, in thousandths of a point
+pub const MAX_SKILL_SYNCHRONIZATION_THOUSANDTHS: u32 = 2000;
+// A skill is landed consistently when it loses no more than 0.2 for execution
+pub const MAX_CONSISTENT_DEDUCTION_TENTHS: u32 = 2;
+
+// A skill in FIG numeric notation: the quarter somersaults, then the half twists in each
+// somersault. Stored as a number with dashes written as 0, e.g. 41 for a barani, 800 for a
+// double back or 12000 for a triple
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notation {
+    pub quarter_somersaults: u32,
+    pub half_twists: Vec<u32>,
+}
+
+impl Notation {
+    pub fn parse(fig_rep: i32) -> Option<Notation> {
+        if fig_rep <= 0 {
+            return None;
+        }
+        let digits = fig_rep.to_string();
+
+        // The quarter somersaults take one or two digits, there is a twist digit per somersault
+        (1..=2.min(digits.len())).find_map(|split| {
+            let (quarters, twists) = digits.split_at(split);
+            let quarter_somersaults = quarters.parse::<u32>().ok()?;
+            let somersaults = quarter_somersaults.div_ceil(4).max(1) as usize;
+
+            if quarter_somersaults == 0 || twists.len() != somersaults {
+                return None;
+            }
+
+            Some(Notation {
+                quarter_somersaults,
+                half_twists: twists.chars().filter_map(|c| c.to_digit(10)).collect(),
+            })
+        })
+    }
+
+    pub fn somersaults(&self) -> u32 {
+        self.quarter_somersaults / 4
+    }
+
+    pub fn total_half_twists(&self) -> u32 {
+        self.half_twists.iter().sum()
+    }
+
+    // FIG trampoline tariff: 0.1 per quarter somersault, 0.1 per completed somersault and
+    // 0.1 per half twist. Piked and straight shapes add 0.1 to untwisted singles and 0.1 per
+    // somersault to multiples. Other events have no tariff here, see has_tariff
+    pub fn difficulty_tenths(&self, event: Event, position: Position) -> Option<u32> {
+        if !has_tariff(event) {
+            return None;
+        }
+
+        let somersaults = self.somersaults();
+        let twists = self.total_half_twists();
+        let mut tenths = self.quarter_somersaults + somersaults + twists;
+
+        if matches!(position, Position::Pike | Position::Straight) {
+            match somersaults {
+                1 if twists == 0 => tenths += 1,
+                2.. => tenths += somersaults,
+                _ => {}
+            }
+        }
+
+        Some(tenths)
+    }
+}
+
+// Double mini and tumbling elements are valued from their own FIG tables, which the notation
+// doesn't capture, so their difficulty is left unscored
+pub fn has_tariff(event: Event) -> bool {
+    event == Event::Tra
+}
+
+// Reads a score given in points as whole tenths, rejecting anything finer or out of range
+pub fn to_tenths(points: f32, max_tenths: u32) -> Option<u32> {
+    let tenths = (points * 10.0).round();
+
+    if (points * 10.0 - tenths).abs() > 0.001 || tenths < 0.0 || tenths > max_tenths as f32 {
+        return None;
+    }
+
+    Some(tenths as u32)
+}
+
+pub fn to_points(tenths: u32) -> f32 {
+    tenths as f32 / 10.0
+}
+
+// Scores are stored as floats, this undoes the rounding error when reading them back
+pub fn nearest_tenths(points: f32) -> u32 {
+    (points * 10.0).round().max(0.0) as u32
+}
+
+// FIG execution: each skill performed is worth 1.0, the skill and landing deductions come off
+// that and the result is doubled, so a clean ten skill routine scores 20.0
+pub fn execution_tenths(skill_deductions: &[u32], landing_deduction: u32) -> u32 {
+    let available = skill_deductions.len() as u32 * 10;
+    let deducted = skill_deductions.iter().sum::<u32>() + landing_deduction;
+
+    available.saturating_sub(deducted) * 2
+}
+
+// FIG horizontal displacement: each skill performed is worth 1.0, less 0.1 for every zone its
+// landing was out from the centre, so a routine landed in the centre every time scores 10.0
+pub fn horizontal_tenths(skills: usize, deduction: u32) -> u32 {
+    (skills as u32 * 10).saturating_sub(deduction)
+}
+
+// Reads a time of flight given in seconds as whole milliseconds, rejecting anything out of range
+pub fn to_milliseconds(seconds: f32, max_milliseconds: u32) -> Option<u32> {
+    let milliseconds = (seconds * 1000.0).round();
+
+    if !seconds.is_finite() || milliseconds < 0.0 || milliseconds > max_milliseconds as f32 {
+        return None;
+    }
+
+    Some(milliseconds as u32)
+}
+
+pub fn milliseconds_to_seconds(milliseconds: u32) -> f32 {
+    milliseconds as f32 / 1000.0
+}
+
+pub fn nearest_milliseconds(seconds: f32) -> u32 {
+    (seconds * 1000.0).round().max(0.0) as u32
+}
+
+// Reads a synchronization score as reported by the measuring system, to the thousandth of a point
+pub fn to_thousandths(points: f32, max_thousandths: u32) -> Option<u32> {
+    let thousandths = (points * 1000.0).round();
+
+    if !points.is_finite() || thousandths < 0.0 || thousandths > max_thousandths as f32 {
+        return None;
+    }
+
+    Some(thousandths as u32)
+}
+
+pub fn thousandths_to_points(thousandths: u32) -> f32 {
+    thousandths as f32 / 1000.0
+}
+
+pub fn nearest_thousandths(points: f32) -> u32 {
+    (points * 1000.0).round().max(0.0) as u32
+}
+
+pub fn is_consistent(deduction_tenths: u32) -> bool {
+    deduction_tenths <= MAX_CONSISTENT_DEDUCTION_TENTHS
+}
+
+// A share as a whole percentage, rounded down so nothing shows 100% before it is done
+pub fn percentage(part: u32, whole: u32) -> u32 {
+    if whole == 0 {
+        return 0;
+    }
+
+    part.min(whole) * 100 / whole
+}
+
+// The parts of a turn's score. Execution is only scored once the turn is judged, and time of
+// flight, horizontal displacement and synchronization when they were measured
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Breakdown {
+    pub difficulty: u32,              // Tenths
+    pub execution: Option<u32>,       // Tenths
+    pub time_of_flight: Option<u32>,  // Milliseconds, one point per second
+    pub horizontal: Option<u32>,      // Tenths
+    pub synchronization: Option<u32>, // Thousandths
+    pub penalty: u32,                 // Tenths
+}
+
+impl Breakdown {
+    // D + E + T + H + S - penalties, in thousandths of a point since flight is timed to the
+    // millisecond
+    pub fn total_thousandths(&self) -> u32 {
+        let tenths = [
+            self.difficulty,
+            self.execution.unwrap_or(0),
+            self.horizontal.unwrap_or(0),
+        ]
+        .into_iter()
+        .fold(0u32, u32::saturating_add);
+        let thousandths = self
+            .time_of_flight
+            .unwrap_or(0)
+            .saturating_add(self.synchronization.unwrap_or(0));
+
+        tenths
+            .saturating_mul(100)
+            .saturating_add(thousandths)
+            .saturating_sub(self.penalty.saturating_mul(100))
+    }
+
+    pub fn total_points(&self) -> f32 {
+        self.total_thousandths() as f32 / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn difficulty(fig_rep: i32, position: Position) -> u32 {
+        Notation::parse(fig_rep)
+            .unwrap()
+            .difficulty_tenths(Event::Tra, position)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_fig_notation() {
+        assert_eq!(
+            Notation::parse(41),
+            Some(Notation {
+                quarter_somersaults: 4,
+                half_twists: vec![1],
+            })
+        );
+        assert_eq!(
+            Notation::parse(822),
+            Some(Notation {
+                quarter_somersaults: 8,
+                half_twists: vec![2, 2],
+            })
+        );
+        assert_eq!(
+            Notation::parse(12000),
+            Some(Notation {
+                quarter_somersaults: 12,
+                half_twists: vec![0, 0, 0],
+            })
+        );
+        assert_eq!(Notation::parse(4), None);
+        assert_eq!(Notation::parse(80), None);
+        assert_eq!(Notation::parse(-40), None);
+    }
+
+    #[test]
+    fn calculates_trampoline_tariffs() {
+        assert_eq!(difficulty(40, Position::Tuck), 5); // Back somersault
+        assert_eq!(difficulty(40, Position::Pike), 6);
+        assert_eq!(difficulty(41, Position::Straight), 6); // Barani
+        assert_eq!(difficulty(42, Position::Straight), 7); // Full
+        assert_eq!(difficulty(43, Position::Straight), 8); // Rudi
+        assert_eq!(difficulty(800, Position::Tuck), 10); // Double back
+        assert_eq!(difficulty(800, Position::Pike), 12);
+        assert_eq!(difficulty(822, Position::Tuck), 14); // Full-in-full-out
+    }
+
+    #[test]
+    fn leaves_other_events_unscored() {
+        let notation = Notation::parse(800).unwrap();
+
+        assert_eq!(notation.difficulty_tenths(Event::Dmt, Position::Tuck), None);
+        assert_eq!(
+            notation.difficulty_tenths(Event::Tum, Position::Straight),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_deductions_in_tenths() {
+        assert_eq!(to_tenths(0.3, MAX_SKILL_DEDUCTION_TENTHS), Some(3));
+        assert_eq!(to_tenths(0.0, MAX_SKILL_DEDUCTION_TENTHS), Some(0));
+        assert_eq!(to_tenths(0.6, MAX_SKILL_DEDUCTION_TENTHS), None);
+        assert_eq!(to_tenths(0.25, MAX_SKILL_DEDUCTION_TENTHS), None);
+        assert_eq!(to_tenths(-0.1, MAX_SKILL_DEDUCTION_TENTHS), None);
+    }
+
+    #[test]
+    fn scores_execution_out_of_twenty() {
+        assert_eq!(execution_tenths(&[0; 10], 0), 200);
+        assert_eq!(execution_tenths(&[1, 2, 1, 1, 2, 1, 1, 1, 2, 1], 3), 168);
+        // An interrupted routine only earns execution for the skills performed
+        assert_eq!(execution_tenths(&[1, 1, 1], 0), 54);
+        assert_eq!(execution_tenths(&[5; 2], 10), 0);
+    }
+
+    #[test]
+    fn scores_horizontal_displacement_out_of_ten() {
+        assert_eq!(horizontal_tenths(10, 0), 100);
+        // Five landings one zone out and one two zones out
+        assert_eq!(horizontal_tenths(10, 7), 93);
+        assert_eq!(horizontal_tenths(2, 30), 0);
+    }
+
+    #[test]
+    fn reads_time_of_flight_in_milliseconds() {
+        assert_eq!(to_milliseconds(1.745, MAX_SKILL_TIME_OF_FLIGHT), Some(1745));
+        assert_eq!(to_milliseconds(17.47, MAX_TURN_TIME_OF_FLIGHT), Some(17470));
+        assert_eq!(to_milliseconds(3.0, MAX_SKILL_TIME_OF_FLIGHT), Some(3000));
+        assert_eq!(to_milliseconds(3.001, MAX_SKILL_TIME_OF_FLIGHT), None);
+        assert_eq!(to_milliseconds(1e7, MAX_TURN_TIME_OF_FLIGHT), None);
+        assert_eq!(to_milliseconds(-0.1, MAX_SKILL_TIME_OF_FLIGHT), None);
+        assert_eq!(to_milliseconds(f32::NAN, MAX_SKILL_TIME_OF_FLIGHT), None);
+    }
+
+    #[test]
+    fn totals_saturate_instead_of_overflowing() {
+        let breakdown = Breakdown {
+            difficulty: u32::MAX,
+            time_of_flight: Some(u32::MAX),
+            ..Default::default()
+        };
+
+        assert_eq!(breakdown.total_thousandths(), u32::MAX);
+    }
+
+    // Breakdowns as results sheets show them, with every part to three decimals
+    #[test]
+    fn totals_match_results_sheets() {
+        // D 17.400 + E 16.800 + T 17.915 + H 9.600
+        let breakdown = Breakdown {
+            difficulty: 174,
+            execution: Some(168),
+            time_of_flight: Some(17915),
+            horizontal: Some(96),
+            synchronization: None,
+            penalty: 0,
+        };
+        assert_eq!(breakdown.total_thousandths(), 61715);
+        assert_eq!(breakdown.total_points(), 61.715);
+
+        // D 16.500 + E 17.000 + T 18.050 + H 9.300 - 0.200 penalty
+        let breakdown = Breakdown {
+            difficulty: 165,
+            execution: Some(170),
+            time_of_flight: Some(18050),
+            horizontal: Some(93),
+            synchronization: None,
+            penalty: 2,
+        };
+        assert_eq!(breakdown.total_thousandths(), 60650);
+    }
+
+    #[test]
+    fn unmeasured_parts_are_left_out_of_the_total() {
+        let breakdown = Breakdown {
+            difficulty: 26,
+            execution: Some(44),
+            ..Default::default()
+        };
+        assert_eq!(breakdown.total_thousandths(), 7000);
+
+        // An unjudged turn scores its difficulty alone
+        let breakdown = Breakdown {
+            difficulty: 26,
+            ..Default::default()
+        };
+        assert_eq!(breakdown.total_thousandths(), 2600);
+    }
+
+    #[test]
+    fn synchronized_turns_add_their_synchronization_score() {
+        assert_eq!(to_thousandths(18.4, 20000), Some(18400));
+        assert_eq!(to_thousandths(19.045, 20000), Some(19045));
+        assert_eq!(to_thousandths(20.5, 20000), None);
+        assert_eq!(to_thousandths(-1.0, 20000), None);
+
+        // D 15.200 + E 16.400 + H 9.500 + S 18.620
+        let breakdown = Breakdown {
+            difficulty: 152,
+            execution: Some(164),
+            horizontal: Some(95),
+            synchronization: Some(18620),
+            ..Default::default()
+        };
+        assert_eq!(breakdown.total_thousandths(), 59720);
+    }
+
+    #[test]
+    fn percentages_round_down() {
+        assert_eq!(percentage(2, 3), 66);
+        assert_eq!(percentage(3, 3), 100);
+        assert_eq!(percentage(4, 3), 100);
+        assert_eq!(percentage(0, 0), 0);
+        assert!(is_consistent(2));
+        assert!(!is_consistent(3));
+    }
+}
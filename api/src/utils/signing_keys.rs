@@ -0,0 +1,264 @@
+use std::sync::RwLock;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, NaiveDateTime, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey};
+use ring::{
+    rand::{SecureRandom, SystemRandom},
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, Set,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{
+    config::{Config, Secret, SigningKeyConfig},
+    encryption::{is_encrypted, EncryptionKey},
+    migrations::{self, Lock},
+};
+use crate::entities;
+
+const ALGORITHM: &str = "EdDSA";
+
+// The public keys served at /.well-known/jwks.json
+#[derive(Serialize, ToSchema)]
+pub struct JwkSet {
+    pub keys: Vec<PublicJwk>,
+}
+
+// An Ed25519 public key in JWK format (RFC 8037)
+#[derive(Serialize, ToSchema)]
+pub struct PublicJwk {
+    #[serde(rename = "use")]
+    #[schema(example = "sig")]
+    pub public_key_use: String,
+    #[schema(example = "EdDSA")]
+    pub alg: String,
+    pub kid: String,
+    #[schema(example = "OKP")]
+    pub kty: String,
+    #[schema(example = "Ed25519")]
+    pub crv: String,
+    pub x: String, // The base64url public key
+}
+
+// A decoded key, ready to sign or verify tokens
+pub struct SigningKey {
+    pub kid: String,
+    pub public_key: String,
+    pub activates_at: NaiveDateTime,
+    pub retires_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+}
+
+// Every key that has not expired yet. New tokens are signed with the active key, while
+// retired keys are kept until the tokens they signed have expired
+pub struct KeyStore {
+    keys: RwLock<Vec<SigningKey>>,
+    settings: SigningKeyConfig,
+    encryption_key: EncryptionKey,
+    legacy_secret: Option<(Secret, NaiveDateTime)>,
+}
+
+impl KeyStore {
+    // Loads the keys from the database, creating the first one if needed
+    pub async fn load(db: &DatabaseConnection, config: &Config) -> Result<KeyStore, DbErr> {
+        let key_store = KeyStore {
+            keys: RwLock::new(Vec::new()),
+            settings: config.signing_keys,
+            encryption_key: config.encryption_key.clone(),
+            legacy_secret: config
+                .legacy_secret
+                .clone()
+                .zip(config.legacy_secret_expires_at)
+                .map(|(secret, expires_at)| (secret, expires_at.naive_utc())),
+        };
+        key_store.rotate_if_due(db).await?;
+        Ok(key_store)
+    }
+
+    // Removes expired keys, publishes the next key ahead of time, then reloads from the
+    // database so keys created by other replicas are picked up. Replicas take turns, so
+    // they can't both publish a key
+    pub async fn rotate_if_due(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        migrations::with_lock(db, Lock::KeyRotation, self.rotate(db)).await?;
+
+        let keys = entities::signing_key::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|key| key.algorithm == ALGORITHM)
+            .map(|key| decode_key(&self.encryption_key, key))
+            .collect::<Result<Vec<SigningKey>, DbErr>>()?;
+
+        *self.keys.write().unwrap() = keys;
+        Ok(())
+    }
+
+    async fn rotate(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+
+        entities::signing_key::Entity::delete_many()
+            .filter(entities::signing_key::Column::ExpiresAt.lt(now))
+            .exec(db)
+            .await?;
+
+        let keys = entities::signing_key::Entity::find().all(db).await?;
+        let latest_retirement = keys.iter().map(|key| key.retires_at).max();
+
+        // Keys stored before encryption at rest are encrypted in place
+        for key in keys
+            .into_iter()
+            .filter(|key| !is_encrypted(&key.private_key))
+        {
+            let private_key =
+                encrypt_private_key(&self.encryption_key, &key.kid, &key.private_key)?;
+
+            let mut key = key.into_active_model();
+            key.private_key = Set(private_key);
+            key.update(db).await?;
+        }
+
+        match latest_retirement {
+            None => create_key(db, &self.settings, &self.encryption_key, now).await,
+            Some(retires_at)
+                if retires_at - now < Duration::hours(self.settings.prepublish_hours) =>
+            {
+                create_key(
+                    db,
+                    &self.settings,
+                    &self.encryption_key,
+                    retires_at.max(now),
+                )
+                .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // The key new tokens are signed with
+    pub fn active_key(&self) -> Option<(String, EncodingKey)> {
+        let now = Utc::now().naive_utc();
+        let keys = self.keys.read().unwrap();
+
+        keys.iter()
+            .filter(|key| key.activates_at <= now && now < key.retires_at)
+            .max_by_key(|key| key.activates_at)
+            .map(|key| (key.kid.clone(), key.encoding_key.clone()))
+    }
+
+    pub fn decoding_key(&self, kid: &str) -> Option<DecodingKey> {
+        let now = Utc::now().naive_utc();
+        let keys = self.keys.read().unwrap();
+
+        keys.iter()
+            .find(|key| key.kid == kid && now < key.expires_at)
+            .map(|key| key.decoding_key.clone())
+    }
+
+    // The old shared secret, for tokens signed before key rotation. It stops being accepted
+    // at SECRET_EXPIRES_AT, so it can't be used to mint tokens forever
+    pub fn legacy_secret(&self) -> Option<&Secret> {
+        let now = Utc::now().naive_utc();
+
+        self.legacy_secret
+            .as_ref()
+            .filter(|(_, expires_at)| now < *expires_at)
+            .map(|(secret, _)| secret)
+    }
+
+    // Public keys in JWKS format, so other services can verify our tokens
+    pub fn jwks(&self) -> JwkSet {
+        let keys = self.keys.read().unwrap();
+
+        JwkSet {
+            keys: keys
+                .iter()
+                .map(|key| PublicJwk {
+                    public_key_use: "sig".to_string(),
+                    alg: ALGORITHM.to_string(),
+                    kid: key.kid.clone(),
+                    kty: "OKP".to_string(),
+                    crv: "Ed25519".to_string(),
+                    x: key.public_key.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+async fn create_key(
+    db: &DatabaseConnection,
+    settings: &SigningKeyConfig,
+    encryption_key: &EncryptionKey,
+    activates_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let rng = SystemRandom::new();
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(key_error)?;
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(key_error)?;
+
+    let mut kid = [0u8; 12];
+    rng.fill(&mut kid).map_err(key_error)?;
+
+    let kid = URL_SAFE_NO_PAD.encode(kid);
+    let private_key = encrypt_private_key(
+        encryption_key,
+        &kid,
+        &URL_SAFE_NO_PAD.encode(pkcs8.as_ref()),
+    )?;
+    let retires_at = activates_at + Duration::days(settings.rotation_days);
+
+    entities::signing_key::ActiveModel {
+        kid: Set(kid),
+        algorithm: Set(ALGORITHM.to_string()),
+        private_key: Set(private_key),
+        public_key: Set(URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref())),
+        created_at: Set(Utc::now().naive_utc()),
+        activates_at: Set(activates_at),
+        retires_at: Set(retires_at),
+        expires_at: Set(retires_at + Duration::hours(settings.verify_grace_hours)),
+    }
+    .insert(db)
+    .await?;
+
+    Ok(())
+}
+
+fn key_error<E>(_: E) -> DbErr {
+    DbErr::Custom("Failed to generate signing key".to_string())
+}
+
+fn encrypt_private_key(key: &EncryptionKey, kid: &str, private_key: &str) -> Result<String, DbErr> {
+    key.encrypt(private_key, &format!("signing_key:{}", kid))
+        .map_err(DbErr::Custom)
+}
+
+fn decode_key(
+    encryption_key: &EncryptionKey,
+    key: entities::signing_key::Model,
+) -> Result<SigningKey, DbErr> {
+    let private_key = encryption_key
+        .decrypt(&key.private_key, &format!("signing_key:{}", key.kid))
+        .map_err(|err| DbErr::Custom(format!("Invalid signing key {}: {}", key.kid, err)))?;
+    let private_key = URL_SAFE_NO_PAD
+        .decode(&private_key)
+        .map_err(|err| DbErr::Custom(format!("Invalid signing key {}: {}", key.kid, err)))?;
+    let decoding_key = DecodingKey::from_ed_components(&key.public_key)
+        .map_err(|err| DbErr::Custom(format!("Invalid signing key {}: {}", key.kid, err)))?;
+
+    Ok(SigningKey {
+        kid: key.kid,
+        public_key: key.public_key,
+        activates_at: key.activates_at,
+        retires_at: key.retires_at,
+        expires_at: key.expires_at,
+        encoding_key: EncodingKey::from_ed_der(&private_key),
+        decoding_key,
+    })
+}
@@ -0,0 +1,172 @@
+mod support;
+
+use chrono::{Datelike, Days, Utc};
+use serde_json::json;
+use support::{field, spawn_app};
+
+// A barani, a rudi and a double back pike: 2.6 DD, four somersaults and two twists
+fn routine(session_id: i32) -> serde_json::Value {
+    json!({
+        "session_id": session_id,
+        "note": "",
+        "skills": [
+            { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT" },
+            { "fig_rep": 43, "direction": "FORWARD", "position": "STRAIGHT" },
+            { "fig_rep": 800, "direction": "BACKWARD", "position": "PIKE" },
+        ],
+    })
+}
+
+#[actix_web::test]
+async fn an_athletes_training_is_added_up_per_period() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    app.post("/turn/create", &athlete, routine(session_id))
+        .await;
+    app.log_turn(&athlete, session_id, 3).await;
+    let today = Utc::now().date_naive();
+
+    let res = app
+        .get(
+            &format!("/analytics/athlete/{}?period=day", athlete_id),
+            &athlete,
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "period"), "day");
+    assert_eq!(field(&res.body, "event_id"), "null");
+    assert_eq!(field(&res.body, "start"), today.to_string());
+    assert_eq!(field(&res.body, "sessions"), "1");
+    assert_eq!(field(&res.body, "turns"), "2");
+    assert_eq!(field(&res.body, "skills"), "6");
+    assert_eq!(field(&res.body, "total_difficulty"), "4.1");
+    assert_eq!(field(&res.body, "somersaults"), "7");
+    assert_eq!(field(&res.body, "twists"), "2");
+
+    // Weeks are the default and start on a Monday
+    let monday = today
+        .checked_sub_days(Days::new(today.weekday().num_days_from_monday() as u64))
+        .unwrap();
+    let res = app
+        .get(&format!("/analytics/athlete/{}", athlete_id), &athlete)
+        .await;
+    assert_eq!(field(&res.body, "period"), "week");
+    assert_eq!(field(&res.body, "start"), monday.to_string());
+}
+
+#[actix_web::test]
+async fn training_can_be_filtered_by_event_and_dates() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    app.log_turn(&athlete, session_id, 2).await;
+    app.post(&format!("/session/{}/end", session_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "DMT").await;
+    app.log_turn(&athlete, session_id, 2).await;
+    let today = Utc::now().date_naive();
+
+    let res = app
+        .get(
+            &format!("/analytics/athlete/{}?event=DMT", athlete_id),
+            &athlete,
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "event_id"), "DMT");
+    assert_eq!(field(&res.body, "sessions"), "1");
+    assert_eq!(field(&res.body, "turns"), "1");
+
+    // Both ends of the range are included
+    let res = app
+        .get(
+            &format!(
+                "/analytics/athlete/{}?from={}&to={}",
+                athlete_id, today, today
+            ),
+            &athlete,
+        )
+        .await;
+    assert_eq!(field(&res.body, "sessions"), "2");
+    assert_eq!(field(&res.body, "skills"), "4");
+
+    let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+    let res = app
+        .get(
+            &format!("/analytics/athlete/{}?from={}", athlete_id, tomorrow),
+            &athlete,
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res.body.ends_with("'buckets': [  ] }"));
+
+    for query in [
+        "period=year",
+        "event=SWIM",
+        "from=yesterday",
+        "from=2025-03-02&to=2025-03-01",
+    ] {
+        let res = app
+            .get(
+                &format!("/analytics/athlete/{}?{}", athlete_id, query),
+                &athlete,
+            )
+            .await;
+        assert_eq!(res.status, 422, "{}", query);
+    }
+}
+
+#[actix_web::test]
+async fn coaches_see_their_whole_roster() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, other_coach) = app.coach("othercoach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    for token in [&athlete, &partner] {
+        app.post(&format!("/club/{}/join", club_id), token, json!({}))
+            .await;
+    }
+
+    let session_id = app.start_session(&athlete, "TRA").await;
+    app.log_turn(&athlete, session_id, 3).await;
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+    let mut turn = routine(session_id);
+    turn["sync_pair_id"] = json!(sync_pair_id);
+    let res = app.post("/turn/create", &athlete, turn).await;
+    assert_eq!(res.status, 201);
+    let session_id = app.start_session(&partner, "TRA").await;
+    app.log_turn(&partner, session_id, 1).await;
+
+    // The synchronized turn is in both partners' training
+    let res = app
+        .get(&format!("/analytics/athlete/{}", partner_id), &coach)
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "turns"), "2");
+
+    // But only counted once for the club
+    let res = app
+        .get(&format!("/analytics/club/{}", club_id), &coach)
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "sessions"), "2");
+    assert_eq!(field(&res.body, "turns"), "3");
+    assert_eq!(field(&res.body, "skills"), "7");
+
+    for token in [&other_coach, &athlete] {
+        let res = app
+            .get(&format!("/analytics/club/{}", club_id), token)
+            .await;
+        assert_eq!(res.status, 401);
+    }
+    let res = app
+        .get(&format!("/analytics/athlete/{}", athlete_id), &other_coach)
+        .await;
+    assert_eq!(res.status, 401);
+
+    let res = app.get("/analytics/club/999", &coach).await;
+    assert_eq!(res.status, 404);
+}
@@ -0,0 +1,456 @@
+mod support;
+
+use actix_web::http::Method;
+use api::{entities, routes::services::two_factor_service};
+use chrono::{Duration, Utc};
+use sea_orm::{sea_query::Expr, EntityTrait};
+use serde_json::json;
+use support::{field, spawn_app, totp_code};
+
+#[actix_web::test]
+async fn register_returns_the_new_user_id() {
+    let app = spawn_app().await;
+
+    let first = app.register("A", "first@example.com").await;
+    let second = app.register("C", "second@example.com").await;
+
+    assert_ne!(first, second);
+}
+
+#[actix_web::test]
+async fn register_rejects_a_duplicate_email_and_type() {
+    let app = spawn_app().await;
+    app.register("A", "athlete@example.com").await;
+
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/register",
+            None,
+            Some(json!({
+                "user_type": "A",
+                "name_first": "Test",
+                "name_last": "User",
+                "email": "athlete@example.com",
+                "password": "password",
+            })),
+        )
+        .await;
+
+    assert_eq!(res.status, 409);
+}
+
+#[actix_web::test]
+async fn register_rejects_an_unknown_user_type() {
+    let app = spawn_app().await;
+
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/register",
+            None,
+            Some(json!({
+                "user_type": "X",
+                "name_first": "Test",
+                "name_last": "User",
+                "email": "someone@example.com",
+                "password": "password",
+            })),
+        )
+        .await;
+
+    assert_eq!(res.status, 500);
+    assert_eq!(res.body, "Invalid user type, must be A or C");
+}
+
+#[actix_web::test]
+async fn login_returns_a_token_that_authenticates_requests() {
+    let app = spawn_app().await;
+    let (user_id, token) = app.athlete("athlete@example.com").await;
+
+    let res = app.get(&format!("/user/{}", user_id), &token).await;
+
+    assert_eq!(res.status, 200);
+    assert!(res.body.contains("athlete@example.com"));
+}
+
+#[actix_web::test]
+async fn login_rejects_a_wrong_password() {
+    let app = spawn_app().await;
+    app.register("A", "athlete@example.com").await;
+
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login",
+            None,
+            Some(json!({
+                "user_type": "A",
+                "email": "athlete@example.com",
+                "password": "wrong",
+            })),
+        )
+        .await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Invalid email or password");
+}
+
+#[actix_web::test]
+async fn login_rejects_the_wrong_user_type() {
+    let app = spawn_app().await;
+    app.register("A", "athlete@example.com").await;
+
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login",
+            None,
+            Some(json!({
+                "user_type": "C",
+                "email": "athlete@example.com",
+                "password": "password",
+            })),
+        )
+        .await;
+
+    assert_eq!(res.status, 401);
+}
+
+#[actix_web::test]
+async fn protected_routes_require_a_token() {
+    let app = spawn_app().await;
+
+    let missing = app.request(Method::GET, "/user/1", None, None).await;
+    let invalid = app.get("/user/1", "not-a-token").await;
+
+    assert_eq!(missing.status, 401);
+    assert_eq!(invalid.status, 401);
+}
+
+// The seconds left on a lockout, from a 429 login response
+fn seconds_left(body: &str) -> i64 {
+    body.strip_prefix("Too many failed login attempts. Try again in ")
+        .and_then(|rest| rest.strip_suffix(" seconds"))
+        .unwrap_or_else(|| panic!("not a lockout: {}", body))
+        .parse()
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn repeated_failures_lock_the_account() {
+    let app = spawn_app().await;
+    app.register("A", "athlete@example.com").await;
+
+    for _ in 0..5 {
+        let res = app.try_login("A", "athlete@example.com", "wrong").await;
+        assert_eq!(res.status, 401);
+    }
+
+    // Even the right password is refused while locked out, for the configured 60 seconds
+    let res = app.try_login("A", "Athlete@Example.com ", "password").await;
+    assert_eq!(res.status, 429);
+    assert!((1..=60).contains(&seconds_left(&res.body)));
+
+    // Other accounts are unaffected
+    app.register("A", "other@example.com").await;
+    let res = app.try_login("A", "other@example.com", "password").await;
+    assert_eq!(res.status, 200);
+}
+
+#[actix_web::test]
+async fn each_failure_past_the_threshold_doubles_the_lockout() {
+    let app = spawn_app().await;
+    app.register("A", "athlete@example.com").await;
+
+    for _ in 0..5 {
+        app.try_login("A", "athlete@example.com", "wrong").await;
+    }
+
+    // Let the first lockout run out, the failures before it still count
+    entities::account_lockout::Entity::update_many()
+        .col_expr(
+            entities::account_lockout::Column::LockedUntil,
+            Expr::value(Utc::now().naive_utc() - Duration::seconds(1)),
+        )
+        .exec(&app.state.db)
+        .await
+        .unwrap();
+    let res = app.try_login("A", "athlete@example.com", "wrong").await;
+    assert_eq!(res.status, 401);
+
+    let res = app.try_login("A", "athlete@example.com", "password").await;
+    assert_eq!(res.status, 429);
+    assert!((61..=120).contains(&seconds_left(&res.body)));
+}
+
+#[actix_web::test]
+async fn admins_clear_lockouts() {
+    let app = spawn_app().await;
+    let (_, admin) = app.admin("admin@example.com").await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    app.register("A", "athlete@example.com").await;
+
+    for _ in 0..5 {
+        app.try_login("A", "athlete@example.com", "wrong").await;
+    }
+
+    let res = app.get("/admin/lockouts?active=true", &coach).await;
+    assert_eq!(res.status, 403);
+    let res = app.get("/admin/lockouts?active=true", &admin).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "scope"), "ACCOUNT");
+    assert_eq!(field(&res.body, "subject"), "athlete@example.com");
+    assert_eq!(field(&res.body, "failed_attempts"), "5");
+    let lockout_id = field(&res.body, "lockout_id");
+
+    let res = app
+        .post(
+            &format!("/admin/lockouts/{}/clear", lockout_id),
+            &admin,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .post(
+            &format!("/admin/lockouts/{}/clear", lockout_id),
+            &admin,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+    let res = app.get("/admin/lockouts?active=true", &admin).await;
+    assert_eq!(res.body, "[  ]");
+
+    // Clearing gives the account a clean slate, so one more failure doesn't lock it again
+    let res = app.try_login("A", "athlete@example.com", "wrong").await;
+    assert_eq!(res.status, 401);
+    let res = app.try_login("A", "athlete@example.com", "password").await;
+    assert_eq!(res.status, 200);
+}
+
+#[actix_web::test]
+async fn enrolled_users_log_in_with_a_single_use_challenge() {
+    let app = spawn_app().await;
+    let (user_id, token) = app.athlete("athlete@example.com").await;
+
+    let res = app.post("/two-factor/enroll", &token, json!({})).await;
+    let secret = field(&res.body, "secret");
+    let res = app
+        .post(
+            "/two-factor/enroll/confirm",
+            &token,
+            json!({ "code": "abcdef" }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+
+    // The secret is only stored encrypted
+    let stored = entities::user_totp::Entity::find_by_id(user_id)
+        .one(&app.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(stored.secret.starts_with("v1:"));
+    assert!(!stored.secret.contains(&secret));
+
+    let res = app
+        .post(
+            "/two-factor/enroll/confirm",
+            &token,
+            json!({ "code": totp_code(&secret, "athlete@example.com", 0) }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body.matches('-').count(), 10);
+    assert!(res.body.ends_with("'token': null }"));
+
+    let challenge_token = app.challenge("athlete@example.com").await;
+    let verify = |code: String| json!({ "challenge_token": challenge_token.clone(), "code": code });
+
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            Some(verify("abcdef".to_string())),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Invalid two-factor code");
+
+    // The code from enrolling was used up, so this is the next one
+    let code = totp_code(&secret, "athlete@example.com", 30);
+    let res = app
+        .request(Method::POST, "/auth/login/verify", None, Some(verify(code)))
+        .await;
+    assert_eq!(res.status, 200, "{}", res.body);
+    let res = app
+        .get(&format!("/user/{}", user_id), &field(&res.body, "token"))
+        .await;
+    assert_eq!(res.status, 200);
+
+    // Replaying the challenge fails before the code is even checked
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            Some(verify("whatever".to_string())),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Invalid or expired two-factor challenge");
+
+    // Challenge tokens aren't access tokens
+    let res = app
+        .get(&format!("/user/{}", user_id), &challenge_token)
+        .await;
+    assert_eq!(res.status, 401);
+}
+
+#[actix_web::test]
+async fn recovery_codes_work_once_and_disable_needs_the_password() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let (_, recovery_codes) = app.enable_two_factor(&token, "athlete@example.com").await;
+    assert_eq!(recovery_codes.len(), 10);
+
+    let verify = |challenge_token: &str, code: &str| {
+        Some(json!({ "challenge_token": challenge_token, "code": code }))
+    };
+
+    let challenge_token = app.challenge("athlete@example.com").await;
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            verify(&challenge_token, &recovery_codes[0]),
+        )
+        .await;
+    assert_eq!(res.status, 200, "{}", res.body);
+
+    // Each recovery code can only be used once, and the dash is optional
+    let challenge_token = app.challenge("athlete@example.com").await;
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            verify(&challenge_token, &recovery_codes[0]),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            verify(&challenge_token, &recovery_codes[1].replace('-', "")),
+        )
+        .await;
+    assert_eq!(res.status, 200, "{}", res.body);
+
+    let res = app
+        .post(
+            "/two-factor/disable",
+            &token,
+            json!({ "password": "wrong", "code": recovery_codes[2] }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Password is incorrect");
+    let res = app
+        .post(
+            "/two-factor/disable",
+            &token,
+            json!({ "password": "password", "code": recovery_codes[1] }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .post(
+            "/two-factor/disable",
+            &token,
+            json!({ "password": "password", "code": recovery_codes[2] }),
+        )
+        .await;
+    assert_eq!(res.status, 200, "{}", res.body);
+
+    // Logging in is back to a single step
+    let res = app.try_login("A", "athlete@example.com", "password").await;
+    assert_eq!(res.status, 200);
+    assert!(!res.body.contains("two_factor_required"));
+    let res = app
+        .post(
+            "/two-factor/disable",
+            &field(&res.body, "token"),
+            json!({ "password": "password", "code": recovery_codes[3] }),
+        )
+        .await;
+    assert_eq!(res.status, 404);
+}
+
+#[actix_web::test]
+async fn concurrent_logins_cannot_share_a_recovery_code() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let (_, recovery_codes) = app.enable_two_factor(&token, "athlete@example.com").await;
+
+    let first = app.challenge("athlete@example.com").await;
+    let second = app.challenge("athlete@example.com").await;
+    let verify = |challenge_token: &str| {
+        app.request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            Some(json!({ "challenge_token": challenge_token, "code": recovery_codes[0] })),
+        )
+    };
+    let (first, second) = tokio::join!(verify(&first), verify(&second));
+
+    let mut statuses = [first.status, second.status];
+    statuses.sort_unstable();
+    assert_eq!(statuses, [200, 401]);
+}
+
+#[actix_web::test]
+async fn secrets_stored_before_encryption_are_encrypted_on_startup() {
+    let app = spawn_app().await;
+    let (user_id, token) = app.athlete("athlete@example.com").await;
+    let (secret, _) = app.enable_two_factor(&token, "athlete@example.com").await;
+
+    // As it was stored before encryption at rest
+    entities::user_totp::Entity::update_many()
+        .col_expr(entities::user_totp::Column::Secret, Expr::value(&secret))
+        .exec(&app.state.db)
+        .await
+        .unwrap();
+
+    two_factor_service::encrypt_stored_secrets(&app.state)
+        .await
+        .unwrap();
+
+    let stored = entities::user_totp::Entity::find_by_id(user_id)
+        .one(&app.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(stored.secret.starts_with("v1:"));
+
+    let challenge_token = app.challenge("athlete@example.com").await;
+    let res = app
+        .request(
+            Method::POST,
+            "/auth/login/verify",
+            None,
+            Some(json!({
+                "challenge_token": challenge_token,
+                "code": totp_code(&secret, "athlete@example.com", 30),
+            })),
+        )
+        .await;
+    assert_eq!(res.status, 200, "{}", res.body);
+}
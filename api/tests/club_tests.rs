@@ -0,0 +1,419 @@
+mod support;
+
+use serde_json::json;
+use support::{field, spawn_app};
+
+#[actix_web::test]
+async fn coach_creates_a_club_and_becomes_its_owner() {
+    let app = spawn_app().await;
+    let (coach_id, token) = app.coach("coach@example.com").await;
+
+    let club_id = app.create_club(&token, "Bouncers").await;
+
+    let res = app.get(&format!("/club/{}", club_id), &token).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "name"), "bouncers");
+    assert_eq!(field(&res.body, "owner_id"), coach_id.to_string());
+
+    let res = app.get(&format!("/user/{}/club", coach_id), &token).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "club_id"), club_id.to_string());
+}
+
+#[actix_web::test]
+async fn athletes_cannot_create_clubs() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+
+    let res = app
+        .post("/club/create", &token, json!({ "name": "Bouncers" }))
+        .await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "Coach account not found for that email");
+}
+
+#[actix_web::test]
+async fn coach_cannot_create_a_second_club() {
+    let app = spawn_app().await;
+    let (_, token) = app.coach("coach@example.com").await;
+    app.create_club(&token, "Bouncers").await;
+
+    let res = app
+        .post("/club/create", &token, json!({ "name": "Flippers" }))
+        .await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "Users cannot be part of two clubs at once");
+}
+
+#[actix_web::test]
+async fn club_names_are_unique_ignoring_case() {
+    let app = spawn_app().await;
+    let (_, first) = app.coach("first@example.com").await;
+    let (_, second) = app.coach("second@example.com").await;
+    app.create_club(&first, "Bouncers").await;
+
+    let res = app
+        .post("/club/create", &second, json!({ "name": "BOUNCERS" }))
+        .await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(
+        res.body,
+        "A club with that name already exists. Please try a different name"
+    );
+}
+
+#[actix_web::test]
+async fn get_club_reports_a_missing_club() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+
+    let res = app.get("/club/999", &token).await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "No club found with that club_id");
+}
+
+#[actix_web::test]
+async fn athlete_joins_a_club() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+
+    let res = app
+        .post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "user_id"), athlete_id.to_string());
+    assert_eq!(field(&res.body, "club_id"), club_id.to_string());
+}
+
+#[actix_web::test]
+async fn joining_a_missing_club_fails() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+
+    let res = app.post("/club/999/join", &athlete, json!({})).await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "No club found with that club_id");
+}
+
+#[actix_web::test]
+async fn users_cannot_join_two_clubs() {
+    let app = spawn_app().await;
+    let (_, first) = app.coach("first@example.com").await;
+    let (_, second) = app.coach("second@example.com").await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let first_club = app.create_club(&first, "Bouncers").await;
+    let second_club = app.create_club(&second, "Flippers").await;
+    app.post(&format!("/club/{}/join", first_club), &athlete, json!({}))
+        .await;
+
+    let res = app
+        .post(&format!("/club/{}/join", second_club), &athlete, json!({}))
+        .await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "User is already a member of a club");
+}
+
+#[actix_web::test]
+async fn member_leaves_a_club() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app
+        .post(&format!("/club/{}/leave", club_id), &athlete, json!({}))
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body, "Successfully left club");
+
+    let res = app
+        .get(&format!("/user/{}/club", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.status, 404);
+}
+
+#[actix_web::test]
+async fn leaving_without_a_club_fails() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+
+    let res = app.post("/club/1/leave", &athlete, json!({})).await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "No club found for user");
+}
+
+#[actix_web::test]
+async fn owner_cannot_leave_their_club() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+
+    let res = app
+        .post(&format!("/club/{}/leave", club_id), &coach, json!({}))
+        .await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "User cannot leave the club if they are the owner");
+}
+
+#[actix_web::test]
+async fn owner_transfers_the_club_to_another_coach() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (new_owner_id, new_owner) = app.coach("new@example.com").await;
+    let club_id = app.create_club(&owner, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &new_owner, json!({}))
+        .await;
+
+    let res = app
+        .put(
+            "/club/transfer",
+            &owner,
+            json!({ "new_owner_id": new_owner_id }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body, "Club owner updated successfully");
+
+    let res = app.get(&format!("/club/{}", club_id), &owner).await;
+    assert_eq!(field(&res.body, "owner_id"), new_owner_id.to_string());
+
+    // The old owner is now a regular member and can leave
+    let res = app
+        .post(&format!("/club/{}/leave", club_id), &owner, json!({}))
+        .await;
+    assert_eq!(res.status, 200);
+}
+
+#[actix_web::test]
+async fn transfer_to_a_missing_user_fails() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    app.create_club(&owner, "Bouncers").await;
+
+    let res = app
+        .put("/club/transfer", &owner, json!({ "new_owner_id": 999 }))
+        .await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "User not found");
+}
+
+#[actix_web::test]
+async fn transfer_to_an_athlete_fails() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&owner, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app
+        .put(
+            "/club/transfer",
+            &owner,
+            json!({ "new_owner_id": athlete_id }),
+        )
+        .await;
+
+    assert_eq!(res.status, 422);
+    assert_eq!(res.body, "New owner must be a coach");
+}
+
+#[actix_web::test]
+async fn only_the_owner_can_transfer() {
+    let app = spawn_app().await;
+    let (owner_id, owner) = app.coach("owner@example.com").await;
+    let (_, member) = app.coach("member@example.com").await;
+    let club_id = app.create_club(&owner, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &member, json!({}))
+        .await;
+
+    let res = app
+        .put(
+            "/club/transfer",
+            &member,
+            json!({ "new_owner_id": owner_id }),
+        )
+        .await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "User is not the owner of this club");
+}
+
+#[actix_web::test]
+async fn transfer_without_a_club_fails() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (other_id, _) = app.coach("other@example.com").await;
+
+    let res = app
+        .put(
+            "/club/transfer",
+            &coach,
+            json!({ "new_owner_id": other_id }),
+        )
+        .await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "No club found for user");
+}
+
+#[actix_web::test]
+async fn transfer_to_a_coach_without_a_club_fails() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (other_id, _) = app.coach("other@example.com").await;
+    app.create_club(&owner, "Bouncers").await;
+
+    let res = app
+        .put(
+            "/club/transfer",
+            &owner,
+            json!({ "new_owner_id": other_id }),
+        )
+        .await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "No club found for user");
+}
+
+#[actix_web::test]
+async fn transfer_to_a_coach_in_another_club_fails() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (other_id, other) = app.coach("other@example.com").await;
+    app.create_club(&owner, "Bouncers").await;
+    app.create_club(&other, "Flippers").await;
+
+    let res = app
+        .put(
+            "/club/transfer",
+            &owner,
+            json!({ "new_owner_id": other_id }),
+        )
+        .await;
+
+    assert_eq!(res.status, 403);
+    assert_eq!(res.body, "The new owner is not a member of the club");
+}
+
+#[actix_web::test]
+async fn owner_deletes_their_club() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&owner, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app.delete("/club/delete", &owner).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body, "Club deleted successfully");
+
+    // Memberships go with the club
+    let res = app.get(&format!("/club/{}", club_id), &owner).await;
+    assert_eq!(res.status, 404);
+    let res = app
+        .get(&format!("/user/{}/club", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.status, 404);
+}
+
+#[actix_web::test]
+async fn only_the_owner_can_delete() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&owner, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app.delete("/club/delete", &athlete).await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "User is not the owner of this club");
+}
+
+#[actix_web::test]
+async fn delete_without_a_club_fails() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+
+    let res = app.delete("/club/delete", &coach).await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "No club found for user");
+}
+
+#[actix_web::test]
+async fn owner_can_turn_off_required_two_factor() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    app.create_club(&owner, "Bouncers").await;
+
+    let res = app
+        .put(
+            "/club/require-two-factor",
+            &owner,
+            json!({ "required": false }),
+        )
+        .await;
+
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "require_two_factor"), "false");
+}
+
+#[actix_web::test]
+async fn requiring_two_factor_needs_it_on_the_owner_first() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    app.create_club(&owner, "Bouncers").await;
+
+    let res = app
+        .put(
+            "/club/require-two-factor",
+            &owner,
+            json!({ "required": true }),
+        )
+        .await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(
+        res.body,
+        "Enable two-factor authentication on your own account first"
+    );
+}
+
+#[actix_web::test]
+async fn only_the_owner_can_require_two_factor() {
+    let app = spawn_app().await;
+    let (_, owner) = app.coach("owner@example.com").await;
+    let (_, member) = app.coach("member@example.com").await;
+    let club_id = app.create_club(&owner, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &member, json!({}))
+        .await;
+
+    let res = app
+        .put(
+            "/club/require-two-factor",
+            &member,
+            json!({ "required": false }),
+        )
+        .await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "User is not the owner of this club");
+}
@@ -0,0 +1,344 @@
+mod support;
+
+use serde_json::json;
+use support::{field, spawn_app};
+
+#[actix_web::test]
+async fn coaches_and_athletes_discuss_a_turn() {
+    let app = spawn_app().await;
+    let (coach_id, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let turn_id = app.log_turn(&athlete, session_id, 2).await;
+
+    let res = app
+        .post(
+            "/comment/create",
+            &coach,
+            json!({ "turn_id": turn_id, "body": "Great height" }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "session_id"), session_id.to_string());
+    assert_eq!(field(&res.body, "turn_id"), turn_id.to_string());
+    assert_eq!(field(&res.body, "user_id"), coach_id.to_string());
+    let comment_id = field(&res.body, "comment_id");
+
+    // A reply stays on the same turn
+    let res = app
+        .post(
+            "/comment/create",
+            &athlete,
+            json!({ "parent_comment_id": comment_id.parse::<i32>().unwrap(), "body": "Thanks!" }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "turn_id"), turn_id.to_string());
+    assert_eq!(field(&res.body, "parent_comment_id"), comment_id);
+
+    // Or on one skill within it
+    let res = app.get(&format!("/turn/{}", turn_id), &athlete).await;
+    let skill_id: i32 = field(&res.body, "skill_id").parse().unwrap();
+    let res = app
+        .post(
+            "/comment/create",
+            &coach,
+            json!({ "skill_id": skill_id, "body": "Tighter tuck" }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "skill_id"), skill_id.to_string());
+
+    let res = app
+        .get(&format!("/comment/session/{}", session_id), &athlete)
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body.matches("'comment_id'").count(), 3);
+    // The coach's comments are new to the athlete, their own reply is not
+    assert_eq!(res.body.matches("'unread': true").count(), 2);
+    assert!(res.body.contains(&format!(
+        "'user_id': {}, 'body': Thanks!, 'created_at'",
+        athlete_id
+    )));
+}
+
+#[actix_web::test]
+async fn sessions_show_unread_comments_until_read() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    for body in ["Warm up properly", "Good session"] {
+        app.post(
+            "/comment/create",
+            &coach,
+            json!({ "session_id": session_id, "body": body }),
+        )
+        .await;
+    }
+
+    let res = app.get("/comment/unread", &athlete).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(
+        res.body,
+        format!(
+            "[ {{ 'session_id': {}, 'athlete_id': {}, 'unread': 2 }} ]",
+            session_id, athlete_id
+        )
+    );
+    // Coaches see unread comments across their club
+    let res = app.get("/comment/unread", &coach).await;
+    assert_eq!(res.body, "[  ]");
+
+    let res = app
+        .post(
+            &format!("/comment/session/{}/read", session_id),
+            &athlete,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    let res = app.get("/comment/unread", &athlete).await;
+    assert_eq!(res.body, "[  ]");
+    let res = app
+        .get(&format!("/comment/session/{}", session_id), &athlete)
+        .await;
+    assert!(!res.body.contains("'unread': true"));
+
+    app.post(
+        "/comment/create",
+        &athlete,
+        json!({ "session_id": session_id, "body": "Will do" }),
+    )
+    .await;
+    let res = app.get("/comment/unread", &coach).await;
+    assert_eq!(field(&res.body, "unread"), "1");
+    app.post(
+        "/comment/create",
+        &coach,
+        json!({ "session_id": session_id, "body": "See you Thursday" }),
+    )
+    .await;
+    let res = app.get("/comment/unread", &athlete).await;
+    assert_eq!(field(&res.body, "unread"), "1");
+}
+
+// The partner in a synchronized turn and their coach can discuss that turn, but not the rest of
+// the session it was logged in
+#[actix_web::test]
+async fn sync_partners_and_their_coaches_discuss_a_synchronized_turn() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+    let (_, partner_coach) = app.coach("partnercoach@example.com").await;
+    let club_id = app.create_club(&partner_coach, "Partners").await;
+    app.post(&format!("/club/{}/join", club_id), &partner, json!({}))
+        .await;
+
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let solo_turn_id = app.log_turn(&athlete, session_id, 1).await;
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "sync_pair_id": sync_pair_id,
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" }],
+            }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    let sync_turn_id: i32 = field(&res.body, "turn_id").parse().unwrap();
+    let skill_id: i32 = field(&res.body, "skill_id").parse().unwrap();
+    app.post(
+        "/comment/create",
+        &athlete,
+        json!({ "session_id": session_id, "body": "Tired today" }),
+    )
+    .await;
+
+    for (token, target) in [
+        (&partner, json!({ "turn_id": sync_turn_id })),
+        (&partner_coach, json!({ "skill_id": skill_id })),
+    ] {
+        let mut comment = target;
+        comment["body"] = json!("In time");
+        let res = app.post("/comment/create", token, comment).await;
+        assert_eq!(res.status, 201, "{}", res.body);
+    }
+
+    // The athlete sees the whole thread
+    let res = app
+        .get(&format!("/comment/session/{}", session_id), &athlete)
+        .await;
+    assert_eq!(res.body.matches("'comment_id'").count(), 3);
+
+    // The partner's side only sees the synchronized turn's comments
+    for token in [&partner, &partner_coach] {
+        let res = app
+            .get(&format!("/comment/session/{}", session_id), token)
+            .await;
+        assert_eq!(res.status, 200);
+        assert_eq!(res.body.matches("'comment_id'").count(), 2);
+        assert!(!res.body.contains("Tired today"));
+    }
+    let res = app.get("/comment/unread", &partner).await;
+    assert_eq!(field(&res.body, "unread"), "1");
+    let res = app
+        .post(
+            &format!("/comment/session/{}/read", session_id),
+            &partner,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    let res = app.get("/comment/unread", &partner).await;
+    assert_eq!(res.body, "[  ]");
+
+    for target in [
+        json!({ "session_id": session_id }),
+        json!({ "turn_id": solo_turn_id }),
+    ] {
+        let mut comment = target;
+        comment["body"] = json!("Hello");
+        let res = app.post("/comment/create", &partner, comment).await;
+        assert_eq!(res.status, 401);
+    }
+}
+
+#[actix_web::test]
+async fn only_the_author_can_edit_or_delete_a_comment() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let res = app
+        .post(
+            "/comment/create",
+            &coach,
+            json!({ "session_id": session_id, "body": "Nice" }),
+        )
+        .await;
+    let comment_id = field(&res.body, "comment_id");
+
+    let res = app
+        .put(
+            &format!("/comment/{}", comment_id),
+            &athlete,
+            json!({ "body": "Changed" }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .put(
+            &format!("/comment/{}", comment_id),
+            &coach,
+            json!({ "body": "Very nice" }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "body"), "Very nice");
+    assert_ne!(field(&res.body, "edited_at"), "null");
+
+    let res = app
+        .delete(&format!("/comment/{}", comment_id), &athlete)
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .delete(&format!("/comment/{}", comment_id), &coach)
+        .await;
+    assert_eq!(res.status, 200);
+
+    // The deleted comment keeps its place in the thread without its text
+    let res = app
+        .get(&format!("/comment/session/{}", session_id), &athlete)
+        .await;
+    assert_eq!(field(&res.body, "body"), "");
+    assert_eq!(field(&res.body, "deleted"), "true");
+    assert_eq!(field(&res.body, "unread"), "false");
+
+    let res = app
+        .post(
+            "/comment/create",
+            &athlete,
+            json!({ "parent_comment_id": comment_id.parse::<i32>().unwrap(), "body": "?" }),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+    let res = app
+        .put(
+            &format!("/comment/{}", comment_id),
+            &coach,
+            json!({ "body": "Back" }),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+}
+
+#[actix_web::test]
+async fn comments_need_a_target_and_club_access() {
+    let app = spawn_app().await;
+    let (_, other_coach) = app.coach("othercoach@example.com").await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let turn_id = app.log_turn(&athlete, session_id, 1).await;
+
+    let res = app
+        .post(
+            "/comment/create",
+            &other_coach,
+            json!({ "session_id": session_id, "body": "Hello" }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .get(&format!("/comment/session/{}", session_id), &other_coach)
+        .await;
+    assert_eq!(res.status, 401);
+
+    for invalid in [
+        json!({ "body": "Nothing to comment on" }),
+        json!({ "session_id": session_id, "turn_id": turn_id, "body": "Both" }),
+        json!({ "session_id": session_id, "body": "   " }),
+        json!({ "session_id": session_id, "body": "a".repeat(2001) }),
+    ] {
+        let res = app.post("/comment/create", &athlete, invalid).await;
+        assert_eq!(res.status, 422);
+    }
+
+    let res = app
+        .post(
+            "/comment/create",
+            &athlete,
+            json!({ "turn_id": 999, "body": "Missing" }),
+        )
+        .await;
+    assert_eq!(res.status, 404);
+
+    // Deleting a turn takes its comments with it
+    app.post(
+        "/comment/create",
+        &athlete,
+        json!({ "turn_id": turn_id, "body": "Felt good" }),
+    )
+    .await;
+    let res = app.delete(&format!("/turn/{}", turn_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .get(&format!("/comment/session/{}", session_id), &athlete)
+        .await;
+    assert_eq!(res.body, "[  ]");
+}
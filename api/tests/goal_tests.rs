@@ -0,0 +1,280 @@
+mod support;
+
+use serde_json::{json, Value};
+use support::{field, spawn_app};
+
+fn difficulty_goal(athlete_id: i32, target: f32) -> Value {
+    json!({
+        "athlete_id": athlete_id,
+        "kind": "DIFFICULTY",
+        "event": "TRA",
+        "target": target,
+        "deadline": "2099-12-31",
+    })
+}
+
+// Landing a tucked somersault with at most 0.2 off
+fn consistent_goal(athlete_id: i32, target: u32) -> Value {
+    json!({
+        "athlete_id": athlete_id,
+        "kind": "CONSISTENT_SKILL",
+        "event": "TRA",
+        "target": target,
+        "fig_rep": 40,
+        "position": "TUCK",
+        "deadline": "2099-12-31",
+    })
+}
+
+#[actix_web::test]
+async fn logged_turns_complete_a_difficulty_goal() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+
+    let res = app
+        .post("/goal/create", &athlete, difficulty_goal(athlete_id, 2.0))
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "set_by"), athlete_id.to_string());
+    assert!(res.body.contains(
+        "'kind': DIFFICULTY, 'event_id': TRA, 'target': 2, 'fig_rep': null, 'position': null, 'name': null, 'deadline': 2099-12-31"
+    ));
+    assert!(res
+        .body
+        .ends_with("'completed_at': null, 'current': 0, 'progress': 0, 'status': ACTIVE }"));
+    let goal_id = field(&res.body, "goal_id");
+
+    // Training in another event doesn't count
+    let dmt_session_id = app.start_session(&athlete, "DMT").await;
+    app.log_turn(&athlete, dmt_session_id, 5).await;
+    app.post(
+        &format!("/session/{}/end", dmt_session_id),
+        &athlete,
+        json!({}),
+    )
+    .await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    app.log_turn(&athlete, session_id, 3).await;
+
+    let res = app.get(&format!("/goal/{}", goal_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    assert!(res
+        .body
+        .ends_with("'current': 1.5, 'progress': 75, 'status': ACTIVE }"));
+
+    let turn_id = app.log_turn(&athlete, session_id, 4).await;
+    let res = app.get(&format!("/goal/{}", goal_id), &athlete).await;
+    assert!(res
+        .body
+        .ends_with("'current': 2, 'progress': 100, 'status': COMPLETED }"));
+    assert_ne!(field(&res.body, "completed_at"), "null");
+
+    // Completion sticks once reached
+    let res = app.delete(&format!("/turn/{}", turn_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    let res = app.get(&format!("/goal/{}", goal_id), &athlete).await;
+    assert!(res
+        .body
+        .ends_with("'current': 1.5, 'progress': 100, 'status': COMPLETED }"));
+}
+
+#[actix_web::test]
+async fn coaches_set_consistency_goals_that_judging_completes() {
+    let app = spawn_app().await;
+    let (coach_id, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app
+        .post("/goal/create", &coach, consistent_goal(athlete_id, 3))
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "athlete_id"), athlete_id.to_string());
+    assert_eq!(field(&res.body, "set_by"), coach_id.to_string());
+    assert!(res.body.contains(
+        "'kind': CONSISTENT_SKILL, 'event_id': TRA, 'target': 3, 'fig_rep': 40, 'position': TUCK, 'name': Somersault,"
+    ));
+    let goal_id = field(&res.body, "goal_id");
+    app.post("/goal/create", &athlete, difficulty_goal(athlete_id, 14.0))
+        .await;
+
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "skills": [
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.3 },
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.1 },
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.4 },
+                ],
+            }),
+        )
+        .await;
+    let turn_id = field(&res.body, "turn_id");
+
+    let res = app.get(&format!("/goal/{}", goal_id), &coach).await;
+    assert!(res
+        .body
+        .ends_with("'current': 1, 'progress': 33, 'status': ACTIVE }"));
+
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &coach,
+            json!({ "deductions": [0.2, 0.1, 0.0], "landing_deduction": 0.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+
+    let res = app
+        .get(
+            &format!("/goal/athlete/{}?status=COMPLETED", athlete_id),
+            &athlete,
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res
+        .body
+        .starts_with(&format!("[ {{ 'goal_id': {},", goal_id)));
+    assert!(res
+        .body
+        .ends_with("'current': 3, 'progress': 100, 'status': COMPLETED } ]"));
+
+    let res = app
+        .get(
+            &format!("/goal/athlete/{}?status=ACTIVE", athlete_id),
+            &coach,
+        )
+        .await;
+    assert_eq!(res.body.matches("'goal_id'").count(), 1);
+    assert_eq!(field(&res.body, "kind"), "DIFFICULTY");
+    assert!(res.body.contains("'target': 14, "));
+
+    let res = app
+        .get(&format!("/goal/athlete/{}?status=DONE", athlete_id), &coach)
+        .await;
+    assert_eq!(res.status, 422);
+}
+
+#[actix_web::test]
+async fn unjudged_attempts_do_not_count_as_consistent() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let res = app
+        .post("/goal/create", &athlete, consistent_goal(athlete_id, 3))
+        .await;
+    let goal_id = field(&res.body, "goal_id");
+
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let turn_id = app.log_turn(&athlete, session_id, 5).await;
+    let res = app.get(&format!("/goal/{}", goal_id), &athlete).await;
+    assert!(res
+        .body
+        .ends_with("'current': 0, 'progress': 0, 'status': ACTIVE }"));
+
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &athlete,
+            json!({ "deductions": [0.1, 0.3, 0.2, 0.4, 0.3], "landing_deduction": 0.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    let res = app.get(&format!("/goal/{}", goal_id), &athlete).await;
+    assert!(res
+        .body
+        .ends_with("'current': 2, 'progress': 66, 'status': ACTIVE }"));
+}
+
+#[actix_web::test]
+async fn only_the_athlete_and_their_coaches_manage_goals() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (coach_id, other_coach) = app.coach("othercoach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (_, outsider) = app.athlete("outsider@example.com").await;
+    app.create_club(&coach, "Bouncers").await;
+
+    let res = app
+        .post(
+            "/goal/create",
+            &other_coach,
+            difficulty_goal(athlete_id, 2.0),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    // Coaches don't train, so they can't have goals of their own
+    let res = app
+        .post("/goal/create", &other_coach, difficulty_goal(coach_id, 2.0))
+        .await;
+    assert_eq!(res.status, 422);
+
+    let res = app
+        .post("/goal/create", &athlete, difficulty_goal(athlete_id, 2.0))
+        .await;
+    let goal_id = field(&res.body, "goal_id");
+
+    let res = app.get(&format!("/goal/{}", goal_id), &outsider).await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .get(&format!("/goal/athlete/{}", athlete_id), &outsider)
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app.delete(&format!("/goal/{}", goal_id), &outsider).await;
+    assert_eq!(res.status, 401);
+
+    let res = app.delete(&format!("/goal/{}", goal_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    let res = app.get(&format!("/goal/{}", goal_id), &athlete).await;
+    assert_eq!(res.status, 404);
+    let res = app
+        .get(&format!("/goal/athlete/{}", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.body, "[  ]");
+}
+
+#[actix_web::test]
+async fn goals_need_a_valid_target_and_deadline() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+
+    let with = |mut goal: Value, key: &str, value: Value| {
+        goal[key] = value;
+        goal
+    };
+
+    for invalid in [
+        with(difficulty_goal(athlete_id, 2.0), "kind", json!("HEIGHT")),
+        with(difficulty_goal(athlete_id, 2.0), "event", json!("BEAM")),
+        with(difficulty_goal(athlete_id, 2.0), "event", json!("TUM")),
+        with(
+            difficulty_goal(athlete_id, 2.0),
+            "deadline",
+            json!("31/12/2099"),
+        ),
+        with(
+            difficulty_goal(athlete_id, 2.0),
+            "deadline",
+            json!("2020-01-01"),
+        ),
+        with(difficulty_goal(athlete_id, 2.0), "fig_rep", json!(40)),
+        difficulty_goal(athlete_id, 0.0),
+        difficulty_goal(athlete_id, 2.05),
+        difficulty_goal(athlete_id, 51.0),
+        consistent_goal(athlete_id, 0),
+        consistent_goal(athlete_id, 1001),
+        with(consistent_goal(athlete_id, 10), "target", json!(2.5)),
+        with(consistent_goal(athlete_id, 10), "fig_rep", json!(4)),
+        with(consistent_goal(athlete_id, 10), "position", json!("LAYOUT")),
+        with(consistent_goal(athlete_id, 10), "position", Value::Null),
+    ] {
+        let res = app.post("/goal/create", &athlete, invalid).await;
+        assert_eq!(res.status, 422, "{}", res.body);
+    }
+}
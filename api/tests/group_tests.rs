@@ -0,0 +1,192 @@
+mod support;
+
+use serde_json::json;
+use support::{field, spawn_app};
+
+#[actix_web::test]
+async fn plans_are_assigned_to_a_groups_athletes() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (first_id, first) = app.athlete("first@example.com").await;
+    let (second_id, second) = app.athlete("second@example.com").await;
+    let (third_id, third) = app.athlete("third@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    for token in [&first, &second, &third] {
+        app.post(&format!("/club/{}/join", club_id), token, json!({}))
+            .await;
+    }
+
+    let res = app
+        .post(
+            "/group/create",
+            &coach,
+            json!({ "name": "Juniors", "athlete_ids": [second_id, first_id, first_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(
+        res.body,
+        format!(
+            "{{ 'group_id': {}, 'club_id': {}, 'name': Juniors, 'athlete_ids': [ {}, {} ] }}",
+            field(&res.body, "group_id"),
+            club_id,
+            first_id,
+            second_id
+        )
+    );
+    let group_id: i32 = field(&res.body, "group_id").parse().unwrap();
+
+    // Membership is replaced as a whole
+    let res = app
+        .put(
+            &format!("/group/{}/members", group_id),
+            &coach,
+            json!({ "athlete_ids": [first_id, third_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res
+        .body
+        .ends_with(&format!("'athlete_ids': [ {}, {} ] }}", first_id, third_id)));
+
+    // Athletes who leave the club drop out of its groups
+    app.post(&format!("/club/{}/leave", club_id), &third, json!({}))
+        .await;
+    let res = app.get(&format!("/group/club/{}", club_id), &coach).await;
+    assert_eq!(res.status, 200);
+    assert!(res
+        .body
+        .ends_with(&format!("'athlete_ids': [ {} ] }} ]", first_id)));
+
+    let res = app.post("/plan/create", &coach, json!({
+        "event": "TRA",
+        "scheduled_for": "2025-04-10",
+        "name": "Basics",
+        "items": [{ "kind": "SKILL", "skills": [{ "fig_rep": 40, "position": "TUCK" }], "repetitions": 3 }],
+    })).await;
+    let plan_id = field(&res.body, "plan_id");
+    let res = app
+        .post(
+            &format!("/plan/{}/assign", plan_id),
+            &coach,
+            json!({ "athlete_ids": [second_id], "group_ids": [group_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body.matches("'athlete_id'").count(), 2);
+    assert!(res
+        .body
+        .contains(&format!("{{ 'athlete_id': {}, 'sessions'", first_id)));
+    assert!(res
+        .body
+        .contains(&format!("{{ 'athlete_id': {}, 'sessions'", second_id)));
+
+    // Deleting the group keeps the plans assigned through it
+    let res = app.delete(&format!("/group/{}", group_id), &coach).await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .get(&format!("/plan/athlete/{}", first_id), &first)
+        .await;
+    assert_eq!(field(&res.body, "plan_id"), plan_id);
+    let res = app
+        .post(
+            &format!("/plan/{}/assign", plan_id),
+            &coach,
+            json!({ "athlete_ids": [], "group_ids": [group_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 404);
+}
+
+#[actix_web::test]
+async fn only_the_clubs_coaches_manage_its_groups() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, other_coach) = app.coach("othercoach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (outsider_id, _) = app.athlete("outsider@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    let other_club_id = app.create_club(&other_coach, "Flyers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app
+        .post(
+            "/group/create",
+            &athlete,
+            json!({ "name": "Mine", "athlete_ids": [] }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+
+    for (invalid, status) in [
+        (json!({ "name": " ", "athlete_ids": [] }), 422),
+        (
+            json!({ "name": "Seniors", "athlete_ids": [outsider_id] }),
+            422,
+        ),
+    ] {
+        let res = app.post("/group/create", &coach, invalid).await;
+        assert_eq!(res.status, status, "{}", res.body);
+    }
+
+    let res = app
+        .post(
+            "/group/create",
+            &coach,
+            json!({ "name": "Seniors", "athlete_ids": [athlete_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    let group_id: i32 = field(&res.body, "group_id").parse().unwrap();
+    let res = app
+        .post(
+            "/group/create",
+            &coach,
+            json!({ "name": "Seniors", "athlete_ids": [] }),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+
+    // Another club's coach can't see, change or use the group
+    let res = app
+        .get(&format!("/group/club/{}", club_id), &other_coach)
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .put(
+            &format!("/group/{}/members", group_id),
+            &other_coach,
+            json!({ "athlete_ids": [] }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .delete(&format!("/group/{}", group_id), &other_coach)
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .post("/plan/create", &other_coach, json!({
+            "event": "TRA",
+            "scheduled_for": "2025-04-10",
+            "name": "Basics",
+            "items": [{ "kind": "SKILL", "skills": [{ "fig_rep": 40, "position": "TUCK" }], "repetitions": 1 }],
+        }))
+        .await;
+    let plan_id = field(&res.body, "plan_id");
+    let res = app
+        .post(
+            &format!("/plan/{}/assign", plan_id),
+            &other_coach,
+            json!({ "athlete_ids": [], "group_ids": [group_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 422);
+
+    let res = app
+        .get(&format!("/group/club/{}", other_club_id), &other_coach)
+        .await;
+    assert_eq!(res.body, "[  ]");
+    let res = app.get("/group/club/999", &coach).await;
+    assert_eq!(res.status, 404);
+}
@@ -0,0 +1,283 @@
+mod support;
+
+use serde_json::{json, Value};
+use support::{field, spawn_app};
+
+// Two tucked somersaults, then a turn of exactly a tucked somersault and a straight barani
+fn plan() -> Value {
+    json!({
+        "event": "TRA",
+        "scheduled_for": "2025-04-10",
+        "name": "Basics",
+        "items": [
+            { "kind": "SKILL", "skills": [{ "fig_rep": 40, "position": "TUCK" }], "repetitions": 3 },
+            {
+                "kind": "TURN",
+                "skills": [
+                    { "fig_rep": 40, "position": "TUCK" },
+                    { "fig_rep": 41, "position": "STRAIGHT" },
+                ],
+                "repetitions": 1,
+            },
+        ],
+    })
+}
+
+// Starts a session against the plan
+fn plan_session(event: &str, plan_id: &str) -> Value {
+    json!({ "event": event, "summary": "Plan", "plan_id": plan_id.parse::<i32>().unwrap() })
+}
+
+#[actix_web::test]
+async fn logged_turns_complete_an_assigned_plan() {
+    let app = spawn_app().await;
+    let (coach_id, coach) = app.coach("coach@example.com").await;
+    let (first_id, first) = app.athlete("first@example.com").await;
+    let (second_id, second) = app.athlete("second@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    for token in [&first, &second] {
+        app.post(&format!("/club/{}/join", club_id), token, json!({}))
+            .await;
+    }
+
+    let res = app.post("/plan/create", &coach, plan()).await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "club_id"), club_id.to_string());
+    assert_eq!(field(&res.body, "coach_id"), coach_id.to_string());
+    assert!(res.body.contains(
+        "'items': [ { 'item_num': 1, 'kind': SKILL, 'skills': [ { 'fig_rep': 40, 'position': TUCK } ], 'repetitions': 3, 'difficulty': 0.5 }"
+    ));
+    assert!(res.body.contains("'kind': TURN, 'skills': [ { 'fig_rep': 40, 'position': TUCK }, { 'fig_rep': 41, 'position': STRAIGHT } ], 'repetitions': 1, 'difficulty': 1.1 }"));
+    assert!(res.body.ends_with("'athletes': [  ] }"));
+    let plan_id = field(&res.body, "plan_id");
+
+    // Assigning the whole club reaches every athlete in it
+    let res = app
+        .post(
+            &format!("/plan/{}/assign", plan_id),
+            &coach,
+            json!({ "athlete_ids": [], "whole_club": true }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res.body.ends_with(&format!(
+        "'athletes': [ {{ 'athlete_id': {}, 'sessions': 0, 'done': [ 0, 0 ], 'progress': 0, 'completed': false }}, {{ 'athlete_id': {}, 'sessions': 0, 'done': [ 0, 0 ], 'progress': 0, 'completed': false }} ] }}",
+        first_id, second_id
+    )));
+
+    let res = app
+        .post("/session/start", &first, plan_session("TRA", &plan_id))
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "plan_id"), plan_id);
+    let session_id: i32 = field(&res.body, "session_id").parse().unwrap();
+
+    let res = app
+        .post(
+            "/turn/create",
+            &first,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "skills": [
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" },
+                    { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT" },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+
+    let res = app.get(&format!("/plan/{}", plan_id), &first).await;
+    assert_eq!(res.status, 200);
+    // Athletes only see their own progress
+    assert!(res.body.ends_with(&format!(
+        "'athletes': [ {{ 'athlete_id': {}, 'sessions': 1, 'done': [ 1, 1 ], 'progress': 50, 'completed': false }} ] }}",
+        first_id
+    )));
+
+    app.log_turn(&first, session_id, 2).await;
+    // Training outside the plan doesn't count towards it
+    let other_session_id = app.start_session(&second, "TRA").await;
+    app.log_turn(&second, other_session_id, 3).await;
+
+    let res = app.get(&format!("/plan/{}", plan_id), &coach).await;
+    assert!(res.body.ends_with(&format!(
+        "'athletes': [ {{ 'athlete_id': {}, 'sessions': 1, 'done': [ 3, 1 ], 'progress': 100, 'completed': true }}, {{ 'athlete_id': {}, 'sessions': 0, 'done': [ 0, 0 ], 'progress': 0, 'completed': false }} ] }}",
+        first_id, second_id
+    )));
+
+    let res = app
+        .get(&format!("/plan/athlete/{}", first_id), &coach)
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res
+        .body
+        .starts_with(&format!("[ {{ 'plan_id': {},", plan_id)));
+    assert_eq!(field(&res.body, "completed"), "true");
+
+    let res = app.get(&format!("/plan/club/{}", club_id), &coach).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body.matches("'plan_id'").count(), 1);
+    assert_eq!(res.body.matches("'athlete_id'").count(), 2);
+}
+
+#[actix_web::test]
+async fn only_the_clubs_coaches_manage_its_plans() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, other_coach) = app.coach("othercoach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (outsider_id, outsider) = app.athlete("outsider@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.create_club(&other_coach, "Rebounders").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app.post("/plan/create", &athlete, plan()).await;
+    assert_eq!(res.status, 401);
+    let res = app.post("/plan/create", &coach, plan()).await;
+    let plan_id = field(&res.body, "plan_id");
+
+    let res = app
+        .post(
+            &format!("/plan/{}/assign", plan_id),
+            &other_coach,
+            json!({ "athlete_ids": [athlete_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app.get(&format!("/plan/{}", plan_id), &other_coach).await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .get(&format!("/plan/club/{}", club_id), &other_coach)
+        .await;
+    assert_eq!(res.status, 401);
+
+    for invalid in [
+        json!({ "athlete_ids": [] }),
+        json!({ "athlete_ids": [outsider_id] }),
+    ] {
+        let res = app
+            .post(&format!("/plan/{}/assign", plan_id), &coach, invalid)
+            .await;
+        assert_eq!(res.status, 422);
+    }
+
+    // Athletes can only train against plans assigned to them, in the plan's event
+    assert_eq!(
+        app.post("/session/start", &athlete, plan_session("TRA", &plan_id))
+            .await
+            .status,
+        401
+    );
+    assert_eq!(
+        app.post("/session/start", &outsider, plan_session("TRA", &plan_id))
+            .await
+            .status,
+        401
+    );
+    let res = app
+        .post(
+            &format!("/plan/{}/assign", plan_id),
+            &coach,
+            json!({ "athlete_ids": [athlete_id] }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(
+        app.post("/session/start", &athlete, plan_session("DMT", &plan_id))
+            .await
+            .status,
+        422
+    );
+    assert_eq!(
+        app.post("/session/start", &athlete, plan_session("TRA", "999"))
+            .await
+            .status,
+        404
+    );
+
+    let res = app.get(&format!("/plan/{}", plan_id), &outsider).await;
+    assert_eq!(res.status, 401);
+    let res = app.get(&format!("/plan/{}", plan_id), &athlete).await;
+    assert_eq!(res.status, 200);
+}
+
+#[actix_web::test]
+async fn plans_need_valid_items() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    app.create_club(&coach, "Bouncers").await;
+
+    let item = |kind: &str, skills: Value, repetitions: u32| {
+        let mut plan = plan();
+        plan["items"] = json!([{ "kind": kind, "skills": skills, "repetitions": repetitions }]);
+        plan
+    };
+    let tuck = json!([{ "fig_rep": 40, "position": "TUCK" }]);
+    let mut no_items = plan();
+    no_items["items"] = json!([]);
+    let mut bad_date = plan();
+    bad_date["scheduled_for"] = json!("10/04/2025");
+    let mut no_name = plan();
+    no_name["name"] = json!(" ");
+
+    for invalid in [
+        no_items,
+        bad_date,
+        no_name,
+        item("ROUTINE", tuck.clone(), 1),
+        item("SKILL", json!([]), 1),
+        item(
+            "SKILL",
+            json!([{ "fig_rep": 40, "position": "TUCK" }, { "fig_rep": 41, "position": "PIKE" }]),
+            1,
+        ),
+        item("TURN", tuck.clone(), 0),
+        item("TURN", tuck.clone(), 101),
+        item("TURN", json!([{ "fig_rep": 4, "position": "TUCK" }]), 1),
+        item("TURN", json!([{ "fig_rep": 40, "position": "LAYOUT" }]), 1),
+    ] {
+        let res = app.post("/plan/create", &coach, invalid).await;
+        assert_eq!(res.status, 422, "{}", res.body);
+    }
+}
+
+#[actix_web::test]
+async fn deleting_a_plan_keeps_its_sessions() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+
+    let res = app.post("/plan/create", &coach, plan()).await;
+    let plan_id = field(&res.body, "plan_id");
+    app.post(
+        &format!("/plan/{}/assign", plan_id),
+        &coach,
+        json!({ "athlete_ids": [athlete_id] }),
+    )
+    .await;
+    let res = app
+        .post("/session/start", &athlete, plan_session("TRA", &plan_id))
+        .await;
+    let session_id = field(&res.body, "session_id");
+
+    let res = app.delete(&format!("/plan/{}", plan_id), &athlete).await;
+    assert_eq!(res.status, 401);
+    let res = app.delete(&format!("/plan/{}", plan_id), &coach).await;
+    assert_eq!(res.status, 200);
+
+    let res = app.get(&format!("/plan/{}", plan_id), &coach).await;
+    assert_eq!(res.status, 404);
+    let res = app
+        .get(&format!("/plan/athlete/{}", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.body, "[  ]");
+    let res = app.get(&format!("/session/{}", session_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "plan_id"), "null");
+}
@@ -0,0 +1,69 @@
+mod support;
+
+use std::collections::HashMap;
+
+use actix_web::http::{header::RETRY_AFTER, Method};
+use api::utils::{
+    config::RateLimitBackend,
+    rate_limiter::{DatabaseStore, RateLimitDecision, RateLimitPolicy, RateLimitStore},
+};
+use support::spawn_rate_limited_app;
+
+// Two requests, then one more every ten seconds
+fn policies() -> HashMap<String, RateLimitPolicy> {
+    HashMap::from([(
+        "auth".to_string(),
+        RateLimitPolicy {
+            capacity: 2.0,
+            refill_per_second: 0.1,
+        },
+    )])
+}
+
+#[actix_web::test]
+async fn requests_past_capacity_get_429() {
+    for backend in [RateLimitBackend::Memory, RateLimitBackend::Database] {
+        let app = spawn_rate_limited_app(backend, policies()).await;
+        let login = || app.request(Method::POST, "/auth/login", None, None);
+
+        for remaining in ["1", "0"] {
+            let res = login().await;
+            assert_ne!(res.status, 429);
+            assert_eq!(res.headers.get("x-ratelimit-remaining").unwrap(), remaining);
+        }
+
+        let res = login().await;
+        assert_eq!(res.status, 429);
+        assert_eq!(res.headers.get(RETRY_AFTER).unwrap(), "10");
+        assert_eq!(res.body, "Too many requests, please slow down");
+
+        // Scopes without a policy are never throttled
+        let res = app.request(Method::GET, "/user/1", None, None).await;
+        assert_eq!(res.status, 401);
+    }
+}
+
+#[actix_web::test]
+async fn concurrent_first_requests_share_one_database_bucket() {
+    let app = spawn_rate_limited_app(RateLimitBackend::Database, HashMap::new()).await;
+    let store = DatabaseStore {
+        db: app.state.db.clone(),
+    };
+    let policy = policies()["auth"];
+
+    // None of them fail on the bucket's key, and only the capacity gets through
+    let take = || store.take("auth:ip:unknown", &policy);
+    let decisions = tokio::join!(take(), take(), take(), take(), take());
+    let allowed = [
+        decisions.0,
+        decisions.1,
+        decisions.2,
+        decisions.3,
+        decisions.4,
+    ]
+    .into_iter()
+    .map(|decision| decision.unwrap())
+    .filter(|decision| matches!(decision, RateLimitDecision::Allowed { .. }))
+    .count();
+    assert_eq!(allowed, 2);
+}
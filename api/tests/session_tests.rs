@@ -0,0 +1,255 @@
+mod support;
+
+use api::{entities, routes::services::session_service};
+use chrono::{Duration, SubsecRound, Utc};
+use sea_orm::{EntityTrait, Set};
+use serde_json::json;
+use support::{field, spawn_app};
+
+#[actix_web::test]
+async fn athlete_starts_and_ends_a_session() {
+    let app = spawn_app().await;
+    let (athlete_id, token) = app.athlete("athlete@example.com").await;
+
+    let res = app
+        .post(
+            "/session/start",
+            &token,
+            json!({ "event": "TRA", "summary": "Routine work" }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "user_id"), athlete_id.to_string());
+    assert_eq!(field(&res.body, "event_id"), "TRA");
+    assert_eq!(field(&res.body, "status"), "OPEN");
+    assert_eq!(field(&res.body, "time_end"), "null");
+    let session_id = field(&res.body, "session_id");
+
+    let res = app
+        .post(&format!("/session/{}/end", session_id), &token, json!({}))
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "status"), "CLOSED");
+    assert_ne!(field(&res.body, "time_end"), "null");
+}
+
+#[actix_web::test]
+async fn starting_a_session_counts_it_in_the_metrics() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+
+    app.start_session(&token, "DMT").await;
+
+    let metrics = app.state.metrics.render(&app.state.db).unwrap();
+    assert!(metrics.contains("sessions_logged_total{event=\"DMT\"} 1"));
+}
+
+#[actix_web::test]
+async fn coaches_cannot_start_sessions() {
+    let app = spawn_app().await;
+    let (_, token) = app.coach("coach@example.com").await;
+
+    let res = app
+        .post(
+            "/session/start",
+            &token,
+            json!({ "event": "TRA", "summary": "Routine work" }),
+        )
+        .await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Only athletes can start sessions");
+}
+
+#[actix_web::test]
+async fn start_session_rejects_an_unknown_event() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+
+    let res = app
+        .post(
+            "/session/start",
+            &token,
+            json!({ "event": "POMMEL", "summary": "Routine work" }),
+        )
+        .await;
+
+    assert_eq!(res.status, 422);
+    assert_eq!(res.body, "Invalid event, must be DMT, TRA or TUM");
+}
+
+#[actix_web::test]
+async fn athlete_can_only_have_one_open_session() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let res = app
+        .post(
+            "/session/start",
+            &token,
+            json!({ "event": "TUM", "summary": "Tumbling" }),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+    assert_eq!(
+        res.body,
+        "A session is already open, end it before starting another"
+    );
+
+    // Once it has ended a new one can start
+    app.post(&format!("/session/{}/end", session_id), &token, json!({}))
+        .await;
+    app.start_session(&token, "TUM").await;
+}
+
+#[actix_web::test]
+async fn open_session_index_rejects_a_second_open_session() {
+    let app = spawn_app().await;
+    let (athlete_id, token) = app.athlete("athlete@example.com").await;
+    app.start_session(&token, "TRA").await;
+
+    // Bypasses the service check, as a racing request would
+    let now = Utc::now().naive_utc();
+    let inserted = entities::session::Entity::insert(entities::session::ActiveModel {
+        user_id: Set(athlete_id),
+        event_id: Set(entities::sea_orm_active_enums::Event::Tra),
+        time_start: Set(now),
+        summary: Set("Racing".to_string()),
+        status: Set(entities::sea_orm_active_enums::SessionStatus::Open),
+        time_end: Set(None),
+        last_activity_at: Set(Some(now)),
+        ..Default::default()
+    })
+    .exec(&app.state.db)
+    .await;
+
+    assert!(inserted.is_err());
+}
+
+#[actix_web::test]
+async fn a_session_can_only_be_ended_once() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    let uri = format!("/session/{}/end", session_id);
+
+    app.post(&uri, &token, json!({})).await;
+    let res = app.post(&uri, &token, json!({})).await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "Session has already ended");
+}
+
+#[actix_web::test]
+async fn only_the_athlete_can_end_their_session() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let (_, other) = app.athlete("other@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app
+        .post(&format!("/session/{}/end", session_id), &other, json!({}))
+        .await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Only the athlete can end their session");
+}
+
+#[actix_web::test]
+async fn ending_a_missing_session_is_not_found() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+
+    let res = app.post("/session/999/end", &token, json!({})).await;
+
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "Session not found");
+}
+
+#[actix_web::test]
+async fn coach_in_the_same_club_can_view_sessions() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app.get(&format!("/session/{}", session_id), &coach).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "session_id"), session_id.to_string());
+
+    let res = app
+        .get(&format!("/session/athlete/{}", athlete_id), &coach)
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res.body.starts_with("[ {"));
+}
+
+#[actix_web::test]
+async fn sessions_are_hidden_from_other_users() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (_, other) = app.athlete("other@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    // A coach outside the athlete's club
+    let res = app.get(&format!("/session/{}", session_id), &coach).await;
+    assert_eq!(res.status, 401);
+    assert_eq!(
+        res.body,
+        "Only the athlete and their coaches can access their training"
+    );
+
+    let res = app
+        .get(&format!("/session/athlete/{}", athlete_id), &other)
+        .await;
+    assert_eq!(res.status, 401);
+}
+
+#[actix_web::test]
+async fn idle_sessions_are_closed_at_their_last_activity() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let idle_id = app.start_session(&token, "TRA").await;
+    let (_, other) = app.athlete("other@example.com").await;
+    let active_id = app.start_session(&other, "TRA").await;
+
+    // Push the first session's last activity past the idle period. Postgres keeps microseconds
+    let last_activity = (Utc::now().naive_utc() - Duration::minutes(45)).trunc_subsecs(6);
+    entities::session::Entity::update(entities::session::ActiveModel {
+        session_id: Set(idle_id),
+        last_activity_at: Set(Some(last_activity)),
+        ..Default::default()
+    })
+    .exec(&app.state.db)
+    .await
+    .unwrap();
+
+    session_service::close_idle_sessions(&app.state)
+        .await
+        .unwrap();
+
+    let idle = session_service::get_session_by_id(&app.state.db, idle_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        idle.status,
+        entities::sea_orm_active_enums::SessionStatus::AutoClosed
+    );
+    assert_eq!(idle.time_end, Some(last_activity));
+
+    let active = session_service::get_session_by_id(&app.state.db, active_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        active.status,
+        entities::sea_orm_active_enums::SessionStatus::Open
+    );
+
+    // The athlete is free to start again
+    app.start_session(&token, "TRA").await;
+}
@@ -0,0 +1,229 @@
+mod support;
+
+use api::{
+    entities,
+    utils::{
+        jwt::{decode_jwt, encode_jwt},
+        signing_keys::KeyStore,
+    },
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use jsonwebtoken::{decode_header, encode, EncodingKey, Header};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde_json::{json, Value};
+use support::{spawn_app, test_config};
+
+async fn keys(db: &DatabaseConnection) -> Vec<entities::signing_key::Model> {
+    entities::signing_key::Entity::find()
+        .order_by_asc(entities::signing_key::Column::ActivatesAt)
+        .all(db)
+        .await
+        .unwrap()
+}
+
+async fn set_times(
+    db: &DatabaseConnection,
+    kid: &str,
+    column: entities::signing_key::Column,
+    value: NaiveDateTime,
+) {
+    entities::signing_key::Entity::update_many()
+        .col_expr(column, Expr::value(value))
+        .filter(entities::signing_key::Column::Kid.eq(kid))
+        .exec(db)
+        .await
+        .unwrap();
+}
+
+fn kid(token: &str) -> String {
+    decode_header(token).unwrap().kid.unwrap()
+}
+
+#[actix_web::test]
+async fn the_first_key_is_encrypted_and_published() {
+    let app = spawn_app().await;
+
+    let stored = keys(&app.state.db).await;
+    assert_eq!(stored.len(), 1);
+    assert!(stored[0].private_key.starts_with("v1:"));
+
+    let res = app
+        .request(
+            actix_web::http::Method::GET,
+            "/.well-known/jwks.json",
+            None,
+            None,
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    let jwks: Value = serde_json::from_str(&res.body).unwrap();
+    assert_eq!(
+        jwks,
+        json!({ "keys": [{
+            "use": "sig",
+            "alg": "EdDSA",
+            "kid": stored[0].kid,
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": stored[0].public_key,
+        }] })
+    );
+
+    let token = encode_jwt(&app.state.signing_keys, "a@example.com".to_string(), 1).unwrap();
+    assert_eq!(kid(&token), stored[0].kid);
+}
+
+#[actix_web::test]
+async fn keys_are_prepublished_then_retired_then_expired() {
+    let app = spawn_app().await;
+    let db = &app.state.db;
+    let store = &app.state.signing_keys;
+    let now = Utc::now().naive_utc();
+    let first = keys(db).await.remove(0);
+
+    // Inside the prepublish window the next key is published, but doesn't sign yet
+    set_times(
+        db,
+        &first.kid,
+        entities::signing_key::Column::RetiresAt,
+        now + Duration::hours(1),
+    )
+    .await;
+    store.rotate_if_due(db).await.unwrap();
+    let stored = keys(db).await;
+    assert_eq!(stored.len(), 2);
+    let second = stored[1].clone();
+    assert_eq!(second.activates_at, stored[0].retires_at);
+    assert_eq!(store.jwks().keys.len(), 2);
+    let old_token = encode_jwt(store, "a@example.com".to_string(), 1).unwrap();
+    assert_eq!(kid(&old_token), first.kid);
+
+    // Once the first key retires, the second signs while the first still verifies
+    set_times(
+        db,
+        &first.kid,
+        entities::signing_key::Column::RetiresAt,
+        now - Duration::minutes(1),
+    )
+    .await;
+    set_times(
+        db,
+        &second.kid,
+        entities::signing_key::Column::ActivatesAt,
+        now - Duration::minutes(1),
+    )
+    .await;
+    store.rotate_if_due(db).await.unwrap();
+    assert_eq!(keys(db).await.len(), 2);
+    let new_token = encode_jwt(store, "a@example.com".to_string(), 1).unwrap();
+    assert_eq!(kid(&new_token), second.kid);
+    assert!(decode_jwt(store, old_token.clone()).is_ok());
+
+    // After the grace period the first key is gone, along with the tokens it signed
+    set_times(
+        db,
+        &first.kid,
+        entities::signing_key::Column::ExpiresAt,
+        now - Duration::minutes(1),
+    )
+    .await;
+    store.rotate_if_due(db).await.unwrap();
+    let stored = keys(db).await;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].kid, second.kid);
+    assert_eq!(store.jwks().keys.len(), 1);
+    assert!(decode_jwt(store, old_token).is_err());
+    assert!(decode_jwt(store, new_token).is_ok());
+}
+
+#[actix_web::test]
+async fn concurrent_rotations_publish_one_key() {
+    let app = spawn_app().await;
+    let db = &app.state.db;
+    // Another replica sharing the database
+    let other = KeyStore::load(db, &test_config()).await.unwrap();
+
+    let first = keys(db).await.remove(0);
+    set_times(
+        db,
+        &first.kid,
+        entities::signing_key::Column::RetiresAt,
+        Utc::now().naive_utc() + Duration::hours(1),
+    )
+    .await;
+    let (ours, theirs) = tokio::join!(
+        app.state.signing_keys.rotate_if_due(db),
+        other.rotate_if_due(db)
+    );
+    ours.unwrap();
+    theirs.unwrap();
+
+    assert_eq!(keys(db).await.len(), 2);
+}
+
+#[actix_web::test]
+async fn keys_stored_before_encryption_are_encrypted_on_rotation() {
+    let app = spawn_app().await;
+    let db = &app.state.db;
+    let first = keys(db).await.remove(0);
+    let plaintext = app
+        .state
+        .config
+        .encryption_key
+        .decrypt(&first.private_key, &format!("signing_key:{}", first.kid))
+        .unwrap();
+
+    entities::signing_key::Entity::update_many()
+        .col_expr(
+            entities::signing_key::Column::PrivateKey,
+            Expr::value(plaintext),
+        )
+        .exec(db)
+        .await
+        .unwrap();
+    app.state.signing_keys.rotate_if_due(db).await.unwrap();
+
+    let stored = keys(db).await.remove(0);
+    assert!(stored.private_key.starts_with("v1:"));
+    assert_ne!(stored.private_key, first.private_key);
+    let token = encode_jwt(&app.state.signing_keys, "a@example.com".to_string(), 1).unwrap();
+    assert!(decode_jwt(&app.state.signing_keys, token).is_ok());
+}
+
+#[actix_web::test]
+async fn legacy_tokens_stop_verifying_once_the_secret_expires() {
+    let app = spawn_app().await;
+    let now = Utc::now();
+    let legacy_token = encode(
+        &Header::default(),
+        &json!({
+            "exp": (now + Duration::hours(24)).timestamp(),
+            "iat": now.timestamp(),
+            "email": "a@example.com",
+            "user_id": 1,
+        }),
+        &EncodingKey::from_secret(b"old-secret"),
+    )
+    .unwrap();
+
+    let mut config = test_config();
+    config.legacy_secret = Some("old-secret".parse().unwrap());
+    config.legacy_secret_expires_at = Some(now + Duration::hours(1));
+    let store = KeyStore::load(&app.state.db, &config).await.unwrap();
+    assert_eq!(
+        decode_jwt(&store, legacy_token.clone())
+            .unwrap()
+            .claims
+            .user_id,
+        1
+    );
+
+    config.legacy_secret_expires_at = Some(now - Duration::seconds(1));
+    let store = KeyStore::load(&app.state.db, &config).await.unwrap();
+    assert!(decode_jwt(&store, legacy_token.clone()).is_err());
+
+    // Without the secret, kid-less tokens are never accepted
+    assert!(decode_jwt(&app.state.signing_keys, legacy_token).is_err());
+}
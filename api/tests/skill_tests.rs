@@ -0,0 +1,141 @@
+mod support;
+
+use serde_json::json;
+use support::{field, spawn_app};
+
+#[actix_web::test]
+async fn the_catalogue_names_skills_and_works_out_their_dd() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+
+    let res = app.get("/skill/catalogue?event=TRA", &athlete).await;
+    assert_eq!(res.status, 200);
+    assert!(res
+        .body
+        .starts_with("[ { 'catalogue_skill_id': 2, 'event_id': TRA, 'fig_rep': 40, 'position': PIKE, 'name': Somersault, 'difficulty': 0.6 }"));
+    assert!(res
+        .body
+        .contains("'fig_rep': 41, 'position': STRAIGHT, 'name': Barani, 'difficulty': 0.6"));
+    assert!(res
+        .body
+        .contains("'fig_rep': 822, 'position': PIKE, 'name': Full-in-full-out, 'difficulty': 1.6"));
+    assert!(!res.body.contains("'event_id': DMT"));
+
+    // Tumbling skills have to be added by an admin
+    let res = app.get("/skill/catalogue?event=TUM", &athlete).await;
+    assert_eq!(res.body, "[  ]");
+
+    let res = app.get("/skill/catalogue?event=SWIM", &athlete).await;
+    assert_eq!(res.status, 422);
+}
+
+#[actix_web::test]
+async fn only_admins_can_change_the_catalogue() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, admin) = app.admin("admin@example.com").await;
+    let back_tuck =
+        json!({ "event": "TUM", "fig_rep": 40, "position": "TUCK", "name": "Back tuck" });
+
+    let res = app
+        .post("/skill/catalogue", &coach, back_tuck.clone())
+        .await;
+    assert_eq!(res.status, 403);
+
+    let res = app
+        .post("/skill/catalogue", &admin, back_tuck.clone())
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "name"), "Back tuck");
+    // Tumbling has no tariff
+    assert_eq!(field(&res.body, "difficulty"), "null");
+    let catalogue_skill_id = field(&res.body, "catalogue_skill_id");
+
+    let res = app.post("/skill/catalogue", &admin, back_tuck).await;
+    assert_eq!(res.status, 409);
+
+    for invalid in [
+        json!({ "event": "TUM", "fig_rep": 4, "position": "TUCK", "name": "Quarter" }),
+        json!({ "event": "TUM", "fig_rep": 41, "position": "LAYOUT", "name": "Barani" }),
+        json!({ "event": "TUM", "fig_rep": 41, "position": "TUCK", "name": " " }),
+    ] {
+        let res = app.post("/skill/catalogue", &admin, invalid).await;
+        assert_eq!(res.status, 422);
+    }
+
+    let res = app
+        .delete(&format!("/skill/catalogue/{}", catalogue_skill_id), &coach)
+        .await;
+    assert_eq!(res.status, 403);
+    let res = app
+        .delete(&format!("/skill/catalogue/{}", catalogue_skill_id), &admin)
+        .await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .delete(&format!("/skill/catalogue/{}", catalogue_skill_id), &admin)
+        .await;
+    assert_eq!(res.status, 404);
+}
+
+#[actix_web::test]
+async fn the_repertoire_counts_attempts_and_consistency() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "skills": [
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.3 },
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.1 },
+                    { "fig_rep": 44, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.0 },
+                    { "fig_rep": 800, "direction": "BACKWARD", "position": "PIKE", "deduction": 0.2 },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    // Unjudged tucked backs are attempts, but say nothing about consistency
+    app.log_turn(&athlete, session_id, 2).await;
+
+    let res = app
+        .get(&format!("/skill/repertoire/{}", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.status, 200);
+    assert!(res.body.starts_with(
+        "[ { 'event_id': TRA, 'fig_rep': 40, 'position': TUCK, 'name': Somersault, 'difficulty': 0.5, 'first_landed_at': "
+    ));
+    assert!(res
+        .body
+        .contains("'attempts': 4, 'judged': 2, 'consistent': 1, 'consistency_rate': 50 }"));
+    // Skills that are not in the catalogue have no name
+    assert!(res
+        .body
+        .contains("'fig_rep': 44, 'position': TUCK, 'name': null, 'difficulty': 0.9"));
+    assert!(res.body.contains(
+        "'fig_rep': 800, 'position': PIKE, 'name': Double somersault, 'difficulty': 1.2"
+    ));
+    assert!(res
+        .body
+        .ends_with("'attempts': 1, 'judged': 1, 'consistent': 1, 'consistency_rate': 100 } ]"));
+
+    let res = app
+        .get(
+            &format!("/skill/repertoire/{}?event=DMT", athlete_id),
+            &athlete,
+        )
+        .await;
+    assert_eq!(res.body, "[  ]");
+
+    // Coaches outside the athlete's club cannot see it
+    let res = app
+        .get(&format!("/skill/repertoire/{}", athlete_id), &coach)
+        .await;
+    assert_eq!(res.status, 401);
+}
@@ -0,0 +1,219 @@
+mod support;
+
+use serde_json::json;
+use support::{field, spawn_app};
+
+// A barani, a rudi and a double back pike, 2.6 DD
+fn routine(session_id: i32) -> serde_json::Value {
+    json!({
+        "session_id": session_id,
+        "note": "",
+        "skills": [
+            { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT" },
+            { "fig_rep": 43, "direction": "FORWARD", "position": "STRAIGHT" },
+            { "fig_rep": 800, "direction": "BACKWARD", "position": "PIKE" },
+        ],
+    })
+}
+
+// Reads one of the bests, e.g. `{ 'turn_id': 1, 'value': 2.6, ... }`, out of a response body
+fn best(body: &str, kind: &str) -> String {
+    let marker = format!("'{}': ", kind);
+    let start = body
+        .find(&marker)
+        .unwrap_or_else(|| panic!("no {} in {}", kind, body))
+        + marker.len();
+    let end = start + body[start..].find('}').unwrap() + 1;
+
+    body[start..end].to_string()
+}
+
+#[actix_web::test]
+async fn bests_update_as_turns_are_logged() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app
+        .post("/turn/create", &athlete, routine(session_id))
+        .await;
+    let routine_id = field(&res.body, "turn_id");
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "event_id"), "TRA");
+    assert_eq!(
+        field(&best(&res.body, "highest_difficulty"), "turn_id"),
+        routine_id
+    );
+    assert_eq!(
+        field(&best(&res.body, "highest_difficulty"), "value"),
+        "2.6"
+    );
+
+    // Ten tucked backs have more DD and more skills
+    let turn_id = app.log_turn(&athlete, session_id, 10).await.to_string();
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(
+        field(&best(&res.body, "highest_difficulty"), "turn_id"),
+        turn_id
+    );
+    assert_eq!(field(&best(&res.body, "highest_difficulty"), "value"), "5");
+    // Neither turn has been judged, so neither has a score to rank
+    assert_eq!(field(&res.body, "highest_score"), "null");
+    assert_eq!(
+        field(&best(&res.body, "longest_routine"), "turn_id"),
+        turn_id
+    );
+    assert_eq!(field(&best(&res.body, "longest_routine"), "value"), "10");
+
+    // Each distinct skill is credited to the turn it was first landed in
+    assert!(res.body.contains(&format!(
+        "{{ 'fig_rep': 41, 'position': STRAIGHT, 'turn_id': {},",
+        routine_id
+    )));
+    assert!(res.body.contains(&format!(
+        "{{ 'fig_rep': 40, 'position': TUCK, 'turn_id': {},",
+        turn_id
+    )));
+    assert_eq!(res.body.matches("'fig_rep': 40").count(), 1);
+}
+
+#[actix_web::test]
+async fn deleting_a_best_turn_falls_back_to_the_next_best() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let res = app
+        .post("/turn/create", &athlete, routine(session_id))
+        .await;
+    let routine_id = field(&res.body, "turn_id");
+    let turn_id = app.log_turn(&athlete, session_id, 10).await;
+
+    let res = app.delete(&format!("/turn/{}", turn_id), &athlete).await;
+    assert_eq!(res.status, 200);
+
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(
+        field(&best(&res.body, "highest_difficulty"), "turn_id"),
+        routine_id
+    );
+    assert_eq!(
+        field(&best(&res.body, "longest_routine"), "turn_id"),
+        routine_id
+    );
+    assert!(!res.body.contains("'fig_rep': 40"));
+
+    // With no turns left there are no bests
+    let res = app.delete(&format!("/turn/{}", routine_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(res.body, "[  ]");
+}
+
+#[actix_web::test]
+async fn judging_a_turn_updates_the_highest_score() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let first_id = app.log_turn(&athlete, session_id, 3).await;
+    let second_id = app.log_turn(&athlete, session_id, 3).await;
+
+    // Only judged turns are ranked
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(field(&res.body, "highest_score"), "null");
+
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", second_id),
+            &athlete,
+            json!({ "deductions": [0.5, 0.5, 0.5], "landing_deduction": 1.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+
+    // D 1.5 + E 1.0
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(
+        field(&best(&res.body, "highest_score"), "turn_id"),
+        second_id.to_string()
+    );
+    assert_eq!(field(&best(&res.body, "highest_score"), "value"), "2.5");
+
+    // A tie goes to the turn that was logged first
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", first_id),
+            &athlete,
+            json!({ "deductions": [0.5, 0.5, 0.5], "landing_deduction": 1.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert_eq!(
+        field(&best(&res.body, "highest_score"), "turn_id"),
+        first_id.to_string()
+    );
+}
+
+#[actix_web::test]
+async fn synchronized_turns_count_for_both_partners() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let mut turn = routine(session_id);
+    turn["sync_pair_id"] = json!(sync_pair_id);
+    let res = app.post("/turn/create", &athlete, turn).await;
+    assert_eq!(res.status, 201);
+    let turn_id = field(&res.body, "turn_id");
+
+    let res = app
+        .get(&format!("/user/{}/bests", partner_id), &partner)
+        .await;
+    assert_eq!(
+        field(&best(&res.body, "highest_difficulty"), "turn_id"),
+        turn_id
+    );
+    assert!(res.body.contains("'fig_rep': 800, 'position': PIKE"));
+}
+
+#[actix_web::test]
+async fn bests_are_kept_per_event_and_private() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (_, other) = app.athlete("other@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    app.log_turn(&athlete, session_id, 10).await;
+    app.post(&format!("/session/{}/end", session_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "DMT").await;
+    app.log_turn(&athlete, session_id, 2).await;
+
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &athlete)
+        .await;
+    assert!(res.body.starts_with("[ { 'event_id': DMT"));
+    assert_eq!(field(&best(&res.body, "longest_routine"), "value"), "2");
+    assert!(res.body.contains("'event_id': TRA"));
+
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &other)
+        .await;
+    assert_eq!(res.status, 401);
+}
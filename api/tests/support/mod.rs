@@ -0,0 +1,410 @@
+// Not every test file uses every helper
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use actix_http::Request;
+use actix_web::{
+    body::{to_bytes, BoxBody},
+    dev::{Service, ServiceResponse},
+    http::{
+        header::{HeaderMap, AUTHORIZATION},
+        Method,
+    },
+    test::{init_service, TestRequest},
+    web, App, Error,
+};
+use api::{
+    entities, routes,
+    utils::{
+        app_state::AppState,
+        config::{Config, LogFormat, LoginConfig, RateLimitBackend, SigningKeyConfig},
+        jobs::JobRunner,
+        metrics::Metrics,
+        migrations::{self, MigrationMode},
+        rate_limiter::{DatabaseStore, MemoryStore, RateLimitPolicy, RateLimitStore, RateLimiter},
+        signing_keys::KeyStore,
+    },
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectOptions, ConnectionTrait, Database, DatabaseConnection,
+    EntityTrait, QueryFilter,
+};
+use serde_json::{json, Value};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+pub struct TestResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+// The full set of routes, backed by a fresh in-memory database
+pub struct TestApp<S> {
+    pub service: S,
+    pub state: web::Data<AppState>,
+}
+
+// No rate limit policies, so tests are never throttled
+pub async fn spawn_app(
+) -> TestApp<impl Service<Request, Response = ServiceResponse<BoxBody>, Error = Error>> {
+    spawn_rate_limited_app(RateLimitBackend::Memory, HashMap::new()).await
+}
+
+pub async fn spawn_rate_limited_app(
+    backend: RateLimitBackend,
+    policies: HashMap<String, RateLimitPolicy>,
+) -> TestApp<impl Service<Request, Response = ServiceResponse<BoxBody>, Error = Error>> {
+    let db = connect().await;
+    migrations::run(&db, MigrationMode::Apply).await.unwrap();
+
+    let config = test_config();
+    let signing_keys = KeyStore::load(&db, &config).await.unwrap();
+
+    let store: Box<dyn RateLimitStore> = match backend {
+        RateLimitBackend::Database => Box::new(DatabaseStore { db: db.clone() }),
+        RateLimitBackend::Memory => Box::new(MemoryStore::default()),
+    };
+    let state = web::Data::new(AppState {
+        rate_limiter: RateLimiter { store, policies },
+        config,
+        db,
+        jobs: JobRunner::default(),
+        metrics: Metrics::new().unwrap(),
+        signing_keys,
+    });
+
+    let service = init_service(App::new().app_data(state.clone()).configure(routes::config)).await;
+
+    TestApp { service, state }
+}
+
+// Tests run on Postgres when DATABASE_URL points at one and the postgres feature is enabled,
+// otherwise on SQLite. Either way each test gets an empty database of its own
+async fn connect() -> DatabaseConnection {
+    let url = std::env::var("DATABASE_URL").unwrap_or_default();
+
+    if cfg!(feature = "postgres") && url.starts_with("postgres") {
+        // A schema per test, so tests can run in parallel against one database
+        let schema = format!("test_{:016x}", rand::random::<u64>());
+        let setup = Database::connect(&url).await.unwrap();
+        setup
+            .execute_unprepared(&format!("CREATE SCHEMA {}", schema))
+            .await
+            .unwrap();
+        setup.close().await.unwrap();
+
+        let mut options = ConnectOptions::new(url);
+        options
+            .max_connections(2)
+            .set_schema_search_path(schema)
+            .sqlx_logging(false);
+        return Database::connect(options).await.unwrap();
+    }
+
+    if !cfg!(feature = "sqlite") {
+        panic!("Without the sqlite feature, DATABASE_URL must point at a Postgres database");
+    }
+
+    // Every connection to sqlite::memory: opens its own empty database, so the pool is held
+    // to a single connection that lives as long as the test
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options
+        .max_connections(1)
+        .min_connections(1)
+        .sqlx_logging(false);
+    Database::connect(options).await.unwrap()
+}
+
+// 32 bytes of base64, like the output of `openssl rand -base64 32`
+const TEST_ENCRYPTION_KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+pub fn test_config() -> Config {
+    Config {
+        address: "127.0.0.1".to_string(),
+        port: 0,
+        database_url: "sqlite::memory:".to_string(),
+        run_migrations: true,
+        shutdown_timeout_seconds: 0,
+        log_format: LogFormat::Text,
+        session_idle_minutes: 30,
+        legacy_secret: None,
+        legacy_secret_expires_at: None,
+        encryption_key: TEST_ENCRYPTION_KEY.parse().unwrap(),
+        rate_limit_backend: RateLimitBackend::Memory,
+        rate_limit_policies: HashMap::new(),
+        login: LoginConfig {
+            attempt_window_hours: 24,
+            account_lockout_threshold: 5,
+            ip_lockout_threshold: 20,
+            lockout_base_seconds: 60,
+            lockout_max_seconds: 60 * 60,
+        },
+        signing_keys: SigningKeyConfig {
+            rotation_days: 30,
+            prepublish_hours: 24,
+            verify_grace_hours: 48,
+            rotation_check_minutes: 60,
+        },
+    }
+}
+
+impl<S> TestApp<S>
+where
+    S: Service<Request, Response = ServiceResponse<BoxBody>, Error = Error>,
+{
+    // Errors raised by middleware are rendered the same way the server would
+    pub async fn send(&self, req: TestRequest) -> TestResponse {
+        let res = match self.service.call(req.to_request()).await {
+            Ok(res) => res.into_parts().1,
+            Err(err) => err.error_response(),
+        };
+        let status = res.status().as_u16();
+        let headers = res.headers().clone();
+        let body = to_bytes(res.into_body()).await.unwrap();
+
+        TestResponse {
+            status,
+            headers,
+            body: String::from_utf8(body.to_vec()).unwrap(),
+        }
+    }
+
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> TestResponse {
+        let mut req = TestRequest::default().method(method).uri(uri);
+        if let Some(token) = token {
+            req = req.insert_header((AUTHORIZATION, format!("Bearer {}", token)));
+        }
+        if let Some(body) = body {
+            req = req.set_json(body);
+        }
+        self.send(req).await
+    }
+
+    pub async fn get(&self, uri: &str, token: &str) -> TestResponse {
+        self.request(Method::GET, uri, Some(token), None).await
+    }
+
+    pub async fn post(&self, uri: &str, token: &str, body: Value) -> TestResponse {
+        self.request(Method::POST, uri, Some(token), Some(body))
+            .await
+    }
+
+    pub async fn put(&self, uri: &str, token: &str, body: Value) -> TestResponse {
+        self.request(Method::PUT, uri, Some(token), Some(body))
+            .await
+    }
+
+    pub async fn delete(&self, uri: &str, token: &str) -> TestResponse {
+        self.request(Method::DELETE, uri, Some(token), None).await
+    }
+
+    // Registers a user with the password "password" and returns their id
+    pub async fn register(&self, user_type: &str, email: &str) -> i32 {
+        let res = self
+            .request(
+                Method::POST,
+                "/auth/register",
+                None,
+                Some(json!({
+                    "user_type": user_type,
+                    "name_first": "Test",
+                    "name_last": "User",
+                    "email": email,
+                    "password": "password",
+                })),
+            )
+            .await;
+        assert_eq!(res.status, 200, "register failed: {}", res.body);
+
+        res.body.parse().unwrap()
+    }
+
+    // Logs in with any password, leaving the outcome to the test
+    pub async fn try_login(&self, user_type: &str, email: &str, password: &str) -> TestResponse {
+        self.request(
+            Method::POST,
+            "/auth/login",
+            None,
+            Some(json!({
+                "user_type": user_type,
+                "email": email,
+                "password": password,
+            })),
+        )
+        .await
+    }
+
+    pub async fn login(&self, user_type: &str, email: &str) -> String {
+        let res = self.try_login(user_type, email, "password").await;
+        assert_eq!(res.status, 200, "login failed: {}", res.body);
+
+        field(&res.body, "token")
+    }
+
+    // Registers and logs in, returning the user's id and login token
+    pub async fn user(&self, user_type: &str, email: &str) -> (i32, String) {
+        let user_id = self.register(user_type, email).await;
+        (user_id, self.login(user_type, email).await)
+    }
+
+    // Enrolls the user in two-factor, returning the secret and the recovery codes
+    pub async fn enable_two_factor(&self, token: &str, email: &str) -> (String, Vec<String>) {
+        let res = self.post("/two-factor/enroll", token, json!({})).await;
+        assert_eq!(res.status, 200, "enroll failed: {}", res.body);
+        let secret = field(&res.body, "secret");
+
+        let res = self
+            .post(
+                "/two-factor/enroll/confirm",
+                token,
+                json!({ "code": totp_code(&secret, email, 0) }),
+            )
+            .await;
+        assert_eq!(res.status, 200, "confirm enrollment failed: {}", res.body);
+        let recovery_codes = res
+            .body
+            .split_once("[ ")
+            .and_then(|(_, rest)| rest.split_once(" ]"))
+            .unwrap()
+            .0
+            .split(", ")
+            .map(str::to_string)
+            .collect();
+
+        (secret, recovery_codes)
+    }
+
+    // Logs in an athlete with two-factor enabled, returning the challenge token
+    pub async fn challenge(&self, email: &str) -> String {
+        let res = self.try_login("A", email, "password").await;
+        assert_eq!(res.status, 200, "login failed: {}", res.body);
+        assert_eq!(field(&res.body, "two_factor_required"), "true");
+
+        field(&res.body, "challenge_token")
+    }
+
+    pub async fn coach(&self, email: &str) -> (i32, String) {
+        self.user("C", email).await
+    }
+
+    pub async fn athlete(&self, email: &str) -> (i32, String) {
+        self.user("A", email).await
+    }
+
+    // Admins are only ever made by hand in the database
+    pub async fn admin(&self, email: &str) -> (i32, String) {
+        let (user_id, token) = self.coach(email).await;
+        entities::user::Entity::update_many()
+            .col_expr(entities::user::Column::IsAdmin, Expr::value(true))
+            .filter(entities::user::Column::UserId.eq(user_id))
+            .exec(&self.state.db)
+            .await
+            .unwrap();
+
+        (user_id, token)
+    }
+
+    // Creates a club owned by the coach and returns its id
+    pub async fn create_club(&self, token: &str, name: &str) -> i32 {
+        let res = self
+            .post("/club/create", token, json!({ "name": name }))
+            .await;
+        assert_eq!(res.status, 200, "create club failed: {}", res.body);
+
+        field(&res.body, "club_id").parse().unwrap()
+    }
+
+    // Starts a session for the athlete and returns its id
+    pub async fn start_session(&self, token: &str, event: &str) -> i32 {
+        let res = self
+            .post(
+                "/session/start",
+                token,
+                json!({ "event": event, "summary": "Training" }),
+            )
+            .await;
+        assert_eq!(res.status, 201, "start session failed: {}", res.body);
+
+        field(&res.body, "session_id").parse().unwrap()
+    }
+
+    // Requests a sync pair with the partner and has them accept it, returning the pair's id
+    pub async fn sync_pair(&self, token: &str, partner_id: i32, partner: &str) -> i32 {
+        let res = self
+            .post(
+                "/sync-pair/create",
+                token,
+                json!({ "partner_id": partner_id }),
+            )
+            .await;
+        assert_eq!(res.status, 201, "create sync pair failed: {}", res.body);
+        let sync_pair_id = field(&res.body, "sync_pair_id").parse().unwrap();
+
+        let res = self
+            .post(
+                &format!("/sync-pair/{}/accept", sync_pair_id),
+                partner,
+                json!({}),
+            )
+            .await;
+        assert_eq!(res.status, 200, "accept sync pair failed: {}", res.body);
+
+        sync_pair_id
+    }
+
+    // Logs a turn of untwisted tucked back somersaults and returns its id
+    pub async fn log_turn(&self, token: &str, session_id: i32, skills: usize) -> i32 {
+        let skills = (0..skills)
+            .map(|_| json!({ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" }))
+            .collect::<Vec<Value>>();
+        let res = self
+            .post(
+                "/turn/create",
+                token,
+                json!({ "session_id": session_id, "note": "", "skills": skills }),
+            )
+            .await;
+        assert_eq!(res.status, 201, "log turn failed: {}", res.body);
+
+        field(&res.body, "turn_id").parse().unwrap()
+    }
+}
+
+// Reads a value out of the server's `{ 'key': value, ... }` response bodies
+pub fn field(body: &str, key: &str) -> String {
+    let marker = format!("'{}': ", key);
+    let start = body
+        .find(&marker)
+        .unwrap_or_else(|| panic!("no {} in {}", key, body))
+        + marker.len();
+
+    body[start..]
+        .split([',', '}'])
+        .next()
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+// The authenticator code for `seconds` from now, as an app holding the secret would show it
+pub fn totp_code(secret: &str, email: &str, seconds: i64) -> String {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        30,
+        Secret::Encoded(secret.to_string()).to_bytes().unwrap(),
+        Some("Bounce".to_string()),
+        email.to_string(),
+    )
+    .unwrap();
+
+    totp.generate((chrono::Utc::now().timestamp() + seconds) as u64)
+}
@@ -0,0 +1,349 @@
+mod support;
+
+use serde_json::json;
+use support::{field, spawn_app};
+
+fn sync_turn(session_id: i32, sync_pair_id: i32, sync_score: f32) -> serde_json::Value {
+    json!({
+        "session_id": session_id,
+        "note": "Together",
+        "sync_pair_id": sync_pair_id,
+        "sync_score": sync_score,
+        "skills": [
+            { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT" },
+            { "fig_rep": 40, "direction": "BACKWARD", "position": "PIKE" },
+        ],
+    })
+}
+
+#[actix_web::test]
+async fn a_sync_pair_counts_once_the_partner_accepts() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &partner,
+            json!({ "partner_id": athlete_id }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    // The lower id is always athlete one, whoever asked
+    assert_eq!(field(&res.body, "athlete_one_id"), athlete_id.to_string());
+    assert_eq!(field(&res.body, "requested_by"), partner_id.to_string());
+    assert_eq!(field(&res.body, "accepted_at"), "null");
+    let sync_pair_id = field(&res.body, "sync_pair_id");
+
+    let res = app
+        .post(
+            &format!("/sync-pair/{}/accept", sync_pair_id),
+            &partner,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+
+    let res = app
+        .post(
+            &format!("/sync-pair/{}/accept", sync_pair_id),
+            &athlete,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_ne!(field(&res.body, "accepted_at"), "null");
+
+    let res = app
+        .post(
+            &format!("/sync-pair/{}/accept", sync_pair_id),
+            &athlete,
+            json!({}),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+
+    // The same two athletes the other way round are still the same pair
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &athlete,
+            json!({ "partner_id": partner_id }),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "These athletes are already a sync pair");
+
+    let res = app
+        .get(&format!("/sync-pair/athlete/{}", partner_id), &partner)
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "sync_pair_id"), sync_pair_id);
+}
+
+#[actix_web::test]
+async fn only_athletes_can_form_sync_pairs() {
+    let app = spawn_app().await;
+    let (coach_id, coach) = app.coach("coach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &coach,
+            json!({ "partner_id": athlete_id }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &athlete,
+            json!({ "partner_id": coach_id }),
+        )
+        .await;
+    assert_eq!(res.status, 422);
+
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &athlete,
+            json!({ "partner_id": athlete_id }),
+        )
+        .await;
+    assert_eq!(res.status, 422);
+}
+
+#[actix_web::test]
+async fn a_synchronized_turn_is_in_both_athletes_logs() {
+    let app = spawn_app().await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, sync_pair_id, 3.85),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "sync_pair_id"), sync_pair_id.to_string());
+    assert_eq!(field(&res.body, "sync_score"), "3.85");
+    // D 1.2 + S 3.85 until it is judged, with one shared DD for the pair
+    assert_eq!(field(&res.body, "total_difficulty"), "1.2");
+    assert_eq!(field(&res.body, "total_score"), "5.05");
+    let turn_id = field(&res.body, "turn_id");
+
+    for (user_id, token) in [(athlete_id, &athlete), (partner_id, &partner)] {
+        let res = app.get(&format!("/turn/athlete/{}", user_id), token).await;
+        assert_eq!(res.status, 200);
+        assert_eq!(field(&res.body, "turn_id"), turn_id);
+    }
+
+    // The partner can open the turn, but it stays in the session of the athlete who logged it
+    let res = app.get(&format!("/turn/{}", turn_id), &partner).await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "user_id"), athlete_id.to_string());
+    let res = app
+        .get(&format!("/turn/session/{}", session_id), &partner)
+        .await;
+    assert_eq!(res.status, 401);
+
+    // Judging the turn keeps its synchronization score
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &partner,
+            json!({ "deductions": [0.1, 0.1], "landing_deduction": 0.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "total_score"), "8.65");
+}
+
+#[actix_web::test]
+async fn both_partners_coaches_can_see_a_synchronized_turn() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, partner_coach) = app.coach("partnercoach@example.com").await;
+    let (athlete_id, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+
+    // The partners train at different clubs
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+    let partner_club_id = app.create_club(&partner_coach, "Flyers").await;
+    app.post(
+        &format!("/club/{}/join", partner_club_id),
+        &partner,
+        json!({}),
+    )
+    .await;
+
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, sync_pair_id, 3.5),
+        )
+        .await;
+    let turn_id = field(&res.body, "turn_id");
+    let solo_turn_id = app.log_turn(&athlete, session_id, 1).await;
+
+    for token in [&coach, &partner_coach] {
+        let res = app.get(&format!("/turn/{}", turn_id), token).await;
+        assert_eq!(res.status, 200);
+    }
+
+    let res = app
+        .get(&format!("/turn/athlete/{}", partner_id), &partner_coach)
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "turn_id"), turn_id);
+
+    // The partner's coach only sees the turns the pair did together
+    let res = app
+        .get(&format!("/turn/{}", solo_turn_id), &partner_coach)
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .get(&format!("/turn/athlete/{}", athlete_id), &partner_coach)
+        .await;
+    assert_eq!(res.status, 401);
+}
+
+#[actix_web::test]
+async fn synchronized_turns_need_an_accepted_trampoline_pair() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+    let (other_id, other) = app.athlete("other@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &athlete,
+            json!({ "partner_id": other_id }),
+        )
+        .await;
+    let pending_id = field(&res.body, "sync_pair_id").parse().unwrap();
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, pending_id, 3.5),
+        )
+        .await;
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "Sync pair has not been accepted yet");
+
+    // Someone else's pair
+    let other_pair_id = app.sync_pair(&partner, other_id, &other).await;
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, other_pair_id, 3.5),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+
+    // Two skills can score at most 4.0 for synchronization
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, sync_pair_id, 4.5),
+        )
+        .await;
+    assert_eq!(res.status, 422);
+    assert_eq!(res.body, "Synchronization score must be from 0.0 to 4");
+
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "sync_score": 1.0,
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" }],
+            }),
+        )
+        .await;
+    assert_eq!(res.status, 422);
+    assert_eq!(res.body, "A synchronization score needs a sync pair");
+
+    app.post(&format!("/session/{}/end", session_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "DMT").await;
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, sync_pair_id, 3.5),
+        )
+        .await;
+    assert_eq!(res.status, 422);
+}
+
+#[actix_web::test]
+async fn pairs_with_turns_cannot_be_deleted() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let (partner_id, partner) = app.athlete("partner@example.com").await;
+    let (other_id, other) = app.athlete("other@example.com").await;
+
+    // A request can be turned down by the athlete who was asked
+    let res = app
+        .post(
+            "/sync-pair/create",
+            &athlete,
+            json!({ "partner_id": other_id }),
+        )
+        .await;
+    let pending_id = field(&res.body, "sync_pair_id");
+    let res = app
+        .delete(&format!("/sync-pair/{}", pending_id), &partner)
+        .await;
+    assert_eq!(res.status, 401);
+    let res = app
+        .delete(&format!("/sync-pair/{}", pending_id), &other)
+        .await;
+    assert_eq!(res.status, 200);
+
+    let sync_pair_id = app.sync_pair(&athlete, partner_id, &partner).await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let res = app
+        .post(
+            "/turn/create",
+            &athlete,
+            sync_turn(session_id, sync_pair_id, 3.5),
+        )
+        .await;
+    let turn_id = field(&res.body, "turn_id");
+
+    let res = app
+        .delete(&format!("/sync-pair/{}", sync_pair_id), &partner)
+        .await;
+    assert_eq!(res.status, 409);
+
+    // Once the turn is gone the pair can be split
+    let res = app.delete(&format!("/turn/{}", turn_id), &athlete).await;
+    assert_eq!(res.status, 200);
+    let res = app
+        .delete(&format!("/sync-pair/{}", sync_pair_id), &partner)
+        .await;
+    assert_eq!(res.status, 200);
+}
@@ -0,0 +1,506 @@
+mod support;
+
+use api::routes::services::session_service;
+use serde_json::json;
+use support::{field, spawn_app};
+
+// A barani, a rudi and a double back pike
+fn routine() -> serde_json::Value {
+    json!([
+        { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT", "deduction": 0.1 },
+        { "fig_rep": 43, "direction": "FORWARD", "position": "STRAIGHT", "deduction": 0.2 },
+        { "fig_rep": 800, "direction": "BACKWARD", "position": "PIKE", "deduction": 0.3 },
+    ])
+}
+
+#[actix_web::test]
+async fn athlete_logs_a_scored_turn() {
+    let app = spawn_app().await;
+    let (athlete_id, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &token,
+            json!({
+                "session_id": session_id,
+                "note": "Good height",
+                "landing_deduction": 0.2,
+                "skills": routine(),
+            }),
+        )
+        .await;
+
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "user_id"), athlete_id.to_string());
+    assert_eq!(field(&res.body, "event_id"), "TRA");
+    // 0.6 + 0.8 + 1.2
+    assert_eq!(field(&res.body, "total_difficulty"), "2.6");
+    // (3.0 - 0.6 - 0.2) * 2
+    assert_eq!(field(&res.body, "execution_score"), "4.4");
+    assert_eq!(field(&res.body, "total_score"), "7");
+    assert_eq!(field(&res.body, "skill_num"), "1");
+    assert_eq!(field(&res.body, "difficulty"), "0.6");
+}
+
+#[actix_web::test]
+async fn unjudged_turns_leave_execution_unscored() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &token,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "skills": [
+                    { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT" },
+                    { "fig_rep": 43, "direction": "FORWARD", "position": "STRAIGHT" },
+                ],
+            }),
+        )
+        .await;
+
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "execution_score"), "null");
+    assert_eq!(field(&res.body, "landing_deduction"), "null");
+    assert_eq!(field(&res.body, "deduction"), "null");
+    // The difficulty alone until a judge scores it
+    assert_eq!(field(&res.body, "total_score"), "1.4");
+
+    let turn_id = field(&res.body, "turn_id");
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &token,
+            json!({ "deductions": [0.1, 0.2], "landing_deduction": 0.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "execution_score"), "3.4");
+    assert_eq!(field(&res.body, "total_score"), "4.8");
+
+    // A turn is judged as a whole, not skill by skill
+    let cases = [
+        (
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "skills": [
+                    { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT", "deduction": 0.1 },
+                    { "fig_rep": 43, "direction": "FORWARD", "position": "STRAIGHT" },
+                ],
+            }),
+            "Every skill needs a deduction when any skill has one",
+        ),
+        (
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "landing_deduction": 0.2,
+                "skills": [{ "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT" }],
+            }),
+            "A landing deduction needs a deduction for every skill",
+        ),
+    ];
+    for (turn, message) in cases {
+        let res = app.post("/turn/create", &token, turn).await;
+        assert_eq!(res.status, 422);
+        assert_eq!(res.body, message);
+    }
+}
+
+// The tariff only covers trampoline, double mini and tumbling are scored on execution alone
+#[actix_web::test]
+async fn difficulty_is_unscored_outside_trampoline() {
+    let app = spawn_app().await;
+    let (athlete_id, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "DMT").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &token,
+            json!({ "session_id": session_id, "note": "", "skills": routine() }),
+        )
+        .await;
+
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "total_difficulty"), "null");
+    assert_eq!(field(&res.body, "difficulty"), "null");
+    // (3.0 - 0.6) * 2
+    assert_eq!(field(&res.body, "total_score"), "4.8");
+
+    let res = app
+        .get(&format!("/user/{}/bests", athlete_id), &token)
+        .await;
+    assert_eq!(field(&res.body, "highest_difficulty"), "null");
+}
+
+#[actix_web::test]
+async fn logging_a_turn_keeps_the_session_active() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    let before = session_service::get_session_by_id(&app.state.db, session_id)
+        .await
+        .unwrap()
+        .last_activity_at;
+
+    app.log_turn(&token, session_id, 10).await;
+
+    let after = session_service::get_session_by_id(&app.state.db, session_id)
+        .await
+        .unwrap()
+        .last_activity_at;
+    assert!(after > before);
+
+    let metrics = app.state.metrics.render(&app.state.db).unwrap();
+    assert!(metrics.contains("turns_logged_total{event=\"TRA\"} 1"));
+}
+
+#[actix_web::test]
+async fn turns_cannot_be_logged_in_an_ended_session() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    app.post(&format!("/session/{}/end", session_id), &token, json!({}))
+        .await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &token,
+            json!({ "session_id": session_id, "note": "", "skills": routine() }),
+        )
+        .await;
+
+    assert_eq!(res.status, 409);
+    assert_eq!(res.body, "Session has ended");
+}
+
+#[actix_web::test]
+async fn turns_cannot_be_logged_in_another_athletes_session() {
+    let app = spawn_app().await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let (_, other) = app.athlete("other@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &other,
+            json!({ "session_id": session_id, "note": "", "skills": routine() }),
+        )
+        .await;
+
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Only the athlete can log turns in their session");
+}
+
+#[actix_web::test]
+async fn invalid_skills_are_rejected() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let cases = [
+        (
+            json!([{ "fig_rep": 80, "direction": "BACKWARD", "position": "TUCK" }]),
+            "Skill 1 has an invalid fig_rep, expected FIG notation such as 41",
+        ),
+        (
+            json!([{ "fig_rep": 40, "direction": "SIDEWAYS", "position": "TUCK" }]),
+            "Skill 1 has an invalid direction, must be FORWARD, BACKWARD or NONE",
+        ),
+        (
+            json!([{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "deduction": 0.25 }]),
+            "Skill 1 deduction must be in tenths from 0.0 to 0.5",
+        ),
+        (json!([]), "A turn must have between 1 and 10 skills"),
+    ];
+
+    for (skills, message) in cases {
+        let res = app
+            .post(
+                "/turn/create",
+                &token,
+                json!({ "session_id": session_id, "note": "", "skills": skills }),
+            )
+            .await;
+
+        assert_eq!(res.status, 422);
+        assert_eq!(res.body, message);
+    }
+}
+
+#[actix_web::test]
+async fn coach_in_the_club_judges_a_turn() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let club_id = app.create_club(&coach, "Bouncers").await;
+    app.post(&format!("/club/{}/join", club_id), &athlete, json!({}))
+        .await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let turn_id = app.log_turn(&athlete, session_id, 10).await;
+
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &coach,
+            json!({
+                "deductions": [0.1, 0.2, 0.1, 0.1, 0.2, 0.1, 0.1, 0.1, 0.2, 0.1],
+                "landing_deduction": 0.3,
+            }),
+        )
+        .await;
+
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "total_difficulty"), "5");
+    assert_eq!(field(&res.body, "execution_score"), "16.8");
+    assert_eq!(field(&res.body, "landing_deduction"), "0.3");
+    assert_eq!(field(&res.body, "total_score"), "21.8");
+    assert_eq!(field(&res.body, "deduction"), "0.1");
+}
+
+#[actix_web::test]
+async fn judging_needs_a_deduction_for_every_skill() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    let turn_id = app.log_turn(&token, session_id, 2).await;
+
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &token,
+            json!({ "deductions": [0.1], "landing_deduction": 0.0 }),
+        )
+        .await;
+
+    assert_eq!(res.status, 422);
+    assert_eq!(res.body, "Expected 2 deductions, one per skill");
+}
+
+#[actix_web::test]
+async fn turns_are_hidden_from_coaches_outside_the_club() {
+    let app = spawn_app().await;
+    let (_, coach) = app.coach("coach@example.com").await;
+    let (_, athlete) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&athlete, "TRA").await;
+    let turn_id = app.log_turn(&athlete, session_id, 1).await;
+
+    let res = app.get(&format!("/turn/{}", turn_id), &coach).await;
+    assert_eq!(res.status, 401);
+
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &coach,
+            json!({ "deductions": [0.1], "landing_deduction": 0.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 401);
+    assert_eq!(
+        res.body,
+        "Only the athlete and their coaches can access their training"
+    );
+}
+
+#[actix_web::test]
+async fn session_turns_are_listed_in_order() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    let first = app.log_turn(&token, session_id, 2).await;
+    let second = app.log_turn(&token, session_id, 3).await;
+
+    let res = app
+        .get(&format!("/turn/session/{}", session_id), &token)
+        .await;
+
+    assert_eq!(res.status, 200);
+    let first_at = res.body.find(&format!("'turn_id': {},", first)).unwrap();
+    let second_at = res.body.find(&format!("'turn_id': {},", second)).unwrap();
+    assert!(first_at < second_at);
+    assert_eq!(res.body.matches("'skill_id'").count(), 5);
+}
+
+#[actix_web::test]
+async fn athlete_deletes_a_turn() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let (_, other) = app.athlete("other@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    let turn_id = app.log_turn(&token, session_id, 2).await;
+    let uri = format!("/turn/{}", turn_id);
+
+    let res = app.delete(&uri, &other).await;
+    assert_eq!(res.status, 401);
+    assert_eq!(res.body, "Only the athlete can delete their turn");
+
+    let res = app.delete(&uri, &token).await;
+    assert_eq!(res.status, 200);
+
+    let res = app.get(&uri, &token).await;
+    assert_eq!(res.status, 404);
+    assert_eq!(res.body, "Turn not found");
+}
+
+#[actix_web::test]
+async fn flight_and_displacement_are_scored_per_skill() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &token,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "penalty": 0.2,
+                "skills": [
+                    { "fig_rep": 41, "direction": "FORWARD", "position": "STRAIGHT", "time_of_flight": 1.745, "landing_zone": 0 },
+                    { "fig_rep": 43, "direction": "FORWARD", "position": "STRAIGHT", "time_of_flight": 1.72, "landing_zone": 1 },
+                    { "fig_rep": 800, "direction": "BACKWARD", "position": "PIKE", "time_of_flight": 1.698, "landing_zone": 2 },
+                ],
+            }),
+        )
+        .await;
+
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "time_of_flight"), "5.163");
+    // 3.0 less 0.3 for the zones
+    assert_eq!(field(&res.body, "horizontal_score"), "2.7");
+    assert_eq!(field(&res.body, "penalty"), "0.2");
+    // D 2.6 + T 5.163 + H 2.7 - 0.2, execution is scored once the turn is judged
+    assert_eq!(field(&res.body, "total_score"), "10.263");
+    assert_eq!(field(&res.body, "landing_zone"), "0");
+}
+
+#[actix_web::test]
+async fn flight_and_displacement_can_be_given_for_the_whole_turn() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let res = app
+        .post(
+            "/turn/create",
+            &token,
+            json!({
+                "session_id": session_id,
+                "note": "",
+                "time_of_flight": 5.5,
+                "horizontal_deduction": 0.4,
+                "skills": routine(),
+            }),
+        )
+        .await;
+    assert_eq!(res.status, 201);
+    assert_eq!(field(&res.body, "time_of_flight"), "5.5");
+    assert_eq!(field(&res.body, "horizontal_score"), "2.6");
+    // D 2.6 + E 4.8 + T 5.5 + H 2.6
+    assert_eq!(field(&res.body, "total_score"), "15.5");
+
+    // Judging execution later keeps the flight and displacement in the total
+    let turn_id = field(&res.body, "turn_id");
+    let res = app
+        .put(
+            &format!("/turn/{}/execution", turn_id),
+            &token,
+            json!({ "deductions": [0.0, 0.0, 0.0], "landing_deduction": 0.0 }),
+        )
+        .await;
+    assert_eq!(res.status, 200);
+    assert_eq!(field(&res.body, "total_score"), "16.7");
+}
+
+#[actix_web::test]
+async fn unmeasured_flight_and_displacement_are_null() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+    let turn_id = app.log_turn(&token, session_id, 1).await;
+
+    let res = app.get(&format!("/turn/{}", turn_id), &token).await;
+
+    assert_eq!(field(&res.body, "time_of_flight"), "null");
+    assert_eq!(field(&res.body, "horizontal_score"), "null");
+}
+
+#[actix_web::test]
+async fn flight_and_displacement_inputs_must_be_consistent() {
+    let app = spawn_app().await;
+    let (_, token) = app.athlete("athlete@example.com").await;
+    let session_id = app.start_session(&token, "TRA").await;
+
+    let cases = [
+        (
+            json!({
+                "time_of_flight": 3.0,
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "time_of_flight": 1.5 }],
+            }),
+            "Give time of flight per skill or for the whole turn, not both",
+        ),
+        (
+            json!({
+                "skills": [
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "time_of_flight": 1.5 },
+                    { "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" },
+                ],
+            }),
+            "Every skill needs a time of flight when any skill has one",
+        ),
+        (
+            json!({
+                "horizontal_deduction": 0.1,
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "landing_zone": 1 }],
+            }),
+            "Give landing zones per skill or a horizontal deduction, not both",
+        ),
+        (
+            json!({
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "landing_zone": 4 }],
+            }),
+            "Skill 1 landing zone must be from 0 to 3",
+        ),
+        (
+            json!({
+                "horizontal_deduction": 0.4,
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" }],
+            }),
+            "Horizontal deduction must be in tenths from 0.0 to 0.3",
+        ),
+        // Oversized flight times used to overflow the total
+        (
+            json!({
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK", "time_of_flight": 1e7 }],
+            }),
+            "Skill 1 time of flight must be from 0.0 to 3 seconds",
+        ),
+        (
+            json!({
+                "time_of_flight": 30.001,
+                "skills": [{ "fig_rep": 40, "direction": "BACKWARD", "position": "TUCK" }],
+            }),
+            "The turn time of flight must be from 0.0 to 30 seconds",
+        ),
+    ];
+
+    for (mut body, message) in cases {
+        body["session_id"] = json!(session_id);
+        body["note"] = json!("");
+        let res = app.post("/turn/create", &token, body).await;
+
+        assert_eq!(res.status, 422);
+        assert_eq!(res.body, message);
+    }
+}